@@ -1,20 +1,120 @@
-//! CSV output utilities for exporting algorithm results.
+//! CSV utilities for exporting algorithm results and loading numeric data.
 
 use std::fs::File;
-use std::io::{self, Write};
+use std::io::{self, BufWriter, Write};
 use std::path::Path;
 
 /// Writes a 2D dataset to a CSV file.
 pub fn write_csv<P: AsRef<Path>>(path: P, headers: &[&str], data: &[Vec<f64>]) -> io::Result<()> {
-    let mut file = File::create(path)?;
-    writeln!(file, "{}", headers.join(","))?;
-
+    let mut writer = CsvRowWriter::create(path, headers)?;
     for row in data {
+        writer.write_row(row)?;
+    }
+    writer.flush()
+}
+
+/// Writes a CSV file one row at a time, for results produced incrementally.
+pub struct CsvRowWriter {
+    file: BufWriter<File>,
+}
+
+impl CsvRowWriter {
+    /// Creates (or truncates) `path` and writes the header row.
+    pub fn create<P: AsRef<Path>>(path: P, headers: &[&str]) -> io::Result<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(file, "{}", headers.join(","))?;
+        Ok(Self { file })
+    }
+
+    /// Appends one row of values.
+    pub fn write_row(&mut self, row: &[f64]) -> io::Result<()> {
         let line: String = row.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(",");
-        writeln!(file, "{}", line)?;
+        writeln!(self.file, "{}", line)
+    }
+
+    /// Flushes buffered rows to disk. Also done, ignoring errors, on drop.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// A numeric table read from a CSV file with a header row.
+#[derive(Debug, Clone)]
+pub struct CsvTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<f64>>,
+}
+
+impl CsvTable {
+    /// Index of the column called `name`, ignoring surrounding whitespace.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.headers.iter().position(|h| h.trim() == name.trim())
     }
 
-    Ok(())
+    /// All values in the column called `name`.
+    pub fn column(&self, name: &str) -> Option<Vec<f64>> {
+        let index = self.column_index(name)?;
+        Some(self.rows.iter().map(|row| row[index]).collect())
+    }
+}
+
+/// Reads a CSV file whose header row is followed by rows of numbers.
+///
+/// Empty lines are skipped; any field that does not parse as `f64` is an error.
+pub fn read_csv<P: AsRef<Path>>(path: P) -> Result<CsvTable, Box<dyn std::error::Error>> {
+    let reader = CsvRowReader::open(path)?;
+    let headers = reader.headers().to_vec();
+    let rows = reader.collect::<Result<Vec<_>, _>>()?;
+    Ok(CsvTable { headers, rows })
+}
+
+/// Streams the numeric rows of a CSV file one at a time, for replaying
+/// logged data without loading the whole file.
+///
+/// Parses like [`read_csv`]; each item is one row or the error for that row.
+pub struct CsvRowReader {
+    headers: Vec<String>,
+    records: ::csv::StringRecordsIntoIter<File>,
+    line: usize,
+}
+
+impl CsvRowReader {
+    /// Opens `path` and reads its header row.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut reader = ::csv::ReaderBuilder::new().trim(::csv::Trim::All).from_path(path)?;
+        let headers = reader.headers()?.iter().map(String::from).collect();
+        Ok(Self { headers, records: reader.into_records(), line: 0 })
+    }
+
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    /// Index of the column called `name`, ignoring surrounding whitespace.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.headers.iter().position(|h| h.trim() == name.trim())
+    }
+}
+
+impl Iterator for CsvRowReader {
+    type Item = Result<Vec<f64>, Box<dyn std::error::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.records.next()?;
+        self.line += 1;
+        let line = self.line;
+        let row = record.map_err(Into::into).and_then(|record| {
+            record
+                .iter()
+                .map(|field| {
+                    field
+                        .parse::<f64>()
+                        .map_err(|e| format!("row {}: cannot parse '{}': {}", line, field, e).into())
+                })
+                .collect()
+        });
+        Some(row)
+    }
 }
 
 #[cfg(test)]
@@ -37,4 +137,41 @@ mod tests {
 
         fs::remove_file(path).unwrap();
     }
+
+    #[test]
+    fn test_read_csv_round_trip() {
+        let path = "/tmp/test_read_csv.csv";
+        fs::write(path, "x, y ,sigma\n1,2.5,0.1\n\n3, 4e-1 ,0.2\n").unwrap();
+
+        let table = read_csv(path).unwrap();
+        assert_eq!(table.headers, vec!["x", "y", "sigma"]);
+        assert_eq!(table.column("y").unwrap(), vec![2.5, 0.4]);
+        assert!(table.column("z").is_none());
+
+        fs::write(path, "x\nabc\n").unwrap();
+        assert!(read_csv(path).is_err());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_row_reader_and_writer_stream() {
+        let path = "/tmp/test_csv_stream.csv";
+        let mut writer = CsvRowWriter::create(path, &["t", "value"]).unwrap();
+        for t in 0..3 {
+            writer.write_row(&[t as f64, 0.5 * t as f64]).unwrap();
+        }
+        writer.flush().unwrap();
+
+        let mut reader = CsvRowReader::open(path).unwrap();
+        assert_eq!(reader.column_index("value"), Some(1));
+        assert_eq!(reader.next().unwrap().unwrap(), vec![0.0, 0.0]);
+        let rest: Vec<Vec<f64>> = reader.map(Result::unwrap).collect();
+        assert_eq!(rest, vec![vec![1.0, 0.5], vec![2.0, 1.0]]);
+
+        fs::write(path, "t\n1\nx\n").unwrap();
+        let rows: Vec<_> = CsvRowReader::open(path).unwrap().collect();
+        assert!(rows[0].is_ok());
+        assert!(rows[1].as_ref().unwrap_err().to_string().starts_with("row 2"));
+        fs::remove_file(path).unwrap();
+    }
 }
@@ -0,0 +1,73 @@
+//! Small dense linear-algebra helpers on `ndarray` types.
+
+use ndarray::{Array1, Array2};
+
+/// Solves the square system A x = b by Gaussian elimination with partial pivoting.
+///
+/// Returns `None` if A is not square or is numerically singular.
+pub fn solve(a: &Array2<f64>, b: &Array1<f64>) -> Option<Array1<f64>> {
+    let n = a.nrows();
+    if a.ncols() != n || b.len() != n {
+        return None;
+    }
+
+    let mut m = a.clone();
+    let mut x = b.clone();
+    let scale = m.iter().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+    let tiny = f64::EPSILON * n as f64 * scale;
+
+    for k in 0..n {
+        // Pick the largest remaining entry in column k as the pivot.
+        let pivot = (k..n).max_by(|&i, &j| m[[i, k]].abs().total_cmp(&m[[j, k]].abs()))?;
+        if m[[pivot, k]].abs() <= tiny {
+            return None;
+        }
+        if pivot != k {
+            for c in 0..n {
+                m.swap([k, c], [pivot, c]);
+            }
+            x.swap(k, pivot);
+        }
+
+        for i in (k + 1)..n {
+            let factor = m[[i, k]] / m[[k, k]];
+            if factor == 0.0 {
+                continue;
+            }
+            for c in k..n {
+                m[[i, c]] -= factor * m[[k, c]];
+            }
+            x[i] -= factor * x[k];
+        }
+    }
+
+    for k in (0..n).rev() {
+        let tail: f64 = ((k + 1)..n).map(|c| m[[k, c]] * x[c]).sum();
+        x[k] = (x[k] - tail) / m[[k, k]];
+    }
+
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_solve_requires_pivoting() {
+        let a = array![[0.0, 2.0, 1.0], [1.0, 1.0, 0.0], [3.0, 0.0, 1.0]];
+        let x_true = array![1.0, -2.0, 3.0];
+        let b = a.dot(&x_true);
+        let x = solve(&a, &b).unwrap();
+        for (xi, ti) in x.iter().zip(x_true.iter()) {
+            assert!((xi - ti).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_solve_singular() {
+        let a = array![[1.0, 2.0], [2.0, 4.0]];
+        assert!(solve(&a, &array![1.0, 2.0]).is_none());
+    }
+}
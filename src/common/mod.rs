@@ -2,5 +2,7 @@
 
 pub mod csv;
 pub mod debug;
+pub mod linalg;
 pub mod plot;
+pub mod rng;
 pub mod timing;
@@ -523,6 +523,642 @@ fn grouped_bar_chart_log(
     Ok(())
 }
 
+/// Creates a plot of 2D curves plus iterate trajectories drawn with markers (requires `plotting` feature).
+///
+/// `curves` are plain lines (e.g. a constraint boundary); each trajectory is
+/// drawn as a line through its points with a circle at every iterate.
+#[cfg(feature = "plotting")]
+pub fn trajectory_plot(
+    path: &str,
+    curves: &[(&str, &[(f64, f64)])],
+    trajectories: &[(&str, &[(f64, f64)])],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root = BitMapBackend::new(path, (config.width, config.height)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let all_points: Vec<(f64, f64)> = curves
+        .iter()
+        .chain(trajectories.iter())
+        .flat_map(|(_, pts)| pts.iter().cloned())
+        .collect();
+    let all_x: Vec<f64> = all_points.iter().map(|p| p.0).collect();
+    let all_y: Vec<f64> = all_points.iter().map(|p| p.1).collect();
+    let x_range = config
+        .x_range
+        .map(|(min, max)| min..max)
+        .unwrap_or_else(|| find_range(&all_x, config.margin_fraction));
+    let y_range = config
+        .y_range
+        .map(|(min, max)| min..max)
+        .unwrap_or_else(|| find_range(&all_y, config.margin_fraction));
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(&config.title, ("sans-serif", 24))
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(x_range, y_range)?;
+
+    chart
+        .configure_mesh()
+        .x_desc(&config.x_label)
+        .y_desc(&config.y_label)
+        .draw()?;
+
+    let stroke = config.stroke_width;
+    for (idx, (label, points)) in curves.iter().enumerate() {
+        let color = series_color(idx);
+        let line_series = LineSeries::new(points.iter().cloned(), color.stroke_width(stroke));
+        if config.show_legend {
+            chart
+                .draw_series(line_series)?
+                .label(*label)
+                .legend(move |(x, y)| {
+                    PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(stroke))
+                });
+        } else {
+            chart.draw_series(line_series)?;
+        }
+    }
+
+    for (idx, (label, points)) in trajectories.iter().enumerate() {
+        let color = series_color(curves.len() + idx);
+        chart.draw_series(LineSeries::new(points.iter().cloned(), color.stroke_width(1)))?;
+        let markers = chart.draw_series(points.iter().map(|&p| Circle::new(p, 5, color.filled())))?;
+        if config.show_legend {
+            markers
+                .label(*label)
+                .legend(move |(x, y)| Circle::new((x + 10, y), 5, color.filled()));
+        }
+    }
+
+    if config.show_legend {
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+    }
+
+    root.present()?;
+    Ok(())
+}
+
+/// Plots data points with a fitted curve above a panel of residuals (requires `plotting` feature).
+///
+/// The top two thirds show the data as markers and the fit as a line; the
+/// bottom third shows the residual at each data point against a zero line.
+#[cfg(feature = "plotting")]
+pub fn fit_residual_plot(
+    path: &str,
+    x_data: &[f64],
+    y_data: &[f64],
+    x_fit: &[f64],
+    y_fit: &[f64],
+    residuals: &[f64],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root = BitMapBackend::new(path, (config.width, config.height)).into_drawing_area();
+    root.fill(&WHITE)?;
+    let (upper, lower) = root.split_vertically(config.height * 2 / 3);
+
+    let all_x: Vec<f64> = x_data.iter().chain(x_fit.iter()).cloned().collect();
+    let all_y: Vec<f64> = y_data.iter().chain(y_fit.iter()).cloned().collect();
+    let x_range = config
+        .x_range
+        .map(|(min, max)| min..max)
+        .unwrap_or_else(|| find_range(&all_x, config.margin_fraction));
+    let y_range = config
+        .y_range
+        .map(|(min, max)| min..max)
+        .unwrap_or_else(|| find_range(&all_y, config.margin_fraction));
+
+    let mut chart = ChartBuilder::on(&upper)
+        .caption(&config.title, ("sans-serif", 24))
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(60)
+        .build_cartesian_2d(x_range.clone(), y_range)?;
+
+    chart.configure_mesh().y_desc(&config.y_label).draw()?;
+
+    let data_color = series_color(0);
+    let fit_color = series_color(3);
+    let stroke = config.stroke_width;
+    chart
+        .draw_series(LineSeries::new(
+            x_fit.iter().cloned().zip(y_fit.iter().cloned()),
+            fit_color.stroke_width(stroke),
+        ))?
+        .label("Fit")
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], fit_color.stroke_width(stroke)));
+    chart
+        .draw_series(
+            x_data
+                .iter()
+                .zip(y_data.iter())
+                .map(|(&x, &y)| Circle::new((x, y), 5, data_color.filled())),
+        )?
+        .label("Data")
+        .legend(move |(x, y)| Circle::new((x + 10, y), 5, data_color.filled()));
+
+    if config.show_legend {
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+    }
+
+    // Residual panel, symmetric about zero.
+    let r_max = residuals.iter().fold(0.0_f64, |acc, r| acc.max(r.abs()));
+    let r_max = if r_max > 0.0 { r_max * (1.0 + config.margin_fraction) } else { 1.0 };
+    let mut residual_chart = ChartBuilder::on(&lower)
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(x_range.clone(), -r_max..r_max)?;
+
+    residual_chart
+        .configure_mesh()
+        .x_desc(&config.x_label)
+        .y_desc("Residual")
+        .y_label_formatter(&|v| if v.abs() < 1e-9 * r_max { String::from("0") } else { format!("{:.1e}", v) })
+        .draw()?;
+
+    residual_chart.draw_series(LineSeries::new(
+        vec![(x_range.start, 0.0), (x_range.end, 0.0)],
+        BLACK.stroke_width(1),
+    ))?;
+    residual_chart.draw_series(
+        x_data
+            .iter()
+            .zip(residuals.iter())
+            .map(|(&x, &r)| PathElement::new(vec![(x, 0.0), (x, r)], data_color.stroke_width(2))),
+    )?;
+    residual_chart.draw_series(
+        x_data
+            .iter()
+            .zip(residuals.iter())
+            .map(|(&x, &r)| Circle::new((x, r), 4, data_color.filled())),
+    )?;
+
+    root.present()?;
+    Ok(())
+}
+
+/// Plots a fitted curve with shaded uncertainty bands and the data (requires `plotting` feature).
+///
+/// Each band is `(label, lower, upper)` evaluated at `x_fit`. Bands are drawn
+/// first, widest first, so that narrower bands stay visible on top.
+#[cfg(feature = "plotting")]
+pub fn band_plot(
+    path: &str,
+    x_data: &[f64],
+    y_data: &[f64],
+    x_fit: &[f64],
+    y_fit: &[f64],
+    bands: &[(&str, &[f64], &[f64])],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root = BitMapBackend::new(path, (config.width, config.height)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let all_x: Vec<f64> = x_data.iter().chain(x_fit.iter()).cloned().collect();
+    let all_y: Vec<f64> = y_data
+        .iter()
+        .chain(y_fit.iter())
+        .chain(bands.iter().flat_map(|(_, lower, upper)| lower.iter().chain(upper.iter())))
+        .cloned()
+        .collect();
+    let x_range = config
+        .x_range
+        .map(|(min, max)| min..max)
+        .unwrap_or_else(|| find_range(&all_x, config.margin_fraction));
+    let y_range = config
+        .y_range
+        .map(|(min, max)| min..max)
+        .unwrap_or_else(|| find_range(&all_y, config.margin_fraction));
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(&config.title, ("sans-serif", 24))
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(x_range, y_range)?;
+
+    chart
+        .configure_mesh()
+        .x_desc(&config.x_label)
+        .y_desc(&config.y_label)
+        .draw()?;
+
+    for (i, (label, lower, upper)) in bands.iter().enumerate() {
+        let color = series_color(i + 1);
+        let outline: Vec<(f64, f64)> = x_fit
+            .iter()
+            .cloned()
+            .zip(upper.iter().cloned())
+            .chain(x_fit.iter().cloned().zip(lower.iter().cloned()).rev())
+            .collect();
+        chart
+            .draw_series(std::iter::once(Polygon::new(outline, color.mix(0.25).filled())))?
+            .label(*label)
+            .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 20, y + 5)], color.mix(0.25).filled()));
+    }
+
+    let data_color = series_color(0);
+    let fit_color = series_color(3);
+    let stroke = config.stroke_width;
+    chart
+        .draw_series(LineSeries::new(
+            x_fit.iter().cloned().zip(y_fit.iter().cloned()),
+            fit_color.stroke_width(stroke),
+        ))?
+        .label("Fit")
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], fit_color.stroke_width(stroke)));
+    chart
+        .draw_series(
+            x_data
+                .iter()
+                .zip(y_data.iter())
+                .map(|(&x, &y)| Circle::new((x, y), 5, data_color.filled())),
+        )?
+        .label("Data")
+        .legend(move |(x, y)| Circle::new((x + 10, y), 5, data_color.filled()));
+
+    if config.show_legend {
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+    }
+
+    root.present()?;
+    Ok(())
+}
+
+/// Creates a scatter plot of labelled point groups with optional curves (requires `plotting` feature).
+///
+/// Each group of points gets its own marker colour, so subsets such as
+/// flagged outliers stand out; curves are drawn as lines in the following
+/// colours.
+#[cfg(feature = "plotting")]
+pub fn scatter_plot(
+    path: &str,
+    groups: &[(&str, &[(f64, f64)])],
+    curves: &[(&str, &[(f64, f64)])],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root = BitMapBackend::new(path, (config.width, config.height)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let all_points: Vec<(f64, f64)> = groups
+        .iter()
+        .chain(curves.iter())
+        .flat_map(|(_, pts)| pts.iter().cloned())
+        .collect();
+    let all_x: Vec<f64> = all_points.iter().map(|p| p.0).collect();
+    let all_y: Vec<f64> = all_points.iter().map(|p| p.1).collect();
+    let x_range = config
+        .x_range
+        .map(|(min, max)| min..max)
+        .unwrap_or_else(|| find_range(&all_x, config.margin_fraction));
+    let y_range = config
+        .y_range
+        .map(|(min, max)| min..max)
+        .unwrap_or_else(|| find_range(&all_y, config.margin_fraction));
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(&config.title, ("sans-serif", 24))
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(x_range, y_range)?;
+
+    chart
+        .configure_mesh()
+        .x_desc(&config.x_label)
+        .y_desc(&config.y_label)
+        .draw()?;
+
+    let stroke = config.stroke_width;
+    for (i, (label, points)) in curves.iter().enumerate() {
+        let color = series_color(groups.len() + i);
+        chart
+            .draw_series(LineSeries::new(points.iter().cloned(), color.stroke_width(stroke)))?
+            .label(*label)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(stroke)));
+    }
+
+    for (i, (label, points)) in groups.iter().enumerate() {
+        let color = series_color(i);
+        chart
+            .draw_series(points.iter().map(|&p| Circle::new(p, 5, color.filled())))?
+            .label(*label)
+            .legend(move |(x, y)| Circle::new((x + 10, y), 5, color.filled()));
+    }
+
+    if config.show_legend {
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+    }
+
+    root.present()?;
+    Ok(())
+}
+
+/// A straight line segment between two points.
+pub type Segment = ((f64, f64), (f64, f64));
+
+/// One panel of [`residual_comparison_plot`]: title, fitted curve and residual segments.
+pub type ResidualPanel<'a> = (&'a str, &'a [(f64, f64)], &'a [Segment]);
+
+/// Side-by-side panels comparing fits by how they measure residuals (requires `plotting` feature).
+///
+/// Every panel shows the same data. Each entry of `panels` is
+/// `(title, fitted curve, residual segments)`, where a segment joins a data
+/// point to the point on the curve it is measured against (straight down
+/// for vertical residuals, perpendicular for orthogonal ones). All panels
+/// share axis ranges so the fits can be compared directly; segments are
+/// perpendicular in data units, so they only look perpendicular on screen
+/// when both axes happen to share a scale.
+#[cfg(feature = "plotting")]
+pub fn residual_comparison_plot(
+    path: &str,
+    x_data: &[f64],
+    y_data: &[f64],
+    panels: &[ResidualPanel],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root = BitMapBackend::new(path, (config.width, config.height)).into_drawing_area();
+    root.fill(&WHITE)?;
+    let root = root.titled(&config.title, ("sans-serif", 24))?;
+    let areas = root.split_evenly((1, panels.len().max(1)));
+
+    let all_x: Vec<f64> = x_data
+        .iter()
+        .cloned()
+        .chain(panels.iter().flat_map(|(_, curve, _)| curve.iter().map(|p| p.0)))
+        .collect();
+    let all_y: Vec<f64> = y_data
+        .iter()
+        .cloned()
+        .chain(panels.iter().flat_map(|(_, curve, _)| curve.iter().map(|p| p.1)))
+        .collect();
+    let x_range = config
+        .x_range
+        .map(|(min, max)| min..max)
+        .unwrap_or_else(|| find_range(&all_x, config.margin_fraction));
+    let y_range = config
+        .y_range
+        .map(|(min, max)| min..max)
+        .unwrap_or_else(|| find_range(&all_y, config.margin_fraction));
+
+    let data_color = series_color(0);
+    for (i, ((title, curve, segments), area)) in panels.iter().zip(areas.iter()).enumerate() {
+        let mut chart = ChartBuilder::on(area)
+            .caption(*title, ("sans-serif", 18))
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(50)
+            .build_cartesian_2d(x_range.clone(), y_range.clone())?;
+
+        chart
+            .configure_mesh()
+            .x_desc(&config.x_label)
+            .y_desc(&config.y_label)
+            .draw()?;
+
+        let color = series_color(i + 1);
+        chart.draw_series(
+            segments
+                .iter()
+                .map(|&(a, b)| PathElement::new(vec![a, b], BLACK.mix(0.6).stroke_width(1))),
+        )?;
+        chart.draw_series(LineSeries::new(curve.iter().cloned(), color.stroke_width(config.stroke_width)))?;
+        chart.draw_series(
+            x_data
+                .iter()
+                .zip(y_data.iter())
+                .map(|(&x, &y)| Circle::new((x, y), 4, data_color.filled())),
+        )?;
+    }
+
+    root.present()?;
+    Ok(())
+}
+
+/// Traces the level set f(x, y) = `level` with marching squares.
+///
+/// The rectangle is sampled on a `resolution` x `resolution` grid of cells and
+/// the contour is returned as unordered line segments.
+pub fn contour_segments(
+    f: &dyn Fn(f64, f64) -> f64,
+    x_range: (f64, f64),
+    y_range: (f64, f64),
+    resolution: usize,
+    level: f64,
+) -> Vec<Segment> {
+    let n = resolution.max(1);
+    let dx = (x_range.1 - x_range.0) / n as f64;
+    let dy = (y_range.1 - y_range.0) / n as f64;
+    let xs: Vec<f64> = (0..=n).map(|i| x_range.0 + i as f64 * dx).collect();
+    let ys: Vec<f64> = (0..=n).map(|j| y_range.0 + j as f64 * dy).collect();
+    let values: Vec<Vec<f64>> = ys.iter().map(|&y| xs.iter().map(|&x| f(x, y) - level).collect()).collect();
+
+    // Point on the edge (a, b) where the interpolated value crosses zero.
+    let crossing = |pa: (f64, f64), va: f64, pb: (f64, f64), vb: f64| {
+        let t = va / (va - vb);
+        (pa.0 + t * (pb.0 - pa.0), pa.1 + t * (pb.1 - pa.1))
+    };
+
+    let mut segments = Vec::new();
+    for j in 0..n {
+        for i in 0..n {
+            // Corners counter-clockwise from bottom-left.
+            let corners = [
+                ((xs[i], ys[j]), values[j][i]),
+                ((xs[i + 1], ys[j]), values[j][i + 1]),
+                ((xs[i + 1], ys[j + 1]), values[j + 1][i + 1]),
+                ((xs[i], ys[j + 1]), values[j + 1][i]),
+            ];
+            if corners.iter().any(|c| !c.1.is_finite()) {
+                continue;
+            }
+
+            let mut points = Vec::with_capacity(4);
+            for k in 0..4 {
+                let (pa, va) = corners[k];
+                let (pb, vb) = corners[(k + 1) % 4];
+                if (va < 0.0) != (vb < 0.0) {
+                    points.push(crossing(pa, va, pb, vb));
+                }
+            }
+
+            match points.len() {
+                2 => segments.push((points[0], points[1])),
+                4 => {
+                    // Saddle cell: resolve the ambiguity with the centre value.
+                    let centre = f(xs[i] + 0.5 * dx, ys[j] + 0.5 * dy) - level;
+                    if (centre < 0.0) == (corners[0].1 < 0.0) {
+                        segments.push((points[0], points[1]));
+                        segments.push((points[2], points[3]));
+                    } else {
+                        segments.push((points[0], points[3]));
+                        segments.push((points[1], points[2]));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    segments
+}
+
+/// Plots the geometry of an equality-constrained 2D problem (requires `plotting` feature).
+///
+/// Draws contours of the objective, the constraint curve h(x, y) = 0, the
+/// objective contour through the optimum, and ∇f and ∇h arrows at the
+/// optimum. Both arrows are drawn with the same length so that their
+/// (anti-)parallel alignment is easy to see. Gradients are estimated with
+/// central differences. When `config` has no ranges, a square window of
+/// half-width 2 around the optimum is used.
+#[cfg(feature = "plotting")]
+pub fn lagrange_geometry_plot(
+    path: &str,
+    objective: &dyn Fn(f64, f64) -> f64,
+    constraint: &dyn Fn(f64, f64) -> f64,
+    optimum: (f64, f64),
+    n_levels: usize,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    const RESOLUTION: usize = 150;
+
+    let x_range = config.x_range.unwrap_or((optimum.0 - 2.0, optimum.0 + 2.0));
+    let y_range = config.y_range.unwrap_or((optimum.1 - 2.0, optimum.1 + 2.0));
+
+    let root = BitMapBackend::new(path, (config.width, config.height)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(&config.title, ("sans-serif", 24))
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(x_range.0..x_range.1, y_range.0..y_range.1)?;
+
+    chart
+        .configure_mesh()
+        .x_desc(&config.x_label)
+        .y_desc(&config.y_label)
+        .draw()?;
+
+    // Objective contours, spaced quadratically so they crowd towards the minimum.
+    let samples: Vec<f64> = (0..=RESOLUTION)
+        .flat_map(|j| {
+            (0..=RESOLUTION).map(move |i| {
+                let x = x_range.0 + (x_range.1 - x_range.0) * i as f64 / RESOLUTION as f64;
+                let y = y_range.0 + (y_range.1 - y_range.0) * j as f64 / RESOLUTION as f64;
+                (x, y)
+            })
+        })
+        .map(|(x, y)| objective(x, y))
+        .filter(|v| v.is_finite())
+        .collect();
+    let f_min = samples.iter().cloned().fold(f64::INFINITY, f64::min);
+    let f_max = samples.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let contour_color = RGBColor(170, 170, 170);
+    for k in 1..=n_levels {
+        let frac = k as f64 / (n_levels + 1) as f64;
+        let level = f_min + (f_max - f_min) * frac * frac;
+        let segments = contour_segments(objective, x_range, y_range, RESOLUTION, level);
+        chart.draw_series(
+            segments
+                .into_iter()
+                .map(|(a, b)| PathElement::new(vec![a, b], contour_color.stroke_width(1))),
+        )?;
+    }
+
+    let f_star_color = series_color(1);
+    let f_star = objective(optimum.0, optimum.1);
+    let segments = contour_segments(objective, x_range, y_range, RESOLUTION, f_star);
+    chart
+        .draw_series(
+            segments
+                .into_iter()
+                .map(|(a, b)| PathElement::new(vec![a, b], f_star_color.stroke_width(2))),
+        )?
+        .label("f(x, y) = f*")
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], f_star_color.stroke_width(2)));
+
+    let h_color = series_color(0);
+    let stroke = config.stroke_width;
+    let segments = contour_segments(constraint, x_range, y_range, RESOLUTION, 0.0);
+    chart
+        .draw_series(
+            segments
+                .into_iter()
+                .map(|(a, b)| PathElement::new(vec![a, b], h_color.stroke_width(stroke))),
+        )?
+        .label("h(x, y) = 0")
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], h_color.stroke_width(stroke)));
+
+    // Gradient arrows at the optimum, normalised to a common length.
+    let arrow_length = 0.2 * (x_range.1 - x_range.0).min(y_range.1 - y_range.0);
+    let arrows = [
+        ("∇f", central_gradient(objective, optimum), series_color(3)),
+        ("∇h", central_gradient(constraint, optimum), series_color(2)),
+    ];
+    for (label, (gx, gy), color) in arrows {
+        let norm = (gx * gx + gy * gy).sqrt();
+        if norm == 0.0 || !norm.is_finite() {
+            continue;
+        }
+        let (ux, uy) = (gx / norm, gy / norm);
+        let tip = (optimum.0 + arrow_length * ux, optimum.1 + arrow_length * uy);
+        let head = 0.25 * arrow_length;
+        let (c, s) = (0.5f64.cos(), 0.5f64.sin());
+        let left = (tip.0 - head * (ux * c - uy * s), tip.1 - head * (uy * c + ux * s));
+        let right = (tip.0 - head * (ux * c + uy * s), tip.1 - head * (uy * c - ux * s));
+        chart
+            .draw_series(std::iter::once(PathElement::new(
+                vec![optimum, tip, left, tip, right],
+                color.stroke_width(3),
+            )))?
+            .label(label)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(3)));
+    }
+
+    chart.draw_series(std::iter::once(Circle::new(optimum, 6, BLACK.filled())))?;
+
+    if config.show_legend {
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+    }
+
+    root.present()?;
+    Ok(())
+}
+
+#[cfg(feature = "plotting")]
+fn central_gradient(f: &dyn Fn(f64, f64) -> f64, (x, y): (f64, f64)) -> (f64, f64) {
+    let hx = 1e-6 * x.abs().max(1.0);
+    let hy = 1e-6 * y.abs().max(1.0);
+    (
+        (f(x + hx, y) - f(x - hx, y)) / (2.0 * hx),
+        (f(x, y + hy) - f(x, y - hy)) / (2.0 * hy),
+    )
+}
+
 /// Stub for when plotting feature is disabled.
 #[cfg(not(feature = "plotting"))]
 pub fn line_plot(
@@ -545,3 +1181,31 @@ pub fn line_plot_with_markers(
 ) -> Result<(), Box<dyn std::error::Error>> {
     Err("Enable the 'plotting' feature to use this function".into())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contour_segments_unit_circle() {
+        let f = |x: f64, y: f64| x * x + y * y;
+        let segments = contour_segments(&f, (-2.0, 2.0), (-2.0, 2.0), 80, 1.0);
+
+        assert!(!segments.is_empty());
+        for ((x0, y0), (x1, y1)) in &segments {
+            assert!(((x0 * x0 + y0 * y0).sqrt() - 1.0).abs() < 0.01);
+            assert!(((x1 * x1 + y1 * y1).sqrt() - 1.0).abs() < 0.01);
+        }
+        let length: f64 = segments
+            .iter()
+            .map(|((x0, y0), (x1, y1))| ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt())
+            .sum();
+        assert!((length - 2.0 * std::f64::consts::PI).abs() < 0.01, "length={length}");
+    }
+
+    #[test]
+    fn test_contour_segments_missing_level() {
+        let f = |x: f64, y: f64| x * x + y * y;
+        assert!(contour_segments(&f, (-1.0, 1.0), (-1.0, 1.0), 20, 10.0).is_empty());
+    }
+}
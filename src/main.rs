@@ -5,10 +5,15 @@ use std::env;
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().collect();
 
+    if args.get(1).map(String::as_str) == Some("fit") {
+        return run_fit(&args);
+    }
+
     let week: u32 = match args.get(1).and_then(|s| s.parse().ok()) {
         Some(w) if (1..=10).contains(&w) => w,
         _ => {
             println!("Usage: {} <week>", args[0]);
+            println!("       {} fit <file.csv> --model <name> [options]", args[0]);
             println!("Available weeks: 1-10");
             return Ok(());
         }
@@ -18,9 +23,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         1 => run_week01()?,
         2 => run_week02()?,
         3 => run_week03(&args)?,
-        4 => println!("Week 4: not yet implemented"),
-        5 => println!("Week 5: not yet implemented"),
-        6 => println!("Week 6: not yet implemented"),
+        4 => run_week04()?,
+        5 => run_week05()?,
+        6 => run_week06()?,
         7 => println!("Week 7: not yet implemented"),
         8 => println!("Week 8: not yet implemented"),
         9 => println!("Week 9: not yet implemented"),
@@ -177,3 +182,349 @@ fn run_week03(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+fn run_week04() -> Result<(), Box<dyn std::error::Error>> {
+    use week04_lagrange_multiplier::{augmented_lagrangian, AugLagConfig, ConstrainedProblem};
+
+    println!("Week 4: Augmented Lagrangian");
+    println!("  minimise (x - 2)^2 + (y - 1)^2");
+    println!("  subject to x + y = 2, x^2 - y <= 0");
+    println!();
+
+    let problem = ConstrainedProblem::new(|x| (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2))
+        .with_equality(|x| x[0] + x[1] - 2.0)
+        .with_inequality(|x| x[0] * x[0] - x[1]);
+    let config = AugLagConfig::default().with_initial_penalty(1.0);
+    let timed = common::timing::timed(|| augmented_lagrangian(&problem, &[0.0, 0.0], &config));
+    let result = timed.value;
+
+    println!(
+        "  {:>5}  {:>10}  {:>10}  {:>12}  {:>12}  {:>10}  {:>6}",
+        "outer", "x", "y", "f(x)", "violation", "penalty", "inner"
+    );
+    for it in &result.history {
+        println!(
+            "  {:>5}  {:>10.6}  {:>10.6}  {:>12.6e}  {:>12.3e}  {:>10.1e}  {:>6}",
+            it.iteration, it.x[0], it.x[1], it.fx, it.constraint_violation, it.penalty, it.inner_iterations
+        );
+    }
+    println!();
+    println!("Augmented Lagrangian Results");
+    println!("  Converged:          {}", result.converged);
+    println!("  x* = ({:.6}, {:.6}),  f(x*) = {:.6}", result.x[0], result.x[1], result.fx);
+    println!("  λ (equality):       {:?}", result.equality_multipliers);
+    println!("  ν (inequality):     {:?}", result.inequality_multipliers);
+    println!("  Time:               {}", common::timing::format_duration(timed.duration));
+
+    let dir = "output/week04";
+    std::fs::create_dir_all(dir)?;
+
+    println!();
+    run_week04_sqp(&problem, dir)?;
+
+    println!();
+    run_week04_sensitivity();
+
+    #[cfg(feature = "plotting")]
+    {
+        println!();
+        println!("  Running problem_penalty_barrier_paths...");
+        week04_lagrange_multiplier::problems::problem_penalty_barrier_paths(dir)?;
+
+        println!("  Running problem_lagrange_geometry...");
+        week04_lagrange_multiplier::problems::problem_lagrange_geometry(dir)?;
+    }
+
+    println!("Week 4 complete. Output in {}/", dir);
+
+    Ok(())
+}
+
+fn run_week04_sqp(
+    problem: &week04_lagrange_multiplier::ConstrainedProblem,
+    dir: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use week04_lagrange_multiplier::{sqp, SqpConfig};
+
+    let timed = common::timing::timed(|| sqp(problem, &[0.0, 0.0], &SqpConfig::default()));
+    let result = timed.value;
+
+    println!("Sequential Quadratic Programming");
+    println!("  {:>4}  {:>10}  {:>10}  {:>12}  {:>10}  {:>10}  {:>6}", "iter", "x", "y", "f(x)", "violation", "kkt", "alpha");
+    for it in &result.history {
+        println!(
+            "  {:>4}  {:>10.6}  {:>10.6}  {:>12.6e}  {:>10.3e}  {:>10.3e}  {:>6.3}",
+            it.iteration, it.x[0], it.x[1], it.fx, it.constraint_violation, it.kkt_residual, it.step_length
+        );
+    }
+    println!("  Converged:          {} ({:?})", result.converged, result.stop_reason);
+    println!("  x* = ({:.6}, {:.6}),  f(x*) = {:.6}", result.x[0], result.x[1], result.fx);
+    println!("  λ = {:?},  ν = {:?}", result.equality_multipliers, result.inequality_multipliers);
+    println!("  Time:               {}", common::timing::format_duration(timed.duration));
+
+    let csv_path = format!("{}/sqp_history.csv", dir);
+    result.write_history_csv(&csv_path)?;
+    println!("  Iteration log:      {}", csv_path);
+
+    Ok(())
+}
+
+fn run_week04_sensitivity() {
+    use week04_lagrange_multiplier::{parameter_sensitivity, shadow_prices, AugLagConfig, ConstrainedProblem};
+
+    let perimeter = 20.0;
+    let fence = |p: f64| {
+        ConstrainedProblem::new(|x| -x[0] * x[1]).with_inequality(move |x| 2.0 * x[0] + 2.0 * x[1] - p)
+    };
+
+    println!("Sensitivity Analysis");
+    println!("  maximise area xy subject to 2x + 2y <= {perimeter}");
+
+    let config = AugLagConfig::default();
+    let report = shadow_prices(&fence(perimeter), &[1.0, 1.0], &config, 1e-3);
+    let x = &report.baseline.x;
+    println!("  x* = ({:.6}, {:.6}),  f(x*) = {:.6}", x[0], x[1], report.baseline.fx);
+    for price in &report.shadow_prices {
+        println!(
+            "  {:?} {}: multiplier = {:.6}, shadow price = {:.6}, re-solved = {:.6}",
+            price.kind, price.index, price.multiplier, price.shadow_price, price.finite_difference_price
+        );
+    }
+
+    // "What if the limit were 5% higher?"
+    let dp = 0.05 * perimeter;
+    let sens = parameter_sensitivity(&fence, perimeter, &[1.0, 1.0], &config, 1e-3);
+    let predicted_x = sens.predicted_x(dp);
+    let actual = week04_lagrange_multiplier::augmented_lagrangian(&fence(perimeter + dp), x, &config);
+    println!("  Limit +5% (Δ = {dp}):");
+    println!(
+        "    predicted x* = ({:.4}, {:.4}), f* = {:.4}",
+        predicted_x[0],
+        predicted_x[1],
+        sens.predicted_objective(dp)
+    );
+    println!("    re-solved x* = ({:.4}, {:.4}), f* = {:.4}", actual.x[0], actual.x[1], actual.fx);
+}
+
+fn run_week05() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Week 5: Linear Least Squares");
+
+    println!("  Running problem_ill_conditioned_fit...");
+    week05_linear_least_squares::problems::problem_ill_conditioned_fit();
+
+    println!("  Running problem_table_fit...");
+    week05_linear_least_squares::problems::problem_table_fit();
+
+    println!("  Running problem_regression_report...");
+    week05_linear_least_squares::problems::problem_regression_report();
+
+    println!("  Running problem_weighted_calibration...");
+    week05_linear_least_squares::problems::problem_weighted_calibration()?;
+
+    println!("  Running problem_ridge_polynomial...");
+    week05_linear_least_squares::problems::problem_ridge_polynomial();
+
+    println!("  Running problem_deblur...");
+    week05_linear_least_squares::problems::problem_deblur();
+
+    println!("  Running problem_robust_line...");
+    week05_linear_least_squares::problems::problem_robust_line();
+
+    println!("  Running problem_spectral_unmixing...");
+    week05_linear_least_squares::problems::problem_spectral_unmixing();
+
+    println!("  Running problem_errors_in_variables...");
+    week05_linear_least_squares::problems::problem_errors_in_variables();
+
+    println!("  Running problem_model_selection...");
+    week05_linear_least_squares::problems::problem_model_selection();
+
+    let dir = "output/week05";
+    std::fs::create_dir_all(dir)?;
+
+    println!("  Running problem_streaming_rls...");
+    week05_linear_least_squares::problems::problem_streaming_rls(dir)?;
+
+    #[cfg(feature = "plotting")]
+    {
+        println!("  Running problem_table_fit_plot...");
+        week05_linear_least_squares::problems::problem_table_fit_plot(dir)?;
+
+        println!("  Running problem_prediction_band_plot...");
+        week05_linear_least_squares::problems::problem_prediction_band_plot(dir)?;
+
+        println!("  Running problem_regularization_plots...");
+        week05_linear_least_squares::problems::problem_regularization_plots(dir)?;
+
+        println!("  Running problem_robust_line_plot...");
+        week05_linear_least_squares::problems::problem_robust_line_plot(dir)?;
+
+        println!("  Running problem_errors_in_variables_plot...");
+        week05_linear_least_squares::problems::problem_errors_in_variables_plot(dir)?;
+
+        println!("  Running problem_model_selection_plot...");
+        week05_linear_least_squares::problems::problem_model_selection_plot(dir)?;
+
+        println!("  Running problem_streaming_rls_plot...");
+        week05_linear_least_squares::problems::problem_streaming_rls_plot(dir)?;
+    }
+
+    println!("Week 5 complete. Output in {}/", dir);
+    Ok(())
+}
+
+fn run_week06() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Week 6: Nonlinear Least Squares");
+
+    println!("  Running problem_exponential_decay...");
+    week06_nonlinear_least_squares::problems::problem_exponential_decay();
+
+    println!("  Running problem_michaelis_menten...");
+    week06_nonlinear_least_squares::problems::problem_michaelis_menten();
+
+    let dir = "output/week06";
+    std::fs::create_dir_all(dir)?;
+
+    println!("  Running problem_levenberg_marquardt...");
+    week06_nonlinear_least_squares::problems::problem_levenberg_marquardt(dir)?;
+
+    println!("  Running problem_jacobian_check...");
+    week06_nonlinear_least_squares::problems::problem_jacobian_check();
+
+    println!("  Running problem_parameter_uncertainty...");
+    week06_nonlinear_least_squares::problems::problem_parameter_uncertainty();
+
+    println!("  Running problem_model_library...");
+    week06_nonlinear_least_squares::problems::problem_model_library();
+
+    println!("  Running problem_variable_projection...");
+    week06_nonlinear_least_squares::problems::problem_variable_projection();
+
+    println!("  Running problem_ode_logistic...");
+    week06_nonlinear_least_squares::problems::problem_ode_logistic();
+
+    println!("  Running problem_multi_start...");
+    week06_nonlinear_least_squares::problems::problem_multi_start();
+
+    println!("  Running problem_global_fit...");
+    week06_nonlinear_least_squares::problems::problem_global_fit();
+
+    #[cfg(feature = "plotting")]
+    {
+        println!("  Running problem_gauss_newton_plots...");
+        week06_nonlinear_least_squares::problems::problem_gauss_newton_plots(dir)?;
+
+        println!("  Running problem_profile_plot...");
+        week06_nonlinear_least_squares::problems::problem_profile_plot(dir)?;
+
+        println!("  Running problem_ode_logistic_plot...");
+        week06_nonlinear_least_squares::problems::problem_ode_logistic_plot(dir)?;
+    }
+
+    println!("Week 6 complete. Output in {}/", dir);
+    Ok(())
+}
+
+const FIT_USAGE: &str = "Usage: eg551 fit <file.csv> --model <name> [--x COLUMN] [--y COLUMN] [--sigma COLUMN] \
+[--start p0,p1,...] [--level VALUE] [--output DIR]";
+
+/// Prints the `fit` usage and turns `message` into the error `run_fit` returns.
+fn fit_usage_error(message: String) -> Box<dyn std::error::Error> {
+    eprintln!("{FIT_USAGE}");
+    message.into()
+}
+
+fn run_fit(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    use week05_linear_least_squares::Measurements;
+    use week06_nonlinear_least_squares::{fit_measurements, FitModel, LevenbergMarquardtConfig};
+
+    let Some(path) = args.get(2).filter(|a| !a.starts_with("--")) else {
+        return Err(fit_usage_error(String::from("missing CSV file")));
+    };
+    let mut model = None;
+    let mut x_column = String::from("x");
+    let mut y_column = String::from("y");
+    let mut sigma_column = None;
+    let mut start: Option<Vec<f64>> = None;
+    let mut level = 0.95_f64;
+    let mut dir = String::from("output/fit");
+
+    let mut i = 3;
+    while i < args.len() {
+        let option = args[i].as_str();
+        i += 1;
+        let value = || args.get(i).ok_or_else(|| fit_usage_error(format!("{option} needs a value")));
+        match option {
+            "--model" => {
+                let value = value()?;
+                model = Some(FitModel::from_name(value).ok_or_else(|| {
+                    eprintln!("Available models: {}", FitModel::available_names().join(", "));
+                    format!("unknown model: {value}")
+                })?);
+            }
+            "--x" => x_column = value()?.clone(),
+            "--y" => y_column = value()?.clone(),
+            "--sigma" => sigma_column = Some(value()?.clone()),
+            "--start" => {
+                let value = value()?;
+                let values: Result<Vec<f64>, _> = value.split(',').map(|v| v.trim().parse()).collect();
+                start = Some(values.map_err(|e| format!("invalid value for --start: {value} ({e})"))?);
+            }
+            "--level" => {
+                let value = value()?;
+                level = value.parse().map_err(|e| format!("invalid value for --level: {value} ({e})"))?;
+                if !(level > 0.0 && level < 1.0) {
+                    return Err(format!("--level must lie strictly between 0 and 1, got {value}").into());
+                }
+            }
+            "--output" => dir = value()?.clone(),
+            other => return Err(fit_usage_error(format!("unknown option: {other}"))),
+        }
+        i += 1;
+    }
+    let Some(model) = model else {
+        eprintln!("Available models: {}", FitModel::available_names().join(", "));
+        return Err(fit_usage_error(String::from("missing --model")));
+    };
+
+    // Use a column called "sigma" for the uncertainties unless told otherwise.
+    if sigma_column.is_none() && common::csv::CsvRowReader::open(path)?.column_index("sigma").is_some() {
+        sigma_column = Some(String::from("sigma"));
+    }
+    let data = Measurements::from_csv(path, &x_column, &y_column, sigma_column.as_deref())?;
+    let n_params = model.param_names().len();
+    if n_params > data.x.len() {
+        return Err(format!("the model has {} parameters but {} has only {} points", n_params, path, data.x.len()).into());
+    }
+    if let Some(start) = &start {
+        let expected = model.param_names().len();
+        if start.len() != expected {
+            return Err(format!("--start needs {} values ({})", expected, model.param_names().join(", ")).into());
+        }
+    }
+
+    println!("Fitting {} against {} from {}", y_column, x_column, path);
+    match &sigma_column {
+        Some(column) => println!("  Weighting by 1/sigma^2 from column '{}'", column),
+        None => println!("  Unweighted; uncertainties from the residual scatter"),
+    }
+    let fit = fit_measurements(model, data, start.as_deref(), &LevenbergMarquardtConfig::default());
+    for line in fit.report(level) {
+        println!("  {}", line);
+    }
+
+    std::fs::create_dir_all(&dir)?;
+    let stem = std::path::Path::new(path).file_stem().and_then(|s| s.to_str()).unwrap_or("data");
+    let csv_path = format!("{}/{}_fit.csv", dir, stem);
+    fit.write_csv(&csv_path)?;
+    println!("  Wrote {}", csv_path);
+
+    #[cfg(feature = "plotting")]
+    {
+        let plot_path = format!("{}/{}_fit.png", dir, stem);
+        fit.plot(&plot_path, &x_column, &y_column)?;
+        println!("  Wrote {}", plot_path);
+    }
+
+    Ok(())
+}
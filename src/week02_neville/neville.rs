@@ -42,7 +42,7 @@ pub fn neville(points: &[(f64, f64)], x_target: f64) -> NevilleResult {
     }
 
     // Compute deltas
-    let mut deltas = Vec::with_capacity(if n > 1 { n - 1 } else { 0 });
+    let mut deltas = Vec::with_capacity(n.saturating_sub(1));
     for i in 0..pyramid.len() - 1 {
         deltas.push((pyramid[i + 1][0] - pyramid[i][0]).abs());
     }
@@ -0,0 +1,160 @@
+//! Augmented Lagrangian method for mixed equality/inequality constraints.
+//!
+//! Multipliers follow the convention L(x, λ, ν) = f(x) + Σ λ_i h_i(x) + Σ ν_j g_j(x),
+//! so at a KKT point ∇f = -Σ λ_i ∇h_i - Σ ν_j ∇g_j with ν_j >= 0.
+
+use super::types::{AugLagConfig, AugLagResult, ConstrainedProblem, OuterIteration};
+use super::unconstrained::bfgs;
+
+/// Evaluates the (Powell–Hestenes–Rockafellar) augmented Lagrangian at `x`.
+pub fn augmented_lagrangian_value(
+    problem: &ConstrainedProblem,
+    x: &[f64],
+    lambda: &[f64],
+    nu: &[f64],
+    penalty: f64,
+) -> f64 {
+    let eq: f64 = problem
+        .equalities
+        .iter()
+        .zip(lambda)
+        .map(|(h, &l)| {
+            let hx = h(x);
+            l * hx + 0.5 * penalty * hx * hx
+        })
+        .sum();
+    let ineq: f64 = problem
+        .inequalities
+        .iter()
+        .zip(nu)
+        .map(|(g, &v)| {
+            let shifted = (v + penalty * g(x)).max(0.0);
+            (shifted * shifted - v * v) / (2.0 * penalty)
+        })
+        .sum();
+    problem.objective_value(x) + eq + ineq
+}
+
+/// Solves `problem` with the augmented Lagrangian method starting from `x0`.
+///
+/// Each outer iteration minimises the augmented Lagrangian with BFGS, then
+/// updates the multipliers. The penalty grows whenever the combined
+/// feasibility/complementarity measure fails to drop by at least a factor
+/// of four; the method stops once that measure is below `config.tol`.
+pub fn augmented_lagrangian(problem: &ConstrainedProblem, x0: &[f64], config: &AugLagConfig) -> AugLagResult {
+    let mut lambda = vec![0.0; problem.equalities.len()];
+    let mut nu = vec![0.0; problem.inequalities.len()];
+    let mut penalty = config.initial_penalty;
+    let mut x = x0.to_vec();
+    let mut previous_progress = f64::INFINITY;
+    let mut history = Vec::new();
+    let mut converged = false;
+
+    for k in 1..=config.max_outer {
+        let subproblem = |z: &[f64]| augmented_lagrangian_value(problem, z, &lambda, &nu, penalty);
+        let inner = bfgs(&subproblem, &x, config.inner_tol, config.inner_max_iter);
+        x = inner.x;
+
+        let violation = problem.constraint_violation(&x);
+        history.push(OuterIteration {
+            iteration: k,
+            x: x.clone(),
+            fx: problem.objective_value(&x),
+            constraint_violation: violation,
+            penalty,
+            inner_iterations: inner.iterations,
+        });
+
+        // Feasibility/complementarity measure: an inactive constraint with a
+        // positive multiplier still counts as unconverged.
+        let h = problem.equality_values(&x);
+        let g = problem.inequality_values(&x);
+        let progress = h
+            .iter()
+            .map(|hx| hx.abs())
+            .chain(g.iter().zip(&nu).map(|(gx, v)| gx.max(-v / penalty).abs()))
+            .fold(0.0, f64::max);
+
+        for (l, hx) in lambda.iter_mut().zip(&h) {
+            *l += penalty * hx;
+        }
+        for (v, gx) in nu.iter_mut().zip(&g) {
+            *v = (*v + penalty * gx).max(0.0);
+        }
+
+        if progress < config.tol {
+            converged = true;
+            break;
+        }
+        if progress > 0.25 * previous_progress {
+            penalty = (penalty * config.penalty_growth).min(config.max_penalty);
+        }
+        previous_progress = progress;
+    }
+
+    AugLagResult {
+        fx: problem.objective_value(&x),
+        x,
+        equality_multipliers: lambda,
+        inequality_multipliers: nu,
+        history,
+        converged,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equality_only() {
+        // min x^2 + y^2 s.t. x + y = 1 -> (0.5, 0.5), λ = -1
+        let problem = ConstrainedProblem::new(|x| x[0] * x[0] + x[1] * x[1]).with_equality(|x| x[0] + x[1] - 1.0);
+        let result = augmented_lagrangian(&problem, &[0.0, 0.0], &AugLagConfig::default());
+
+        assert!(result.converged);
+        assert!((result.x[0] - 0.5).abs() < 1e-4, "x={:?}", result.x);
+        assert!((result.x[1] - 0.5).abs() < 1e-4, "x={:?}", result.x);
+        assert!((result.equality_multipliers[0] + 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_mixed_constraints() {
+        // min (x-2)^2 + (y-1)^2 s.t. x + y = 2, x^2 - y <= 0 -> (1, 1), λ = ν = 2/3
+        let problem = ConstrainedProblem::new(|x| (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2))
+            .with_equality(|x| x[0] + x[1] - 2.0)
+            .with_inequality(|x| x[0] * x[0] - x[1]);
+        let result = augmented_lagrangian(&problem, &[0.0, 0.0], &AugLagConfig::default());
+
+        assert!(result.converged);
+        assert!((result.x[0] - 1.0).abs() < 1e-4, "x={:?}", result.x);
+        assert!((result.x[1] - 1.0).abs() < 1e-4, "x={:?}", result.x);
+        assert!((result.equality_multipliers[0] - 2.0 / 3.0).abs() < 1e-3);
+        assert!((result.inequality_multipliers[0] - 2.0 / 3.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_inactive_inequality_has_zero_multiplier() {
+        // Unconstrained minimum (1, 1) already satisfies x + y <= 5.
+        let problem = ConstrainedProblem::new(|x| (x[0] - 1.0).powi(2) + (x[1] - 1.0).powi(2))
+            .with_inequality(|x| x[0] + x[1] - 5.0);
+        let result = augmented_lagrangian(&problem, &[0.0, 0.0], &AugLagConfig::default());
+
+        assert!(result.converged);
+        assert_eq!(result.inequality_multipliers[0], 0.0);
+        assert!((result.fx).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_history_reports_violation() {
+        let problem = ConstrainedProblem::new(|x| x[0] * x[0] + x[1] * x[1]).with_equality(|x| x[0] + x[1] - 1.0);
+        let config = AugLagConfig::default().with_initial_penalty(1.0);
+        let result = augmented_lagrangian(&problem, &[0.0, 0.0], &config);
+
+        assert!(result.history.len() > 1);
+        let first = result.history.first().unwrap().constraint_violation;
+        let last = result.history.last().unwrap().constraint_violation;
+        assert!(last < first);
+        assert!(last < config.tol);
+    }
+}
@@ -1,3 +1,21 @@
 //! Week 4: Lagrange Multipliers
 //!
 //! Constrained optimization using Lagrange multipliers.
+
+pub mod types;
+pub mod unconstrained;
+pub mod augmented_lagrangian;
+pub mod penalty;
+pub mod sensitivity;
+pub mod sqp;
+pub mod problems;
+
+pub use types::{
+    AugLagConfig, AugLagResult, ConstrainedProblem, ConstraintKind, OuterIteration, ParameterSchedule, PathResult,
+    SqpConfig, SqpIteration, SqpResult, SqpStopReason, SubproblemSolution, UnconstrainedResult,
+};
+pub use unconstrained::bfgs;
+pub use augmented_lagrangian::augmented_lagrangian;
+pub use penalty::{log_barrier, quadratic_penalty};
+pub use sensitivity::{parameter_sensitivity, shadow_prices, ParameterSensitivity, SensitivityReport, ShadowPrice};
+pub use sqp::sqp;
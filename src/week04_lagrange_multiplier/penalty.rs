@@ -0,0 +1,136 @@
+//! Exterior quadratic penalty and interior log-barrier methods.
+//!
+//! Both methods solve a sequence of unconstrained subproblems, warm-starting
+//! each from the previous solution. The penalty iterates approach the
+//! constrained optimum from outside the feasible region; the barrier iterates
+//! stay strictly inside it.
+
+use super::types::{ConstrainedProblem, ParameterSchedule, PathResult, SubproblemSolution};
+use super::unconstrained::bfgs;
+
+/// Gradient-norm tolerance for each subproblem.
+const INNER_TOL: f64 = 1e-6;
+
+/// Iteration limit for each subproblem.
+const INNER_MAX_ITER: usize = 500;
+
+/// Quadratic penalty function: f + (μ/2)(Σ h_i² + Σ max(g_j, 0)²).
+pub fn penalty_value(problem: &ConstrainedProblem, x: &[f64], mu: f64) -> f64 {
+    let eq: f64 = problem.equality_values(x).iter().map(|h| h * h).sum();
+    let ineq: f64 = problem.inequality_values(x).iter().map(|g| g.max(0.0).powi(2)).sum();
+    problem.objective_value(x) + 0.5 * mu * (eq + ineq)
+}
+
+/// Log-barrier function: f - t Σ ln(-g_j) + (1/2t) Σ h_i².
+///
+/// Equality constraints have no interior, so they are handled with a
+/// quadratic penalty whose weight grows as t shrinks. Returns `inf` outside
+/// the strict interior of the inequality constraints.
+pub fn barrier_value(problem: &ConstrainedProblem, x: &[f64], t: f64) -> f64 {
+    let g = problem.inequality_values(x);
+    if g.iter().any(|&gj| gj >= 0.0) {
+        return f64::INFINITY;
+    }
+    let log_barrier: f64 = g.iter().map(|gj| (-gj).ln()).sum();
+    let eq: f64 = problem.equality_values(x).iter().map(|h| h * h).sum();
+    problem.objective_value(x) - t * log_barrier + eq / (2.0 * t)
+}
+
+/// Solves `problem` with the exterior quadratic penalty method.
+///
+/// The schedule should list increasing penalty weights μ.
+pub fn quadratic_penalty(problem: &ConstrainedProblem, x0: &[f64], schedule: &ParameterSchedule) -> PathResult {
+    follow_path(problem, x0, schedule, &|x, mu| penalty_value(problem, x, mu))
+}
+
+/// Solves `problem` with the interior log-barrier method.
+///
+/// The schedule should list decreasing barrier weights t. Returns an error
+/// if `x0` is not strictly feasible for every inequality constraint, since
+/// the barrier is infinite there.
+pub fn log_barrier(
+    problem: &ConstrainedProblem,
+    x0: &[f64],
+    schedule: &ParameterSchedule,
+) -> Result<PathResult, String> {
+    if let Some(j) = problem.inequality_values(x0).iter().position(|&g| g >= 0.0 || g.is_nan()) {
+        return Err(format!("log_barrier needs a strictly feasible start, but inequality {} is not < 0", j));
+    }
+    Ok(follow_path(problem, x0, schedule, &|x, t| barrier_value(problem, x, t)))
+}
+
+fn follow_path(
+    problem: &ConstrainedProblem,
+    x0: &[f64],
+    schedule: &ParameterSchedule,
+    subproblem: &dyn Fn(&[f64], f64) -> f64,
+) -> PathResult {
+    let mut x = x0.to_vec();
+    let mut path = Vec::new();
+
+    for parameter in schedule.values() {
+        let inner = bfgs(&|z: &[f64]| subproblem(z, parameter), &x, INNER_TOL, INNER_MAX_ITER);
+        x = inner.x;
+        path.push(SubproblemSolution {
+            parameter,
+            x: x.clone(),
+            fx: problem.objective_value(&x),
+            constraint_violation: problem.constraint_violation(&x),
+            inner_iterations: inner.iterations,
+        });
+    }
+
+    PathResult {
+        fx: problem.objective_value(&x),
+        x,
+        path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// min (x - 2)^2 s.t. x - 1 <= 0, optimum at x = 1.
+    fn wall_problem() -> ConstrainedProblem<'static> {
+        ConstrainedProblem::new(|x| (x[0] - 2.0).powi(2)).with_inequality(|x| x[0] - 1.0)
+    }
+
+    #[test]
+    fn test_schedule_values() {
+        let schedule = ParameterSchedule::Geometric { initial: 1.0, factor: 10.0, steps: 3 };
+        assert_eq!(schedule.values(), vec![1.0, 10.0, 100.0]);
+        assert_eq!(ParameterSchedule::Values(vec![2.0, 4.0]).values(), vec![2.0, 4.0]);
+    }
+
+    #[test]
+    fn test_penalty_approaches_from_outside() {
+        let schedule = ParameterSchedule::Geometric { initial: 1.0, factor: 10.0, steps: 6 };
+        let result = quadratic_penalty(&wall_problem(), &[0.0], &schedule);
+
+        assert_eq!(result.path.len(), 6);
+        assert!(result.path.iter().all(|s| s.x[0] > 1.0));
+        // Analytic subproblem minimiser: x = 1 + 1 / (1 + μ/2)
+        for s in &result.path {
+            assert!((s.x[0] - (1.0 + 1.0 / (1.0 + s.parameter / 2.0))).abs() < 1e-4);
+        }
+        assert!((result.x[0] - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_barrier_approaches_from_inside() {
+        let schedule = ParameterSchedule::Geometric { initial: 1.0, factor: 0.1, steps: 5 };
+        let result = log_barrier(&wall_problem(), &[0.0], &schedule).unwrap();
+
+        assert!(result.path.iter().all(|s| s.x[0] < 1.0));
+        assert!(result.path.windows(2).all(|w| w[1].x[0] > w[0].x[0]));
+        assert!((result.x[0] - 1.0).abs() < 1e-3, "x={:?}", result.x);
+    }
+
+    #[test]
+    fn test_barrier_rejects_infeasible_start() {
+        let schedule = ParameterSchedule::Values(vec![1.0]);
+        assert!(log_barrier(&wall_problem(), &[2.0], &schedule).is_err());
+        assert!(log_barrier(&wall_problem(), &[1.0], &schedule).is_err());
+    }
+}
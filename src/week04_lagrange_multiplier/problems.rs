@@ -0,0 +1,119 @@
+//! EG551T Practical Problems - Constrained Optimization
+//!
+//! minimise (x - 2)^2 + (y - 1)^2 subject to x^2 + y^2 <= 1
+//! optimum on the unit circle at (2, 1) / sqrt(5)
+
+use crate::week04_lagrange_multiplier::types::{ConstrainedProblem, ParameterSchedule};
+
+#[cfg(feature = "plotting")]
+use crate::common::plot::{self, PlotConfig};
+#[cfg(feature = "plotting")]
+use crate::week04_lagrange_multiplier::augmented_lagrangian::augmented_lagrangian;
+#[cfg(feature = "plotting")]
+use crate::week04_lagrange_multiplier::penalty::{log_barrier, quadratic_penalty};
+#[cfg(feature = "plotting")]
+use crate::week04_lagrange_multiplier::types::AugLagConfig;
+
+/// The disc problem shared by the week 4 demos.
+pub fn disc_problem() -> ConstrainedProblem<'static> {
+    ConstrainedProblem::new(|x| (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2))
+        .with_inequality(|x| x[0] * x[0] + x[1] * x[1] - 1.0)
+}
+
+/// Increasing penalty weights μ = 1, 10, ..., 1e5.
+pub fn penalty_schedule() -> ParameterSchedule {
+    ParameterSchedule::Geometric { initial: 1.0, factor: 10.0, steps: 6 }
+}
+
+/// Decreasing barrier weights t = 1, 0.3, ..., ~2e-4.
+pub fn barrier_schedule() -> ParameterSchedule {
+    ParameterSchedule::Geometric { initial: 1.0, factor: 0.3, steps: 8 }
+}
+
+/// Plot the penalty and barrier iterate paths against the constraint boundary.
+#[cfg(feature = "plotting")]
+pub fn problem_penalty_barrier_paths(dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let problem = disc_problem();
+    let penalty = quadratic_penalty(&problem, &[0.0, 0.0], &penalty_schedule());
+    let barrier = log_barrier(&problem, &[0.0, 0.0], &barrier_schedule())?;
+
+    println!("  Penalty path (from outside):");
+    for s in &penalty.path {
+        println!(
+            "    μ = {:>8.1e}  x = ({:.6}, {:.6})  violation = {:.3e}",
+            s.parameter, s.x[0], s.x[1], s.constraint_violation
+        );
+    }
+    println!("  Barrier path (from inside):");
+    for s in &barrier.path {
+        let g = problem.inequality_values(&s.x)[0];
+        println!("    t = {:>8.1e}  x = ({:.6}, {:.6})  g(x) = {:.3e}", s.parameter, s.x[0], s.x[1], g);
+    }
+
+    let n = 200;
+    let circle: Vec<(f64, f64)> = (0..=n)
+        .map(|i| {
+            let theta = i as f64 * 2.0 * std::f64::consts::PI / n as f64;
+            (theta.cos(), theta.sin())
+        })
+        .collect();
+    let penalty_path: Vec<(f64, f64)> = penalty.path.iter().map(|s| (s.x[0], s.x[1])).collect();
+    let barrier_path: Vec<(f64, f64)> = barrier.path.iter().map(|s| (s.x[0], s.x[1])).collect();
+
+    let config = PlotConfig {
+        title: String::from("Penalty vs Barrier Iterates"),
+        x_label: String::from("x"),
+        y_label: String::from("y"),
+        width: 800,
+        height: 800,
+        ..PlotConfig::default()
+    }
+    .with_x_range(-1.2, 2.2)
+    .with_y_range(-1.2, 2.2);
+
+    let curves: Vec<(&str, &[(f64, f64)])> = vec![("x² + y² = 1", &circle)];
+    let trajectories: Vec<(&str, &[(f64, f64)])> = vec![
+        ("Quadratic penalty", &penalty_path),
+        ("Log barrier", &barrier_path),
+    ];
+    plot::trajectory_plot(&format!("{}/penalty_barrier_paths.png", dir), &curves, &trajectories, &config)?;
+
+    // Zoom on the optimum so the final approach is visible.
+    let opt = (2.0 / 5f64.sqrt(), 1.0 / 5f64.sqrt());
+    let zoom = PlotConfig {
+        title: String::from("Penalty vs Barrier Iterates (zoom)"),
+        ..config
+    }
+    .with_x_range(opt.0 - 0.15, opt.0 + 0.15)
+    .with_y_range(opt.1 - 0.15, opt.1 + 0.15);
+    plot::trajectory_plot(&format!("{}/penalty_barrier_zoom.png", dir), &curves, &trajectories, &zoom)?;
+
+    Ok(())
+}
+
+/// Plot contours, the constraint circle and the parallel gradients at the optimum
+/// of the equality form: minimise (x - 2)^2 + (y - 1)^2 subject to x^2 + y^2 = 1.
+#[cfg(feature = "plotting")]
+pub fn problem_lagrange_geometry(dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let f = |x: f64, y: f64| (x - 2.0).powi(2) + (y - 1.0).powi(2);
+    let h = |x: f64, y: f64| x * x + y * y - 1.0;
+
+    let problem = ConstrainedProblem::new(|x| f(x[0], x[1])).with_equality(|x| h(x[0], x[1]));
+    let result = augmented_lagrangian(&problem, &[0.0, 0.0], &AugLagConfig::default());
+    let optimum = (result.x[0], result.x[1]);
+
+    println!("  Optimum x* = ({:.6}, {:.6}),  λ = {:.6}", optimum.0, optimum.1, result.equality_multipliers[0]);
+
+    let config = PlotConfig {
+        title: String::from("Lagrange Multiplier Geometry"),
+        x_label: String::from("x"),
+        y_label: String::from("y"),
+        width: 800,
+        height: 800,
+        ..PlotConfig::default()
+    }
+    .with_x_range(-1.5, 2.5)
+    .with_y_range(-1.5, 2.5);
+
+    plot::lagrange_geometry_plot(&format!("{}/lagrange_geometry.png", dir), &f, &h, optimum, 12, &config)
+}
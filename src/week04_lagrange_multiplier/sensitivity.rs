@@ -0,0 +1,184 @@
+//! Sensitivity analysis and shadow prices.
+//!
+//! With L = f + Σ λ_i h_i + Σ ν_j g_j, relaxing a constraint to c(x) = b (or
+//! c(x) <= b) changes the optimal objective at the rate d f*/d b = -multiplier.
+//! The functions here compare that first-order prediction with the change
+//! observed by re-solving the perturbed problem.
+
+use super::augmented_lagrangian::augmented_lagrangian;
+use super::types::{AugLagConfig, AugLagResult, ConstrainedProblem, ConstraintKind};
+
+/// Shadow price of a single constraint.
+#[derive(Debug, Clone)]
+pub struct ShadowPrice {
+    /// Equality or inequality constraint.
+    pub kind: ConstraintKind,
+    /// Index within its constraint family.
+    pub index: usize,
+    /// Lagrange multiplier at the baseline solution.
+    pub multiplier: f64,
+    /// Predicted rate of change of the optimum, d f*/d b = -multiplier.
+    pub shadow_price: f64,
+    /// Right-hand-side perturbation δ used for re-solving.
+    pub perturbation: f64,
+    /// Observed change f*(b + δ) - f*(b).
+    pub objective_change: f64,
+    /// Central-difference rate (f*(b + δ) - f*(b - δ)) / 2δ from re-solving.
+    pub finite_difference_price: f64,
+}
+
+impl ShadowPrice {
+    /// First-order estimate of the change in f* when the right-hand side moves by `delta`.
+    pub fn predicted_change(&self, delta: f64) -> f64 {
+        self.shadow_price * delta
+    }
+}
+
+/// Shadow prices for every constraint of a problem.
+#[derive(Debug, Clone)]
+pub struct SensitivityReport {
+    /// Solution of the unperturbed problem.
+    pub baseline: AugLagResult,
+    /// One entry per constraint, equalities first.
+    pub shadow_prices: Vec<ShadowPrice>,
+}
+
+/// First-order sensitivity of the optimum to a scalar problem parameter p.
+#[derive(Debug, Clone)]
+pub struct ParameterSensitivity {
+    /// Parameter value at which the sensitivity was evaluated.
+    pub parameter: f64,
+    /// Optimal point x*(p).
+    pub x: Vec<f64>,
+    /// Optimal objective f*(p).
+    pub fx: f64,
+    /// Central-difference derivative d x*/d p from re-solving.
+    pub dx_dp: Vec<f64>,
+    /// Central-difference derivative d f*/d p from re-solving.
+    pub dobjective_dp: f64,
+    /// Envelope-theorem estimate ∂L/∂p at (x*, λ*, ν*), needing no re-solve.
+    pub envelope_estimate: f64,
+}
+
+impl ParameterSensitivity {
+    /// First-order estimate of the optimal point at p + `dp`.
+    pub fn predicted_x(&self, dp: f64) -> Vec<f64> {
+        self.x.iter().zip(&self.dx_dp).map(|(x, d)| x + d * dp).collect()
+    }
+
+    /// First-order estimate of the optimal objective at p + `dp`.
+    pub fn predicted_objective(&self, dp: f64) -> f64 {
+        self.fx + self.envelope_estimate * dp
+    }
+}
+
+/// Computes the shadow price of every constraint of `problem`.
+///
+/// The baseline is solved from `x0`; each constraint's right-hand side is then
+/// shifted by ±`delta` and re-solved, warm-started from the baseline optimum.
+pub fn shadow_prices(problem: &ConstrainedProblem, x0: &[f64], config: &AugLagConfig, delta: f64) -> SensitivityReport {
+    let baseline = augmented_lagrangian(problem, x0, config);
+
+    let constraints = (0..problem.equalities.len())
+        .map(|i| (ConstraintKind::Equality, i, baseline.equality_multipliers[i]))
+        .chain((0..problem.inequalities.len()).map(|j| (ConstraintKind::Inequality, j, baseline.inequality_multipliers[j])));
+
+    let shadow_prices = constraints
+        .map(|(kind, index, multiplier)| {
+            let f_plus = augmented_lagrangian(&problem.perturbed(kind, index, delta), &baseline.x, config).fx;
+            let f_minus = augmented_lagrangian(&problem.perturbed(kind, index, -delta), &baseline.x, config).fx;
+            ShadowPrice {
+                kind,
+                index,
+                multiplier,
+                shadow_price: -multiplier,
+                perturbation: delta,
+                objective_change: f_plus - baseline.fx,
+                finite_difference_price: (f_plus - f_minus) / (2.0 * delta),
+            }
+        })
+        .collect();
+
+    SensitivityReport { baseline, shadow_prices }
+}
+
+/// Estimates how the optimum of a parametrised problem moves with its parameter.
+///
+/// # Arguments
+/// * `build` - Constructs the problem for a given parameter value p
+/// * `p` - Parameter value to linearise about
+/// * `x0` - Starting point for the baseline solve
+/// * `config` - Augmented Lagrangian settings used for every solve
+/// * `delta` - Parameter step for the central differences
+pub fn parameter_sensitivity<'a>(
+    build: &dyn Fn(f64) -> ConstrainedProblem<'a>,
+    p: f64,
+    x0: &[f64],
+    config: &AugLagConfig,
+    delta: f64,
+) -> ParameterSensitivity {
+    let problem = build(p);
+    let baseline = augmented_lagrangian(&problem, x0, config);
+    let plus = augmented_lagrangian(&build(p + delta), &baseline.x, config);
+    let minus = augmented_lagrangian(&build(p - delta), &baseline.x, config);
+
+    // Envelope theorem: d f*/dp = ∂L/∂p with x and the multipliers held fixed.
+    let lagrangian_at = |q: f64| {
+        build(q).lagrangian(&baseline.x, &baseline.equality_multipliers, &baseline.inequality_multipliers)
+    };
+    let envelope_estimate = (lagrangian_at(p + delta) - lagrangian_at(p - delta)) / (2.0 * delta);
+
+    ParameterSensitivity {
+        parameter: p,
+        dx_dp: plus.x.iter().zip(&minus.x).map(|(a, b)| (a - b) / (2.0 * delta)).collect(),
+        dobjective_dp: (plus.fx - minus.fx) / (2.0 * delta),
+        envelope_estimate,
+        fx: baseline.fx,
+        x: baseline.x,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Maximise the area xy of a rectangle whose perimeter is at most `p`.
+    fn fence(p: f64) -> ConstrainedProblem<'static> {
+        ConstrainedProblem::new(|x| -x[0] * x[1]).with_inequality(move |x| 2.0 * x[0] + 2.0 * x[1] - p)
+    }
+
+    #[test]
+    fn test_shadow_price_matches_finite_difference() {
+        // f* = -p²/16, so d f*/dp = -p/8 = -2.5 at p = 20.
+        let report = shadow_prices(&fence(20.0), &[1.0, 1.0], &AugLagConfig::default(), 1e-3);
+        let price = &report.shadow_prices[0];
+
+        assert_eq!(price.kind, ConstraintKind::Inequality);
+        assert!((price.shadow_price + 2.5).abs() < 1e-3, "price={}", price.shadow_price);
+        assert!((price.finite_difference_price - price.shadow_price).abs() < 1e-3);
+        assert!((price.predicted_change(1.0) + 2.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_equality_shadow_price() {
+        // min x² + y² s.t. x + y = b: f* = b²/2, d f*/db = b = 1.
+        let problem = ConstrainedProblem::new(|x| x[0] * x[0] + x[1] * x[1]).with_equality(|x| x[0] + x[1] - 1.0);
+        let report = shadow_prices(&problem, &[0.0, 0.0], &AugLagConfig::default(), 1e-3);
+        let price = &report.shadow_prices[0];
+
+        assert!((price.shadow_price - 1.0).abs() < 1e-3);
+        assert!((price.objective_change - (1.001f64.powi(2) - 1.0) / 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_parameter_sensitivity_fence() {
+        let sens = parameter_sensitivity(&fence, 20.0, &[1.0, 1.0], &AugLagConfig::default(), 1e-3);
+
+        // x* = y* = p/4, so dx*/dp = 0.25.
+        assert!((sens.dx_dp[0] - 0.25).abs() < 1e-3);
+        assert!((sens.dx_dp[1] - 0.25).abs() < 1e-3);
+        assert!((sens.envelope_estimate + 2.5).abs() < 1e-3);
+        assert!((sens.dobjective_dp - sens.envelope_estimate).abs() < 1e-3);
+        assert!((sens.predicted_x(1.0)[0] - 5.25).abs() < 1e-3);
+    }
+}
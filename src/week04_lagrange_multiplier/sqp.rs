@@ -0,0 +1,324 @@
+//! Sequential quadratic programming (SQP) for smooth constrained problems.
+//!
+//! Each iteration solves the QP subproblem
+//!
+//! minimise ½ dᵀB d + ∇f(x)ᵀd subject to h + A_h d = 0, g + A_g d <= 0
+//!
+//! where B is a damped-BFGS approximation to the Hessian of the Lagrangian.
+//! The step is globalised with a backtracking line search on the ℓ1 merit
+//! function φ(x) = f(x) + ρ (Σ |h_i(x)| + Σ max(g_j(x), 0)). Derivatives are
+//! taken with central differences, so the solver accepts the same
+//! [`ConstrainedProblem`] definitions as the augmented Lagrangian method.
+
+use ndarray::{s, Array1, Array2};
+
+use super::types::{ConstrainedProblem, ScalarFn, SqpConfig, SqpIteration, SqpResult, SqpStopReason};
+use super::unconstrained::numerical_gradient;
+use crate::common::linalg::solve;
+
+/// Armijo sufficient-decrease constant for the merit line search.
+const ARMIJO_C: f64 = 1e-4;
+
+/// Maximum number of step halvings in the merit line search.
+const MAX_BACKTRACKS: usize = 40;
+
+/// Tolerance for multiplier signs and constraint activity in the QP.
+const QP_TOL: f64 = 1e-10;
+
+/// Function values and first derivatives of a problem at a point.
+struct Linearisation {
+    f: f64,
+    grad_f: Array1<f64>,
+    h: Array1<f64>,
+    jac_h: Array2<f64>,
+    g: Array1<f64>,
+    jac_g: Array2<f64>,
+}
+
+impl Linearisation {
+    fn at(problem: &ConstrainedProblem, x: &[f64]) -> Self {
+        let n = x.len();
+        let jacobian = |constraints: &[ScalarFn]| {
+            let mut jac = Array2::zeros((constraints.len(), n));
+            for (i, c) in constraints.iter().enumerate() {
+                jac.row_mut(i).assign(&Array1::from(numerical_gradient(c.as_ref(), x)));
+            }
+            jac
+        };
+        Self {
+            f: problem.objective_value(x),
+            grad_f: Array1::from(numerical_gradient(problem.objective.as_ref(), x)),
+            h: Array1::from(problem.equality_values(x)),
+            jac_h: jacobian(&problem.equalities),
+            g: Array1::from(problem.inequality_values(x)),
+            jac_g: jacobian(&problem.inequalities),
+        }
+    }
+
+    /// Gradient of the Lagrangian ∇f + A_hᵀλ + A_gᵀν.
+    fn lagrangian_gradient(&self, lambda: &Array1<f64>, nu: &Array1<f64>) -> Array1<f64> {
+        &self.grad_f + &self.jac_h.t().dot(lambda) + &self.jac_g.t().dot(nu)
+    }
+
+    /// ℓ1 constraint violation Σ |h_i| + Σ max(g_j, 0).
+    fn l1_violation(&self) -> f64 {
+        self.h.iter().map(|v| v.abs()).sum::<f64>() + self.g.iter().map(|v| v.max(0.0)).sum::<f64>()
+    }
+
+    /// Max-norm constraint violation.
+    fn max_violation(&self) -> f64 {
+        self.h.iter().map(|v| v.abs()).chain(self.g.iter().map(|v| v.max(0.0))).fold(0.0, f64::max)
+    }
+}
+
+/// Solution of one QP subproblem.
+struct QpSolution {
+    d: Array1<f64>,
+    lambda: Array1<f64>,
+    nu: Array1<f64>,
+}
+
+/// Solves the QP subproblem with a primal–dual active-set iteration.
+///
+/// Starts from the inequalities that are active or violated at d = 0, then
+/// repeatedly drops the working constraint with the most negative multiplier
+/// or adds the most violated linearised inequality.
+fn solve_qp(b: &Array2<f64>, lin: &Linearisation, max_iter: usize) -> Option<QpSolution> {
+    let n = b.nrows();
+    let n_eq = lin.h.len();
+    let n_ineq = lin.g.len();
+    let mut active: Vec<bool> = lin.g.iter().map(|&g| g >= 0.0).collect();
+
+    for _ in 0..max_iter {
+        let working: Vec<usize> = (0..n_ineq).filter(|&j| active[j]).collect();
+        let m = n_eq + working.len();
+
+        // [B Aᵀ; A 0] [d; μ] = [-∇f; -c]
+        let mut kkt = Array2::zeros((n + m, n + m));
+        let mut rhs = Array1::zeros(n + m);
+        kkt.slice_mut(s![..n, ..n]).assign(b);
+        rhs.slice_mut(s![..n]).assign(&(-&lin.grad_f));
+        let rows = (0..n_eq)
+            .map(|i| (lin.jac_h.row(i), lin.h[i]))
+            .chain(working.iter().map(|&j| (lin.jac_g.row(j), lin.g[j])));
+        for (k, (row, value)) in rows.enumerate() {
+            kkt.slice_mut(s![n + k, ..n]).assign(&row);
+            kkt.slice_mut(s![..n, n + k]).assign(&row);
+            rhs[n + k] = -value;
+        }
+
+        let sol = solve(&kkt, &rhs)?;
+        let d = sol.slice(s![..n]).to_owned();
+        let lambda = sol.slice(s![n..n + n_eq]).to_owned();
+        let mut nu = Array1::zeros(n_ineq);
+        for (k, &j) in working.iter().enumerate() {
+            nu[j] = sol[n + n_eq + k];
+        }
+
+        let most_negative = working
+            .iter()
+            .copied()
+            .filter(|&j| nu[j] < -QP_TOL)
+            .min_by(|&a, &b| nu[a].total_cmp(&nu[b]));
+        if let Some(j) = most_negative {
+            active[j] = false;
+            continue;
+        }
+
+        let most_violated = (0..n_ineq)
+            .filter(|&j| !active[j])
+            .map(|j| (j, lin.g[j] + lin.jac_g.row(j).dot(&d)))
+            .filter(|&(_, v)| v > QP_TOL)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((j, _)) = most_violated {
+            active[j] = true;
+            continue;
+        }
+
+        return Some(QpSolution { d, lambda, nu });
+    }
+
+    None
+}
+
+/// Solves `problem` with SQP starting from `x0`.
+pub fn sqp(problem: &ConstrainedProblem, x0: &[f64], config: &SqpConfig) -> SqpResult {
+    let n = x0.len();
+    let mut x = Array1::from(x0.to_vec());
+    let mut lin = Linearisation::at(problem, x.as_slice().unwrap());
+    let mut lambda = Array1::zeros(problem.equalities.len());
+    let mut nu = Array1::zeros(problem.inequalities.len());
+    let mut b = Array2::<f64>::eye(n);
+    let mut rho = 0.0_f64;
+    let mut history = Vec::new();
+    let mut stop_reason = SqpStopReason::MaxIterations;
+    let mut iterations = 0;
+    let kkt_met = |lin: &Linearisation, lambda: &Array1<f64>, nu: &Array1<f64>| {
+        max_norm(&lin.lagrangian_gradient(lambda, nu)) < config.tol && lin.max_violation() < config.tol
+    };
+
+    for k in 1..=config.max_iter {
+        if k > 1 && kkt_met(&lin, &lambda, &nu) {
+            stop_reason = SqpStopReason::Converged;
+            break;
+        }
+
+        let Some(qp) = solve_qp(&b, &lin, config.qp_max_iter) else {
+            stop_reason = SqpStopReason::QpFailed;
+            break;
+        };
+        iterations = k;
+
+        // The merit penalty must exceed every multiplier for d to be a descent direction.
+        let max_multiplier = qp.lambda.iter().chain(qp.nu.iter()).fold(0.0_f64, |acc, v| acc.max(v.abs()));
+        rho = rho.max(1.1 * max_multiplier + 1e-6);
+
+        let merit = |l: &Linearisation| l.f + rho * l.l1_violation();
+        let phi = merit(&lin);
+        let mut slope = lin.grad_f.dot(&qp.d) - rho * lin.l1_violation();
+        if slope >= 0.0 {
+            slope = -qp.d.dot(&b.dot(&qp.d));
+        }
+
+        let mut alpha = 1.0;
+        let mut next = None;
+        for _ in 0..MAX_BACKTRACKS {
+            let candidate = &x + &(alpha * &qp.d);
+            let lin_candidate = Linearisation::at(problem, candidate.as_slice().unwrap());
+            if merit(&lin_candidate) <= phi + ARMIJO_C * alpha * slope {
+                next = Some((candidate, lin_candidate));
+                break;
+            }
+            alpha *= 0.5;
+        }
+        let Some((x_new, lin_new)) = next else {
+            stop_reason = SqpStopReason::LineSearchFailed;
+            break;
+        };
+
+        // Multipliers are only meaningful once a QP has been solved.
+        lambda = qp.lambda;
+        nu = qp.nu;
+
+        // Damped BFGS update of the Lagrangian Hessian (Powell).
+        let step = &x_new - &x;
+        let mut y = &lin_new.lagrangian_gradient(&lambda, &nu) - &lin.lagrangian_gradient(&lambda, &nu);
+        let bs = b.dot(&step);
+        let sbs = step.dot(&bs);
+        let sy = step.dot(&y);
+        if sbs > 0.0 {
+            if sy < 0.2 * sbs {
+                let theta = 0.8 * sbs / (sbs - sy);
+                y = theta * &y + (1.0 - theta) * &bs;
+            }
+            let sy = step.dot(&y);
+            for r in 0..n {
+                for c in 0..n {
+                    b[[r, c]] += y[r] * y[c] / sy - bs[r] * bs[c] / sbs;
+                }
+            }
+        }
+
+        x = x_new;
+        lin = lin_new;
+
+        history.push(SqpIteration {
+            iteration: k,
+            x: x.to_vec(),
+            fx: lin.f,
+            constraint_violation: lin.max_violation(),
+            kkt_residual: max_norm(&lin.lagrangian_gradient(&lambda, &nu)),
+            step_norm: qp.d.dot(&qp.d).sqrt(),
+            step_length: alpha,
+            merit_penalty: rho,
+        });
+    }
+
+    // The last allowed iteration may have reached the tolerance.
+    if stop_reason == SqpStopReason::MaxIterations && iterations > 0 && kkt_met(&lin, &lambda, &nu) {
+        stop_reason = SqpStopReason::Converged;
+    }
+
+    SqpResult {
+        x: x.to_vec(),
+        fx: lin.f,
+        equality_multipliers: lambda.to_vec(),
+        inequality_multipliers: nu.to_vec(),
+        iterations,
+        converged: stop_reason == SqpStopReason::Converged,
+        stop_reason,
+        history,
+    }
+}
+
+fn max_norm(v: &Array1<f64>) -> f64 {
+    v.iter().fold(0.0, |acc, x| acc.max(x.abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::week04_lagrange_multiplier::augmented_lagrangian::augmented_lagrangian;
+    use crate::week04_lagrange_multiplier::types::AugLagConfig;
+
+    #[test]
+    fn test_equality_only() {
+        let problem = ConstrainedProblem::new(|x| x[0] * x[0] + x[1] * x[1]).with_equality(|x| x[0] + x[1] - 1.0);
+        let result = sqp(&problem, &[0.0, 0.0], &SqpConfig::default());
+
+        assert!(result.converged);
+        assert!((result.x[0] - 0.5).abs() < 1e-6);
+        assert!((result.x[1] - 0.5).abs() < 1e-6);
+        assert!((result.equality_multipliers[0] + 1.0).abs() < 1e-6);
+        assert_eq!(result.stop_reason, SqpStopReason::Converged);
+
+        // Reaching the tolerance on the last allowed iteration still counts.
+        let limited = sqp(&problem, &[0.0, 0.0], &SqpConfig::default().with_max_iter(result.iterations));
+        assert_eq!(limited.stop_reason, SqpStopReason::Converged);
+        assert!(limited.converged);
+        let short = sqp(&problem, &[3.0, -7.0], &SqpConfig::default().with_max_iter(1));
+        assert_eq!(short.stop_reason, SqpStopReason::MaxIterations);
+    }
+
+    #[test]
+    fn test_matches_augmented_lagrangian() {
+        let problem = ConstrainedProblem::new(|x| (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2))
+            .with_equality(|x| x[0] + x[1] - 2.0)
+            .with_inequality(|x| x[0] * x[0] - x[1]);
+        let result = sqp(&problem, &[0.0, 0.0], &SqpConfig::default());
+        let reference = augmented_lagrangian(&problem, &[0.0, 0.0], &AugLagConfig::default());
+
+        assert!(result.converged);
+        for (a, b) in result.x.iter().zip(&reference.x) {
+            assert!((a - b).abs() < 1e-4, "sqp={:?} al={:?}", result.x, reference.x);
+        }
+        assert!((result.equality_multipliers[0] - 2.0 / 3.0).abs() < 1e-5);
+        assert!((result.inequality_multipliers[0] - 2.0 / 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_nonlinear_inequality_from_infeasible_start() {
+        // Disc problem: optimum (2, 1)/√5 on the unit circle.
+        let problem = ConstrainedProblem::new(|x| (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2))
+            .with_inequality(|x| x[0] * x[0] + x[1] * x[1] - 1.0);
+        let result = sqp(&problem, &[2.0, 2.0], &SqpConfig::default());
+
+        assert!(result.converged);
+        assert!((result.x[0] - 2.0 / 5f64.sqrt()).abs() < 1e-6);
+        assert!((result.x[1] - 1.0 / 5f64.sqrt()).abs() < 1e-6);
+        assert!(result.history.last().unwrap().constraint_violation < 1e-8);
+    }
+
+    #[test]
+    fn test_history_csv() {
+        let problem = ConstrainedProblem::new(|x| x[0] * x[0] + x[1] * x[1]).with_equality(|x| x[0] + x[1] - 1.0);
+        let result = sqp(&problem, &[0.0, 0.0], &SqpConfig::default());
+
+        let path = "/tmp/test_sqp_history.csv";
+        result.write_history_csv(path).unwrap();
+        let contents = std::fs::read_to_string(path).unwrap();
+        assert!(contents.starts_with("iteration,x0,x1,f,violation,kkt_residual"));
+        assert_eq!(contents.lines().count(), result.history.len() + 1);
+        std::fs::remove_file(path).unwrap();
+    }
+}
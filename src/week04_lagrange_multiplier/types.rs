@@ -0,0 +1,379 @@
+//! Core data structures for constrained optimization.
+
+use std::io;
+use std::path::Path;
+
+use crate::common::csv::write_csv;
+
+/// A scalar function of a point in R^n.
+pub type ScalarFn<'a> = Box<dyn Fn(&[f64]) -> f64 + 'a>;
+
+/// Which family a constraint belongs to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConstraintKind {
+    /// h(x) = 0
+    Equality,
+    /// g(x) <= 0
+    Inequality,
+}
+
+/// A smooth constrained minimisation problem:
+///
+/// minimise f(x) subject to h_i(x) = 0 and g_j(x) <= 0.
+pub struct ConstrainedProblem<'a> {
+    /// Objective function f(x).
+    pub objective: ScalarFn<'a>,
+    /// Equality constraints h_i(x) = 0.
+    pub equalities: Vec<ScalarFn<'a>>,
+    /// Inequality constraints g_j(x) <= 0.
+    pub inequalities: Vec<ScalarFn<'a>>,
+}
+
+impl<'a> ConstrainedProblem<'a> {
+    /// Create an unconstrained problem with objective `f`.
+    pub fn new(f: impl Fn(&[f64]) -> f64 + 'a) -> Self {
+        Self {
+            objective: Box::new(f),
+            equalities: Vec::new(),
+            inequalities: Vec::new(),
+        }
+    }
+
+    /// Add an equality constraint h(x) = 0.
+    pub fn with_equality(mut self, h: impl Fn(&[f64]) -> f64 + 'a) -> Self {
+        self.equalities.push(Box::new(h));
+        self
+    }
+
+    /// Add an inequality constraint g(x) <= 0.
+    pub fn with_inequality(mut self, g: impl Fn(&[f64]) -> f64 + 'a) -> Self {
+        self.inequalities.push(Box::new(g));
+        self
+    }
+
+    /// Evaluate the objective at `x`.
+    pub fn objective_value(&self, x: &[f64]) -> f64 {
+        (self.objective)(x)
+    }
+
+    /// Evaluate every equality constraint at `x`.
+    pub fn equality_values(&self, x: &[f64]) -> Vec<f64> {
+        self.equalities.iter().map(|h| h(x)).collect()
+    }
+
+    /// Evaluate every inequality constraint at `x`.
+    pub fn inequality_values(&self, x: &[f64]) -> Vec<f64> {
+        self.inequalities.iter().map(|g| g(x)).collect()
+    }
+
+    /// Lagrangian L(x, λ, ν) = f(x) + Σ λ_i h_i(x) + Σ ν_j g_j(x).
+    pub fn lagrangian(&self, x: &[f64], lambda: &[f64], nu: &[f64]) -> f64 {
+        let eq: f64 = self.equalities.iter().zip(lambda).map(|(h, l)| l * h(x)).sum();
+        let ineq: f64 = self.inequalities.iter().zip(nu).map(|(g, v)| v * g(x)).sum();
+        self.objective_value(x) + eq + ineq
+    }
+
+    /// Borrow this problem with the right-hand side of one constraint shifted by `delta`.
+    ///
+    /// The constraint c(x) = 0 (or c(x) <= 0) becomes c(x) = delta (or c(x) <= delta).
+    pub fn perturbed(&self, kind: ConstraintKind, index: usize, delta: f64) -> ConstrainedProblem<'_> {
+        let offset = |k: ConstraintKind, i: usize| if k == kind && i == index { delta } else { 0.0 };
+        ConstrainedProblem {
+            objective: Box::new(|x: &[f64]| (self.objective)(x)),
+            equalities: self
+                .equalities
+                .iter()
+                .enumerate()
+                .map(|(i, h)| {
+                    let d = offset(ConstraintKind::Equality, i);
+                    Box::new(move |x: &[f64]| h(x) - d) as ScalarFn<'_>
+                })
+                .collect(),
+            inequalities: self
+                .inequalities
+                .iter()
+                .enumerate()
+                .map(|(j, g)| {
+                    let d = offset(ConstraintKind::Inequality, j);
+                    Box::new(move |x: &[f64]| g(x) - d) as ScalarFn<'_>
+                })
+                .collect(),
+        }
+    }
+
+    /// Largest constraint violation at `x`: max(|h_i(x)|, max(g_j(x), 0)).
+    pub fn constraint_violation(&self, x: &[f64]) -> f64 {
+        let eq = self.equalities.iter().map(|h| h(x).abs());
+        let ineq = self.inequalities.iter().map(|g| g(x).max(0.0));
+        eq.chain(ineq).fold(0.0, f64::max)
+    }
+}
+
+/// One outer iteration of the augmented Lagrangian method.
+#[derive(Debug, Clone)]
+pub struct OuterIteration {
+    /// Outer iteration number (1-based).
+    pub iteration: usize,
+    /// Inner minimiser solution.
+    pub x: Vec<f64>,
+    /// Objective value f(x).
+    pub fx: f64,
+    /// Constraint violation after the inner solve.
+    pub constraint_violation: f64,
+    /// Penalty parameter used for the inner solve.
+    pub penalty: f64,
+    /// Iterations taken by the inner minimiser.
+    pub inner_iterations: usize,
+}
+
+/// Result from the augmented Lagrangian method.
+#[derive(Debug, Clone)]
+pub struct AugLagResult {
+    /// Final point.
+    pub x: Vec<f64>,
+    /// Objective value f(x).
+    pub fx: f64,
+    /// Multiplier estimates for the equality constraints.
+    pub equality_multipliers: Vec<f64>,
+    /// Multiplier estimates for the inequality constraints (all >= 0).
+    pub inequality_multipliers: Vec<f64>,
+    /// Per-outer-iteration progress.
+    pub history: Vec<OuterIteration>,
+    /// Whether the constraint tolerance was met.
+    pub converged: bool,
+}
+
+/// Result from an unconstrained minimiser.
+#[derive(Debug, Clone)]
+pub struct UnconstrainedResult {
+    /// Final point.
+    pub x: Vec<f64>,
+    /// Function value at x.
+    pub fx: f64,
+    /// Euclidean norm of the gradient at x.
+    pub grad_norm: f64,
+    /// Number of iterations performed.
+    pub iterations: usize,
+}
+
+/// Configuration for the augmented Lagrangian method.
+#[derive(Debug, Clone)]
+pub struct AugLagConfig {
+    /// Penalty parameter for the first outer iteration.
+    pub initial_penalty: f64,
+    /// Factor applied to the penalty when the violation stalls.
+    pub penalty_growth: f64,
+    /// Upper limit on the penalty parameter.
+    pub max_penalty: f64,
+    /// Stop when the constraint violation falls below this value.
+    pub tol: f64,
+    /// Maximum number of outer iterations.
+    pub max_outer: usize,
+    /// Gradient-norm tolerance for the inner minimiser.
+    pub inner_tol: f64,
+    /// Iteration limit for the inner minimiser.
+    pub inner_max_iter: usize,
+}
+
+impl Default for AugLagConfig {
+    fn default() -> Self {
+        Self {
+            initial_penalty: 10.0,
+            penalty_growth: 10.0,
+            max_penalty: 1e8,
+            tol: 1e-6,
+            max_outer: 50,
+            inner_tol: 1e-6,
+            inner_max_iter: 500,
+        }
+    }
+}
+
+impl AugLagConfig {
+    /// Set the initial penalty parameter.
+    pub fn with_initial_penalty(mut self, penalty: f64) -> Self {
+        self.initial_penalty = penalty;
+        self
+    }
+
+    /// Set the constraint violation tolerance.
+    pub fn with_tol(mut self, tol: f64) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    /// Set the maximum number of outer iterations.
+    pub fn with_max_outer(mut self, max_outer: usize) -> Self {
+        self.max_outer = max_outer;
+        self
+    }
+}
+
+/// Sequence of penalty or barrier parameters, one per subproblem.
+#[derive(Debug, Clone)]
+pub enum ParameterSchedule {
+    /// `initial * factor^k` for k = 0, 1, ..., steps - 1.
+    Geometric { initial: f64, factor: f64, steps: usize },
+    /// An explicit list of parameter values.
+    Values(Vec<f64>),
+}
+
+impl ParameterSchedule {
+    /// Expand the schedule into its list of parameter values.
+    pub fn values(&self) -> Vec<f64> {
+        match self {
+            ParameterSchedule::Geometric { initial, factor, steps } => {
+                (0..*steps).map(|k| initial * factor.powi(k as i32)).collect()
+            }
+            ParameterSchedule::Values(values) => values.clone(),
+        }
+    }
+}
+
+/// Solution of one penalty or barrier subproblem.
+#[derive(Debug, Clone)]
+pub struct SubproblemSolution {
+    /// Penalty weight μ or barrier weight t used for this subproblem.
+    pub parameter: f64,
+    /// Minimiser of the subproblem.
+    pub x: Vec<f64>,
+    /// Objective value f(x) of the original problem.
+    pub fx: f64,
+    /// Constraint violation of the original problem at x.
+    pub constraint_violation: f64,
+    /// Iterations taken by the inner minimiser.
+    pub inner_iterations: usize,
+}
+
+/// Result from a penalty or barrier method.
+#[derive(Debug, Clone)]
+pub struct PathResult {
+    /// Subproblem solutions in schedule order.
+    pub path: Vec<SubproblemSolution>,
+    /// Final point (last subproblem solution).
+    pub x: Vec<f64>,
+    /// Objective value f(x).
+    pub fx: f64,
+}
+
+/// Configuration for the SQP solver.
+#[derive(Debug, Clone)]
+pub struct SqpConfig {
+    /// Maximum number of SQP iterations.
+    pub max_iter: usize,
+    /// Stop when both the Lagrangian gradient and the constraint violation fall below this value.
+    pub tol: f64,
+    /// Iteration limit for the active-set QP subproblem solver.
+    pub qp_max_iter: usize,
+}
+
+impl Default for SqpConfig {
+    fn default() -> Self {
+        Self {
+            max_iter: 100,
+            tol: 1e-8,
+            qp_max_iter: 50,
+        }
+    }
+}
+
+impl SqpConfig {
+    /// Set the maximum number of iterations.
+    pub fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    /// Set the convergence tolerance.
+    pub fn with_tol(mut self, tol: f64) -> Self {
+        self.tol = tol;
+        self
+    }
+}
+
+/// One iteration of the SQP solver.
+#[derive(Debug, Clone)]
+pub struct SqpIteration {
+    /// Iteration number (1-based).
+    pub iteration: usize,
+    /// Point after the step.
+    pub x: Vec<f64>,
+    /// Objective value f(x).
+    pub fx: f64,
+    /// Constraint violation at x.
+    pub constraint_violation: f64,
+    /// Max-norm of the Lagrangian gradient at x.
+    pub kkt_residual: f64,
+    /// Norm of the full QP step.
+    pub step_norm: f64,
+    /// Line-search step length α accepted by the merit function.
+    pub step_length: f64,
+    /// Penalty weight ρ of the ℓ1 merit function.
+    pub merit_penalty: f64,
+}
+
+/// Why the SQP solver stopped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SqpStopReason {
+    /// The KKT residual and constraint violation fell below the tolerance.
+    Converged,
+    /// The QP subproblem had a singular KKT system or hit its iteration limit.
+    QpFailed,
+    /// No step along the QP direction reduced the merit function enough.
+    LineSearchFailed,
+    /// The iteration limit was reached first.
+    MaxIterations,
+}
+
+/// Result from the SQP solver.
+#[derive(Debug, Clone)]
+pub struct SqpResult {
+    /// Final point.
+    pub x: Vec<f64>,
+    /// Objective value f(x).
+    pub fx: f64,
+    /// Multiplier estimates for the equality constraints.
+    pub equality_multipliers: Vec<f64>,
+    /// Multiplier estimates for the inequality constraints (all >= 0).
+    pub inequality_multipliers: Vec<f64>,
+    /// Number of iterations performed.
+    pub iterations: usize,
+    /// Whether the KKT tolerance was met.
+    pub converged: bool,
+    pub stop_reason: SqpStopReason,
+    /// Per-iteration log.
+    pub history: Vec<SqpIteration>,
+}
+
+impl SqpResult {
+    /// Write the iteration log to a CSV file.
+    pub fn write_history_csv<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let n = self.x.len();
+        let mut headers = vec![String::from("iteration")];
+        headers.extend((0..n).map(|i| format!("x{i}")));
+        headers.extend(
+            ["f", "violation", "kkt_residual", "step_norm", "step_length", "merit_penalty"].map(String::from),
+        );
+        let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+
+        let rows: Vec<Vec<f64>> = self
+            .history
+            .iter()
+            .map(|it| {
+                let mut row = vec![it.iteration as f64];
+                row.extend(&it.x);
+                row.extend([
+                    it.fx,
+                    it.constraint_violation,
+                    it.kkt_residual,
+                    it.step_norm,
+                    it.step_length,
+                    it.merit_penalty,
+                ]);
+                row
+            })
+            .collect();
+
+        write_csv(path, &headers, &rows)
+    }
+}
@@ -0,0 +1,163 @@
+//! Unconstrained minimisation used for the inner subproblems.
+//!
+//! The constrained methods in this week reduce the original problem to a
+//! sequence of unconstrained ones; these are solved with BFGS using
+//! central-difference gradients and a backtracking line search.
+
+use ndarray::{Array1, Array2};
+
+use super::types::UnconstrainedResult;
+
+/// Armijo sufficient-decrease constant.
+const ARMIJO_C: f64 = 1e-4;
+
+/// Maximum number of step halvings in the line search.
+const MAX_BACKTRACKS: usize = 60;
+
+/// Central-difference approximation to the gradient of `f` at `x`.
+///
+/// Falls back to a one-sided difference when one of the probes leaves the
+/// domain of `f` (returns a non-finite value), e.g. next to a log barrier.
+pub fn numerical_gradient(f: &dyn Fn(&[f64]) -> f64, x: &[f64]) -> Vec<f64> {
+    let mut xp = x.to_vec();
+    let mut f_centre = None;
+    (0..x.len())
+        .map(|i| {
+            let h = f64::EPSILON.cbrt() * x[i].abs().max(1.0);
+            xp[i] = x[i] + h;
+            let f_plus = f(&xp);
+            xp[i] = x[i] - h;
+            let f_minus = f(&xp);
+            xp[i] = x[i];
+            match (f_plus.is_finite(), f_minus.is_finite()) {
+                (true, true) => (f_plus - f_minus) / (2.0 * h),
+                (true, false) => (f_plus - *f_centre.get_or_insert_with(|| f(x))) / h,
+                (false, true) => (*f_centre.get_or_insert_with(|| f(x)) - f_minus) / h,
+                (false, false) => f64::NAN,
+            }
+        })
+        .collect()
+}
+
+/// Minimises `f` from `x0` with the BFGS quasi-Newton method.
+///
+/// # Arguments
+/// * `f` - Objective function to minimise (may return `inf` outside its domain)
+/// * `x0` - Starting point
+/// * `tol` - Stop when the gradient norm falls below this value
+/// * `max_iter` - Maximum number of iterations (safety limit)
+pub fn bfgs(f: &dyn Fn(&[f64]) -> f64, x0: &[f64], tol: f64, max_iter: usize) -> UnconstrainedResult {
+    let n = x0.len();
+    let eval = |x: &Array1<f64>| f(x.as_slice().unwrap());
+    let grad = |x: &Array1<f64>| Array1::from(numerical_gradient(f, x.as_slice().unwrap()));
+
+    let mut x = Array1::from(x0.to_vec());
+    let mut fx = eval(&x);
+    let mut g = grad(&x);
+    let mut h_inv = Array2::<f64>::eye(n);
+    let mut fresh_hessian = true;
+    let mut iterations = 0;
+
+    for i in 1..=max_iter {
+        if norm(&g) < tol {
+            break;
+        }
+        iterations = i;
+
+        let mut direction = -h_inv.dot(&g);
+        let mut slope = g.dot(&direction);
+        if slope >= 0.0 {
+            // Not a descent direction: fall back to steepest descent.
+            h_inv = Array2::eye(n);
+            fresh_hessian = true;
+            direction = -g.clone();
+            slope = g.dot(&direction);
+        }
+
+        let mut alpha = 1.0;
+        let mut accepted = None;
+        for _ in 0..MAX_BACKTRACKS {
+            let candidate = &x + &(alpha * &direction);
+            let f_candidate = eval(&candidate);
+            if f_candidate.is_finite() && f_candidate <= fx + ARMIJO_C * alpha * slope {
+                accepted = Some((candidate, f_candidate));
+                break;
+            }
+            alpha *= 0.5;
+        }
+
+        let Some((x_new, f_new)) = accepted else {
+            if fresh_hessian {
+                break;
+            }
+            h_inv = Array2::eye(n);
+            fresh_hessian = true;
+            continue;
+        };
+
+        let g_new = grad(&x_new);
+        let s = &x_new - &x;
+        let y = &g_new - &g;
+        let sy = s.dot(&y);
+        if sy > 1e-12 * norm(&s) * norm(&y) {
+            if fresh_hessian {
+                h_inv = Array2::eye(n) * (sy / y.dot(&y));
+                fresh_hessian = false;
+            }
+            // H+ = (I - rho s y^T) H (I - rho y s^T) + rho s s^T
+            let rho = 1.0 / sy;
+            let hy = h_inv.dot(&y);
+            let yhy = y.dot(&hy);
+            for r in 0..n {
+                for c in 0..n {
+                    h_inv[[r, c]] += rho * ((1.0 + rho * yhy) * s[r] * s[c] - hy[r] * s[c] - s[r] * hy[c]);
+                }
+            }
+        }
+
+        x = x_new;
+        fx = f_new;
+        g = g_new;
+    }
+
+    UnconstrainedResult {
+        x: x.to_vec(),
+        fx,
+        grad_norm: norm(&g),
+        iterations,
+    }
+}
+
+fn norm(v: &Array1<f64>) -> f64 {
+    v.dot(v).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numerical_gradient_quadratic() {
+        let f = |x: &[f64]| x[0] * x[0] + 3.0 * x[0] * x[1];
+        let g = numerical_gradient(&f, &[1.0, 2.0]);
+        assert!((g[0] - 8.0).abs() < 1e-6);
+        assert!((g[1] - 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_bfgs_rosenbrock() {
+        let f = |x: &[f64]| (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2);
+        let result = bfgs(&f, &[-1.2, 1.0], 1e-6, 500);
+        assert!((result.x[0] - 1.0).abs() < 1e-4, "x={:?}", result.x);
+        assert!((result.x[1] - 1.0).abs() < 1e-4, "x={:?}", result.x);
+    }
+
+    #[test]
+    fn test_bfgs_respects_infinite_barrier() {
+        // Minimum of (x - 2)^2 on x < 1 is pushed against the wall at x = 1.
+        let f = |x: &[f64]| if x[0] < 1.0 { (x[0] - 2.0).powi(2) - 0.01 * (1.0 - x[0]).ln() } else { f64::INFINITY };
+        let result = bfgs(&f, &[0.0], 1e-8, 200);
+        assert!(result.x[0] < 1.0);
+        assert!(result.x[0] > 0.98, "x={:?}", result.x);
+    }
+}
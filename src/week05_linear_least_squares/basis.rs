@@ -0,0 +1,150 @@
+//! Basis functions and design-matrix construction.
+//!
+//! A linear model y ≈ Σ c_k φ_k(x) is fitted by building the design matrix
+//! A[i, k] = φ_k(x_i) and solving the least-squares problem for c.
+
+use ndarray::Array2;
+
+/// A user-supplied basis function.
+pub type BasisFn = Box<dyn Fn(f64) -> f64>;
+
+/// A family of basis functions φ_0, φ_1, ... for linear model fitting.
+pub enum Basis {
+    /// 1, x, x², ..., x^degree.
+    Monomial { degree: usize },
+    /// Legendre polynomials P_0..P_degree with `domain` mapped onto [-1, 1].
+    Legendre { degree: usize, domain: (f64, f64) },
+    /// Chebyshev polynomials T_0..T_degree with `domain` mapped onto [-1, 1].
+    Chebyshev { degree: usize, domain: (f64, f64) },
+    /// 1, cos(2πkx/P), sin(2πkx/P) for k = 1..=harmonics.
+    Fourier { harmonics: usize, period: f64 },
+    /// Arbitrary user closures.
+    Custom(Vec<BasisFn>),
+}
+
+impl Basis {
+    /// Number of basis functions (columns of the design matrix).
+    pub fn len(&self) -> usize {
+        match self {
+            Basis::Monomial { degree } | Basis::Legendre { degree, .. } | Basis::Chebyshev { degree, .. } => degree + 1,
+            Basis::Fourier { harmonics, .. } => 2 * harmonics + 1,
+            Basis::Custom(fns) => fns.len(),
+        }
+    }
+
+    /// Whether the basis has no functions.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Short human-readable description.
+    pub fn label(&self) -> String {
+        match self {
+            Basis::Monomial { degree } => format!("monomial degree {degree}"),
+            Basis::Legendre { degree, .. } => format!("Legendre degree {degree}"),
+            Basis::Chebyshev { degree, .. } => format!("Chebyshev degree {degree}"),
+            Basis::Fourier { harmonics, .. } => format!("Fourier {harmonics} harmonics"),
+            Basis::Custom(fns) => format!("custom ({} functions)", fns.len()),
+        }
+    }
+
+    /// Evaluate every basis function at `x`.
+    pub fn evaluate(&self, x: f64) -> Vec<f64> {
+        match self {
+            Basis::Monomial { degree } => (0..=*degree).map(|k| x.powi(k as i32)).collect(),
+            Basis::Legendre { degree, domain } => {
+                let t = to_unit_interval(x, *domain);
+                // (k + 1) P_{k+1} = (2k + 1) t P_k - k P_{k-1}
+                three_term(*degree, t, |k, t, p, p_prev| {
+                    let k = k as f64;
+                    ((2.0 * k + 1.0) * t * p - k * p_prev) / (k + 1.0)
+                })
+            }
+            Basis::Chebyshev { degree, domain } => {
+                let t = to_unit_interval(x, *domain);
+                // T_{k+1} = 2t T_k - T_{k-1}
+                three_term(*degree, t, |_, t, p, p_prev| 2.0 * t * p - p_prev)
+            }
+            Basis::Fourier { harmonics, period } => {
+                let w = 2.0 * std::f64::consts::PI / period;
+                let mut row = Vec::with_capacity(2 * harmonics + 1);
+                row.push(1.0);
+                for k in 1..=*harmonics {
+                    let arg = w * k as f64 * x;
+                    row.push(arg.cos());
+                    row.push(arg.sin());
+                }
+                row
+            }
+            Basis::Custom(fns) => fns.iter().map(|f| f(x)).collect(),
+        }
+    }
+
+    /// Design matrix A[i, k] = φ_k(x_i).
+    pub fn design_matrix(&self, x: &[f64]) -> Array2<f64> {
+        let mut a = Array2::zeros((x.len(), self.len()));
+        for (i, &xi) in x.iter().enumerate() {
+            for (k, v) in self.evaluate(xi).into_iter().enumerate() {
+                a[[i, k]] = v;
+            }
+        }
+        a
+    }
+}
+
+fn to_unit_interval(x: f64, (a, b): (f64, f64)) -> f64 {
+    (2.0 * x - (a + b)) / (b - a)
+}
+
+/// Evaluates P_0 = 1, P_1 = t and P_{k+1} = next(k, t, P_k, P_{k-1}).
+fn three_term(degree: usize, t: f64, next: impl Fn(usize, f64, f64, f64) -> f64) -> Vec<f64> {
+    let mut row = Vec::with_capacity(degree + 1);
+    row.push(1.0);
+    if degree >= 1 {
+        row.push(t);
+    }
+    for k in 1..degree {
+        let value = next(k, t, row[k], row[k - 1]);
+        row.push(value);
+    }
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monomial_row() {
+        assert_eq!(Basis::Monomial { degree: 3 }.evaluate(2.0), vec![1.0, 2.0, 4.0, 8.0]);
+    }
+
+    #[test]
+    fn test_legendre_values() {
+        // P_2(t) = (3t² - 1)/2, P_3(t) = (5t³ - 3t)/2 on the default interval.
+        let basis = Basis::Legendre { degree: 3, domain: (-1.0, 1.0) };
+        let row = basis.evaluate(0.5);
+        assert!((row[2] - (3.0 * 0.25 - 1.0) / 2.0).abs() < 1e-12);
+        assert!((row[3] - (5.0 * 0.125 - 1.5) / 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_chebyshev_domain_mapping() {
+        // x = 3 on [2, 4] maps to t = 0, where T_2 = -1.
+        let basis = Basis::Chebyshev { degree: 2, domain: (2.0, 4.0) };
+        assert_eq!(basis.evaluate(3.0), vec![1.0, 0.0, -1.0]);
+    }
+
+    #[test]
+    fn test_fourier_and_custom_design_matrix() {
+        let fourier = Basis::Fourier { harmonics: 2, period: 1.0 };
+        let a = fourier.design_matrix(&[0.0, 0.25]);
+        assert_eq!(a.dim(), (2, 5));
+        assert!((a[[1, 2]] - 1.0).abs() < 1e-12); // sin(π/2)
+
+        let custom = Basis::Custom(vec![Box::new(|x| x.exp()), Box::new(|x| x.ln())]);
+        let a = custom.design_matrix(&[1.0]);
+        assert!((a[[0, 0]] - std::f64::consts::E).abs() < 1e-12);
+        assert_eq!(a[[0, 1]], 0.0);
+    }
+}
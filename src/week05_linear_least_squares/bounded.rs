@@ -0,0 +1,302 @@
+//! Non-negative and bound-constrained linear least squares.
+//!
+//! Solves min ||A x - y||₂ subject to l <= x <= u with an active-set method.
+//! Every variable is either *free* or held at one of its bounds. Each outer
+//! step frees the bound variable whose gradient most wants to leave its
+//! bound; the inner loop solves the unconstrained problem in the free
+//! variables and, if that overshoots a bound, steps only as far as the first
+//! bound and pins the variables that hit it. This is the Lawson-Hanson NNLS
+//! algorithm generalised to two-sided bounds (Stark and Parker's BVLS); with
+//! l = 0 and u = ∞ it reduces to Lawson-Hanson exactly.
+//!
+//! At the solution the gradient g = Aᵀ(A x - y) certifies optimality: it is
+//! zero in the free variables, non-negative at lower bounds and non-positive
+//! at upper bounds. Its magnitude at a binding bound is the Lagrange
+//! multiplier, the rate at which ½||A x - y||² would fall if that bound were
+//! relaxed.
+
+use ndarray::{Array1, Array2, Axis};
+
+use super::lstsq::{default_rank_tolerance, svd_lstsq};
+
+/// Where a variable sits relative to its bounds at the solution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BoundStatus {
+    /// Strictly between its bounds.
+    Free,
+    /// Held at its lower bound.
+    AtLower,
+    /// Held at its upper bound.
+    AtUpper,
+}
+
+/// Active-set information for one coefficient.
+#[derive(Debug, Clone)]
+pub struct BoundInfo {
+    pub value: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub status: BoundStatus,
+    /// Component of g = Aᵀ(A x - y), the gradient of ½||A x - y||².
+    pub gradient: f64,
+}
+
+impl BoundInfo {
+    /// Lagrange multiplier of the binding bound (zero when free).
+    pub fn multiplier(&self) -> f64 {
+        match self.status {
+            BoundStatus::Free => 0.0,
+            BoundStatus::AtLower => self.gradient,
+            BoundStatus::AtUpper => -self.gradient,
+        }
+    }
+}
+
+/// Result of a bound-constrained least-squares solve.
+#[derive(Debug, Clone)]
+pub struct BoundedLstsqResult {
+    pub coefficients: Array1<f64>,
+    /// Residuals y - A x.
+    pub residuals: Array1<f64>,
+    /// Residual sum of squares.
+    pub rss: f64,
+    /// Active-set report, one entry per coefficient.
+    pub bounds: Vec<BoundInfo>,
+    /// Number of unconstrained subproblems solved.
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+impl BoundedLstsqResult {
+    /// Indices of coefficients held at a bound.
+    pub fn binding(&self) -> Vec<usize> {
+        self.bounds
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.status != BoundStatus::Free)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// One line per coefficient explaining its status, using `names` for labels.
+    pub fn active_set_report(&self, names: &[&str]) -> Vec<String> {
+        self.bounds
+            .iter()
+            .enumerate()
+            .map(|(i, b)| {
+                let name = names.get(i).map_or_else(|| format!("x{i}"), |n| n.to_string());
+                match b.status {
+                    BoundStatus::Free => format!("{name} = {:.6} free (gradient {:.1e})", b.value, b.gradient),
+                    BoundStatus::AtLower => format!(
+                        "{name} = {:.6} held at lower bound; relaxing it would reduce ½RSS at rate {:.3e}",
+                        b.value,
+                        b.multiplier()
+                    ),
+                    BoundStatus::AtUpper => format!(
+                        "{name} = {:.6} held at upper bound; relaxing it would reduce ½RSS at rate {:.3e}",
+                        b.value,
+                        b.multiplier()
+                    ),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Non-negative least squares: min ||A x - y||₂ subject to x >= 0.
+pub fn nnls(a: &Array2<f64>, y: &Array1<f64>) -> BoundedLstsqResult {
+    let n = a.ncols();
+    bvls(a, y, &Array1::zeros(n), &Array1::from_elem(n, f64::INFINITY))
+}
+
+/// Bounded-variable least squares: min ||A x - y||₂ subject to l <= x <= u.
+///
+/// Bounds may be infinite; a variable with no finite bound is always free.
+pub fn bvls(a: &Array2<f64>, y: &Array1<f64>, lower: &Array1<f64>, upper: &Array1<f64>) -> BoundedLstsqResult {
+    let (m, n) = a.dim();
+    assert!(lower.len() == n && upper.len() == n, "one bound per coefficient");
+    assert!(lower.iter().zip(upper.iter()).all(|(l, u)| l <= u), "lower bounds must not exceed upper bounds");
+
+    // Gradient tolerance scaled like Aᵀy, following Lawson and Hanson.
+    let norm_a = a.axis_iter(Axis(1)).map(|col| col.iter().map(|v| v.abs()).sum::<f64>()).fold(0.0, f64::max);
+    let norm_y = y.iter().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+    let tol = 10.0 * f64::EPSILON * m.max(n) as f64 * norm_a * norm_y;
+
+    let mut x = Array1::<f64>::zeros(n);
+    let mut status = vec![BoundStatus::Free; n];
+    for j in 0..n {
+        if lower[j].is_finite() {
+            x[j] = lower[j];
+            status[j] = BoundStatus::AtLower;
+        } else if upper[j].is_finite() {
+            x[j] = upper[j];
+            status[j] = BoundStatus::AtUpper;
+        }
+    }
+
+    let max_iter = 3 * n + 10;
+    let mut iterations = 0;
+    let mut converged = false;
+
+    'outer: while iterations < max_iter {
+        // Inner loop: solve over the free set, backtracking onto bounds.
+        loop {
+            let free: Vec<usize> = (0..n).filter(|&j| status[j] == BoundStatus::Free).collect();
+            if free.is_empty() {
+                break;
+            }
+            iterations += 1;
+
+            let mut x_bound = x.clone();
+            for &j in &free {
+                x_bound[j] = 0.0;
+            }
+            let a_free = a.select(Axis(1), &free);
+            let rhs = y - &a.dot(&x_bound);
+            let z = svd_lstsq(&a_free, &rhs, default_rank_tolerance(&a_free)).coefficients;
+
+            // Largest step toward z that keeps every free variable feasible.
+            let mut alpha = 1.0_f64;
+            for (k, &j) in free.iter().enumerate() {
+                let step = z[k] - x[j];
+                let limit = if z[k] < lower[j] {
+                    (lower[j] - x[j]) / step
+                } else if z[k] > upper[j] {
+                    (upper[j] - x[j]) / step
+                } else {
+                    continue;
+                };
+                alpha = alpha.min(limit.max(0.0));
+            }
+
+            for (k, &j) in free.iter().enumerate() {
+                x[j] += alpha * (z[k] - x[j]);
+            }
+            if alpha >= 1.0 {
+                break;
+            }
+
+            // Pin the variables that reached a bound.
+            let mut pinned = false;
+            for (k, &j) in free.iter().enumerate() {
+                if z[k] < lower[j] && x[j] - lower[j] <= 1e-12 * (1.0 + lower[j].abs()) {
+                    x[j] = lower[j];
+                    status[j] = BoundStatus::AtLower;
+                    pinned = true;
+                } else if z[k] > upper[j] && upper[j] - x[j] <= 1e-12 * (1.0 + upper[j].abs()) {
+                    x[j] = upper[j];
+                    status[j] = BoundStatus::AtUpper;
+                    pinned = true;
+                }
+            }
+            if !pinned || iterations >= max_iter {
+                break 'outer;
+            }
+        }
+
+        // Outer step: free the bound variable with the largest KKT violation.
+        let gradient = a.t().dot(&(a.dot(&x) - y));
+        let candidate = (0..n)
+            .filter_map(|j| match status[j] {
+                BoundStatus::AtLower if -gradient[j] > tol => Some((j, -gradient[j])),
+                BoundStatus::AtUpper if gradient[j] > tol => Some((j, gradient[j])),
+                _ => None,
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        match candidate {
+            Some((j, _)) => status[j] = BoundStatus::Free,
+            None => {
+                converged = true;
+                break;
+            }
+        }
+    }
+
+    let residuals = y - &a.dot(&x);
+    let rss = residuals.dot(&residuals);
+    let gradient = a.t().dot(&residuals.mapv(|r| -r));
+    let bounds = (0..n)
+        .map(|j| BoundInfo { value: x[j], lower: lower[j], upper: upper[j], status: status[j], gradient: gradient[j] })
+        .collect();
+
+    BoundedLstsqResult { coefficients: x, residuals, rss, bounds, iterations, converged }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::rng::Rng;
+    use crate::week05_linear_least_squares::lstsq::qr_lstsq;
+    use ndarray::array;
+
+    #[test]
+    fn test_nnls_small_example() {
+        // Unconstrained solution is (1.5, -1); NNLS clamps the second to 0.
+        let a = array![[1.0, 0.0], [1.0, 0.0], [0.0, 1.0]];
+        let y = array![2.0, 1.0, -1.0];
+        let result = nnls(&a, &y);
+        assert!(result.converged);
+        assert!((result.coefficients[0] - 1.5).abs() < 1e-12);
+        assert_eq!(result.coefficients[1], 0.0);
+        assert_eq!(result.bounds[1].status, BoundStatus::AtLower);
+        assert!((result.bounds[1].multiplier() - 1.0).abs() < 1e-12);
+        assert_eq!(result.binding(), vec![1]);
+    }
+
+    #[test]
+    fn test_nnls_matches_exhaustive_search() {
+        let mut rng = Rng::new(37);
+        for _ in 0..20 {
+            let a = Array2::from_shape_fn((8, 4), |_| rng.normal());
+            let y = Array1::from_shape_fn(8, |_| rng.normal());
+            let result = nnls(&a, &y);
+            assert!(result.converged);
+            assert!(result.coefficients.iter().all(|&v| v >= 0.0));
+
+            // Best feasible solution over all 2^4 choices of free set.
+            let best = (0..16u32)
+                .filter_map(|mask| {
+                    let free: Vec<usize> = (0..4).filter(|j| mask & (1 << j) != 0).collect();
+                    if free.is_empty() {
+                        return Some(y.dot(&y));
+                    }
+                    let z = qr_lstsq(&a.select(Axis(1), &free), &y);
+                    z.coefficients.iter().all(|&v| v >= 0.0).then_some(z.rss)
+                })
+                .fold(f64::INFINITY, f64::min);
+            assert!((result.rss - best).abs() < 1e-10 * (1.0 + best));
+        }
+    }
+
+    #[test]
+    fn test_bvls_box_and_free_variables() {
+        // y = 1 + 2t on t in [0, 1]; cap the slope at 1.5, leave the intercept unbounded.
+        let t = Array1::linspace(0.0, 1.0, 11);
+        let a = ndarray::stack(Axis(1), &[Array1::ones(11).view(), t.view()]).unwrap();
+        let y = t.mapv(|t| 1.0 + 2.0 * t);
+        let lower = array![f64::NEG_INFINITY, 0.0];
+        let upper = array![f64::INFINITY, 1.5];
+        let result = bvls(&a, &y, &lower, &upper);
+
+        assert!(result.converged);
+        assert_eq!(result.bounds[0].status, BoundStatus::Free);
+        assert_eq!(result.bounds[1].status, BoundStatus::AtUpper);
+        assert_eq!(result.coefficients[1], 1.5);
+        // Intercept re-fits to the mean of y - 1.5t = 1 + 0.5t.
+        assert!((result.coefficients[0] - 1.25).abs() < 1e-12);
+        assert!(result.bounds[0].gradient.abs() < 1e-12);
+        assert!(result.bounds[1].multiplier() > 0.0);
+    }
+
+    #[test]
+    fn test_report_lines() {
+        let a = array![[1.0, 0.0], [0.0, 1.0]];
+        let y = array![0.5, 3.0];
+        let result = bvls(&a, &y, &array![0.0, 0.0], &array![1.0, 2.0]);
+        let report = result.active_set_report(&["c_a"]);
+        assert!(report[0].starts_with("c_a = 0.500000 free"));
+        assert!(report[1].starts_with("x1 = 2.000000 held at upper bound"));
+        assert!((result.rss - 1.0).abs() < 1e-12);
+    }
+}
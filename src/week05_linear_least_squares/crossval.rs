@@ -0,0 +1,245 @@
+//! Model selection by cross-validation.
+//!
+//! The training error of a least-squares fit can only fall as basis
+//! functions are added, so it cannot choose the model size. Cross-validation
+//! estimates the error on unseen data instead: split the observations into
+//! folds, fit on all but one fold, measure the squared prediction error on
+//! the held-out fold, and average over folds. Too small a model is biased and
+//! too large a model fits the noise; the cross-validation error is lowest in
+//! between.
+//!
+//! For leave-one-out the refits are unnecessary: the held-out residual is
+//! e_i / (1 - h_ii), where h_ii is the leverage from the full fit.
+
+use ndarray::{Array1, Array2, Axis};
+
+use super::basis::Basis;
+use super::lstsq::lstsq;
+use super::statistics::normal_matrix_pseudo_inverse;
+use super::types::LstsqMethod;
+use crate::common::rng::Rng;
+
+/// How observations are split into training and validation folds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CrossValidation {
+    /// `k` folds of (nearly) equal size after a seeded shuffle.
+    KFold { k: usize, seed: u64 },
+    /// One fold per observation.
+    LeaveOneOut,
+}
+
+impl CrossValidation {
+    /// Held-out indices of each fold; together they partition 0..n.
+    pub fn folds(&self, n: usize) -> Vec<Vec<usize>> {
+        match *self {
+            CrossValidation::KFold { k, seed } => {
+                assert!(k >= 2 && k <= n, "k-fold needs 2 <= k <= n");
+                let mut order: Vec<usize> = (0..n).collect();
+                Rng::new(seed).shuffle(&mut order);
+                let mut folds = vec![Vec::new(); k];
+                for (position, i) in order.into_iter().enumerate() {
+                    folds[position % k].push(i);
+                }
+                for fold in &mut folds {
+                    fold.sort_unstable();
+                }
+                folds
+            }
+            CrossValidation::LeaveOneOut => (0..n).map(|i| vec![i]).collect(),
+        }
+    }
+
+    /// Short human-readable name.
+    pub fn label(&self) -> String {
+        match self {
+            CrossValidation::KFold { k, .. } => format!("{k}-fold"),
+            CrossValidation::LeaveOneOut => String::from("leave-one-out"),
+        }
+    }
+}
+
+/// Cross-validated prediction error of one model.
+#[derive(Debug, Clone)]
+pub struct CvScore {
+    /// Held-out sum of squared errors over all folds, divided by n.
+    pub mean_squared_error: f64,
+    /// Standard error of the mean of the per-fold errors.
+    pub standard_error: f64,
+    /// Mean squared held-out error of each fold.
+    pub fold_errors: Vec<f64>,
+}
+
+/// Cross-validates the linear model y ≈ A x under `scheme`.
+///
+/// Each training set must keep at least as many rows as A has columns;
+/// otherwise the fold is rank deficient and the result depends on `method`.
+pub fn cross_validate(a: &Array2<f64>, y: &Array1<f64>, scheme: CrossValidation, method: LstsqMethod) -> CvScore {
+    let n = y.len();
+    let mut total = 0.0;
+    let mut fold_errors = Vec::new();
+
+    for held_out in scheme.folds(n) {
+        let mut in_fold = vec![false; n];
+        for &i in &held_out {
+            in_fold[i] = true;
+        }
+        let train: Vec<usize> = (0..n).filter(|&i| !in_fold[i]).collect();
+        let fit = lstsq(&a.select(Axis(0), &train), &y.select(Axis(0), &train), method);
+
+        let errors = &y.select(Axis(0), &held_out) - &a.select(Axis(0), &held_out).dot(&fit.coefficients);
+        let sse = errors.dot(&errors);
+        total += sse;
+        fold_errors.push(sse / held_out.len() as f64);
+    }
+
+    let k = fold_errors.len() as f64;
+    let mean = fold_errors.iter().sum::<f64>() / k;
+    let variance = fold_errors.iter().map(|e| (e - mean).powi(2)).sum::<f64>() / (k - 1.0);
+    CvScore { mean_squared_error: total / n as f64, standard_error: (variance / k).sqrt(), fold_errors }
+}
+
+/// Leave-one-out residuals e_i / (1 - h_ii) from a single full fit.
+///
+/// NaN for observations with leverage one, which no other point can predict.
+pub fn loo_residuals(a: &Array2<f64>, y: &Array1<f64>) -> Array1<f64> {
+    let fit = lstsq(a, y, LstsqMethod::Svd);
+    let (_, leverage, _) = normal_matrix_pseudo_inverse(a);
+    let mut residuals = fit.residuals;
+    for (r, h) in residuals.iter_mut().zip(leverage.iter()) {
+        *r = if 1.0 - h > 1e-12 { *r / (1.0 - h) } else { f64::NAN };
+    }
+    residuals
+}
+
+/// Cross-validation errors across a family of candidate models.
+#[derive(Debug, Clone)]
+pub struct ModelSelection {
+    /// [`Basis::label`] of each candidate.
+    pub labels: Vec<String>,
+    /// Number of basis functions of each candidate.
+    pub n_parameters: Vec<usize>,
+    /// Mean squared residual of each candidate fitted to all the data.
+    pub training_errors: Vec<f64>,
+    /// Cross-validated score of each candidate.
+    pub scores: Vec<CvScore>,
+    /// Index of the candidate with the lowest cross-validation error.
+    pub selected: usize,
+}
+
+impl ModelSelection {
+    /// Cross-validation error of each candidate.
+    pub fn cv_errors(&self) -> Vec<f64> {
+        self.scores.iter().map(|s| s.mean_squared_error).collect()
+    }
+
+    /// Index of the smallest candidate whose error is within one standard
+    /// error of the minimum; a more conservative choice than [`Self::selected`].
+    pub fn one_standard_error(&self) -> usize {
+        let best = &self.scores[self.selected];
+        let threshold = best.mean_squared_error + best.standard_error;
+        (0..self.scores.len())
+            .filter(|&i| self.scores[i].mean_squared_error <= threshold)
+            .min_by_key(|&i| (self.n_parameters[i], i))
+            .unwrap_or(self.selected)
+    }
+}
+
+/// Cross-validates every basis in `family` on (x, y) and selects the best.
+pub fn select_model(
+    family: &[Basis],
+    x: &[f64],
+    y: &[f64],
+    scheme: CrossValidation,
+    method: LstsqMethod,
+) -> ModelSelection {
+    assert!(!family.is_empty(), "need at least one candidate model");
+    let y = Array1::from(y.to_vec());
+    let mut training_errors = Vec::with_capacity(family.len());
+    let mut scores = Vec::with_capacity(family.len());
+
+    for basis in family {
+        let a = basis.design_matrix(x);
+        training_errors.push(lstsq(&a, &y, method).rss / y.len() as f64);
+        scores.push(cross_validate(&a, &y, scheme, method));
+    }
+
+    let selected = (0..scores.len())
+        .min_by(|&i, &j| scores[i].mean_squared_error.total_cmp(&scores[j].mean_squared_error))
+        .unwrap();
+
+    ModelSelection {
+        labels: family.iter().map(Basis::label).collect(),
+        n_parameters: family.iter().map(Basis::len).collect(),
+        training_errors,
+        scores,
+        selected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// y = 1 - 2x + 3x² plus a deterministic wiggle, on [-1, 1].
+    fn quadratic_data() -> (Vec<f64>, Vec<f64>) {
+        let x: Vec<f64> = Array1::linspace(-1.0, 1.0, 25).to_vec();
+        let y = x.iter().map(|&x| 1.0 - 2.0 * x + 3.0 * x * x + 0.05 * (13.0 * x).sin()).collect();
+        (x, y)
+    }
+
+    fn polynomials(max_degree: usize) -> Vec<Basis> {
+        (0..=max_degree).map(|degree| Basis::Legendre { degree, domain: (-1.0, 1.0) }).collect()
+    }
+
+    #[test]
+    fn test_folds_partition_indices() {
+        let folds = CrossValidation::KFold { k: 4, seed: 9 }.folds(10);
+        assert_eq!(folds.len(), 4);
+        assert!(folds.iter().all(|f| f.len() == 2 || f.len() == 3));
+        let mut all: Vec<usize> = folds.concat();
+        all.sort_unstable();
+        assert_eq!(all, (0..10).collect::<Vec<_>>());
+        assert_eq!(folds, CrossValidation::KFold { k: 4, seed: 9 }.folds(10));
+        assert_eq!(CrossValidation::LeaveOneOut.folds(3), vec![vec![0], vec![1], vec![2]]);
+    }
+
+    #[test]
+    fn test_leave_one_out_matches_leverage_formula() {
+        let (x, y) = quadratic_data();
+        let a = Basis::Monomial { degree: 3 }.design_matrix(&x);
+        let y = Array1::from(y);
+        let score = cross_validate(&a, &y, CrossValidation::LeaveOneOut, LstsqMethod::Qr);
+        let shortcut = loo_residuals(&a, &y);
+        for (fold, r) in score.fold_errors.iter().zip(shortcut.iter()) {
+            assert!((fold - r * r).abs() < 1e-10);
+        }
+        assert!((score.mean_squared_error - shortcut.dot(&shortcut) / y.len() as f64).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_selects_quadratic() {
+        let (x, y) = quadratic_data();
+        let family = polynomials(8);
+        for scheme in [CrossValidation::KFold { k: 5, seed: 39 }, CrossValidation::LeaveOneOut] {
+            let selection = select_model(&family, &x, &y, scheme, LstsqMethod::Qr);
+            // Training error never increases with degree.
+            assert!(selection.training_errors.windows(2).all(|w| w[1] <= w[0] + 1e-15));
+            assert_eq!(selection.one_standard_error(), 2, "{}", scheme.label());
+            assert!(selection.selected >= 2, "{}", scheme.label());
+        }
+    }
+
+    #[test]
+    fn test_one_standard_error_prefers_smaller_model() {
+        let score = |mse: f64, se: f64| CvScore { mean_squared_error: mse, standard_error: se, fold_errors: vec![] };
+        let selection = ModelSelection {
+            labels: vec![String::new(); 3],
+            n_parameters: vec![1, 2, 3],
+            training_errors: vec![3.0, 2.0, 1.0],
+            scores: vec![score(5.0, 0.1), score(1.05, 0.1), score(1.0, 0.1)],
+            selected: 2,
+        };
+        assert_eq!(selection.one_standard_error(), 1);
+        assert_eq!(selection.cv_errors(), vec![5.0, 1.05, 1.0]);
+    }
+}
@@ -0,0 +1,230 @@
+//! Matrix decompositions used by the least-squares solvers.
+
+use ndarray::{s, Array1, Array2, Axis};
+
+/// Thin QR decomposition A = Q R of an m x n matrix with m >= n.
+#[derive(Debug, Clone)]
+pub struct QrDecomposition {
+    /// m x n matrix with orthonormal columns.
+    pub q: Array2<f64>,
+    /// n x n upper-triangular factor.
+    pub r: Array2<f64>,
+}
+
+/// Thin singular value decomposition A = U diag(s) Vᵀ.
+#[derive(Debug, Clone)]
+pub struct SvdDecomposition {
+    /// m x k matrix of left singular vectors, k = min(m, n).
+    pub u: Array2<f64>,
+    /// Singular values in descending order.
+    pub s: Array1<f64>,
+    /// n x k matrix of right singular vectors.
+    pub v: Array2<f64>,
+}
+
+/// Cholesky factor L (lower triangular) with A = L Lᵀ.
+///
+/// Returns `None` if A is not symmetric positive definite to working precision.
+pub fn cholesky(a: &Array2<f64>) -> Option<Array2<f64>> {
+    let n = a.nrows();
+    let mut l = Array2::<f64>::zeros((n, n));
+    for j in 0..n {
+        let diag = a[[j, j]] - (0..j).map(|k| l[[j, k]] * l[[j, k]]).sum::<f64>();
+        if diag <= 0.0 || !diag.is_finite() {
+            return None;
+        }
+        l[[j, j]] = diag.sqrt();
+        for i in (j + 1)..n {
+            let off = a[[i, j]] - (0..j).map(|k| l[[i, k]] * l[[j, k]]).sum::<f64>();
+            l[[i, j]] = off / l[[j, j]];
+        }
+    }
+    Some(l)
+}
+
+/// Householder QR decomposition of an m x n matrix with m >= n.
+pub fn householder_qr(a: &Array2<f64>) -> QrDecomposition {
+    let (m, n) = a.dim();
+    assert!(m >= n, "householder_qr requires at least as many rows as columns");
+
+    let mut r = a.clone();
+    let mut reflectors: Vec<Array1<f64>> = Vec::with_capacity(n);
+
+    for k in 0..n {
+        // Reflector v maps r[k.., k] onto a multiple of e_1.
+        let mut v = r.column(k).slice(s![k..]).to_owned();
+        let norm_x = v.dot(&v).sqrt();
+        if norm_x == 0.0 {
+            reflectors.push(Array1::zeros(m - k));
+            continue;
+        }
+        let alpha = if v[0] >= 0.0 { -norm_x } else { norm_x };
+        v[0] -= alpha;
+        let norm_v = v.dot(&v).sqrt();
+        v /= norm_v;
+
+        // R <- (I - 2 v vᵀ) R on the trailing block.
+        for c in k..n {
+            let dot: f64 = (k..m).map(|i| v[i - k] * r[[i, c]]).sum();
+            for i in k..m {
+                r[[i, c]] -= 2.0 * v[i - k] * dot;
+            }
+        }
+        reflectors.push(v);
+    }
+
+    // Accumulate the thin Q by applying the reflectors to the first n columns of I.
+    let mut q = Array2::<f64>::zeros((m, n));
+    for i in 0..n {
+        q[[i, i]] = 1.0;
+    }
+    for (k, v) in reflectors.iter().enumerate().rev() {
+        for c in 0..n {
+            let dot: f64 = (k..m).map(|i| v[i - k] * q[[i, c]]).sum();
+            for i in k..m {
+                q[[i, c]] -= 2.0 * v[i - k] * dot;
+            }
+        }
+    }
+
+    let mut r_thin = r.slice(s![..n, ..]).to_owned();
+    for i in 0..n {
+        for j in 0..i {
+            r_thin[[i, j]] = 0.0;
+        }
+    }
+
+    QrDecomposition { q, r: r_thin }
+}
+
+/// Singular value decomposition by one-sided (Hestenes) Jacobi rotations.
+pub fn svd(a: &Array2<f64>) -> SvdDecomposition {
+    let (m, n) = a.dim();
+    if m < n {
+        let t = svd(&a.t().to_owned());
+        return SvdDecomposition { u: t.v, s: t.s, v: t.u };
+    }
+
+    let mut u = a.clone();
+    let mut v = Array2::<f64>::eye(n);
+    const MAX_SWEEPS: usize = 60;
+
+    for _ in 0..MAX_SWEEPS {
+        let mut rotated = false;
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let alpha: f64 = u.column(i).dot(&u.column(i));
+                let beta: f64 = u.column(j).dot(&u.column(j));
+                let gamma: f64 = u.column(i).dot(&u.column(j));
+                if gamma == 0.0 || gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+
+                let zeta = (beta - alpha) / (2.0 * gamma);
+                let t = zeta.signum() / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                let c = 1.0 / (1.0 + t * t).sqrt();
+                let s = c * t;
+                rotate_columns(&mut u, i, j, c, s);
+                rotate_columns(&mut v, i, j, c, s);
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+
+    let mut s: Vec<f64> = u.axis_iter(Axis(1)).map(|col| col.dot(&col).sqrt()).collect();
+    for (k, &sigma) in s.iter().enumerate() {
+        if sigma > 0.0 {
+            u.column_mut(k).mapv_inplace(|x| x / sigma);
+        }
+    }
+
+    // Sort into descending order of singular value.
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| s[b].total_cmp(&s[a]));
+    let u = u.select(Axis(1), &order);
+    let v = v.select(Axis(1), &order);
+    s = order.iter().map(|&k| s[k]).collect();
+
+    SvdDecomposition { u, s: Array1::from(s), v }
+}
+
+fn rotate_columns(m: &mut Array2<f64>, i: usize, j: usize, c: f64, s: f64) {
+    for row in 0..m.nrows() {
+        let a = m[[row, i]];
+        let b = m[[row, j]];
+        m[[row, i]] = c * a - s * b;
+        m[[row, j]] = s * a + c * b;
+    }
+}
+
+/// Solves U x = b for upper-triangular U by back substitution.
+pub fn solve_upper_triangular(u: &Array2<f64>, b: &Array1<f64>) -> Array1<f64> {
+    let n = u.nrows();
+    let mut x = Array1::<f64>::zeros(n);
+    for i in (0..n).rev() {
+        let tail: f64 = ((i + 1)..n).map(|j| u[[i, j]] * x[j]).sum();
+        x[i] = (b[i] - tail) / u[[i, i]];
+    }
+    x
+}
+
+/// Solves L x = b for lower-triangular L by forward substitution.
+pub fn solve_lower_triangular(l: &Array2<f64>, b: &Array1<f64>) -> Array1<f64> {
+    let n = l.nrows();
+    let mut x = Array1::<f64>::zeros(n);
+    for i in 0..n {
+        let head: f64 = (0..i).map(|j| l[[i, j]] * x[j]).sum();
+        x[i] = (b[i] - head) / l[[i, i]];
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    fn max_abs_diff(a: &Array2<f64>, b: &Array2<f64>) -> f64 {
+        (a - b).iter().fold(0.0, |acc, v| acc.max(v.abs()))
+    }
+
+    #[test]
+    fn test_cholesky_reconstructs() {
+        let a = array![[4.0, 2.0, 0.6], [2.0, 5.0, 1.0], [0.6, 1.0, 3.0]];
+        let l = cholesky(&a).unwrap();
+        assert!(max_abs_diff(&l.dot(&l.t()), &a) < 1e-12);
+        assert!(cholesky(&array![[1.0, 2.0], [2.0, 1.0]]).is_none());
+    }
+
+    #[test]
+    fn test_householder_qr_reconstructs() {
+        let a = array![[1.0, 2.0], [3.0, 4.0], [5.0, 6.0], [7.0, 9.0]];
+        let qr = householder_qr(&a);
+        assert!(max_abs_diff(&qr.q.dot(&qr.r), &a) < 1e-12);
+        assert!(max_abs_diff(&qr.q.t().dot(&qr.q), &Array2::eye(2)) < 1e-12);
+        assert_eq!(qr.r[[1, 0]], 0.0);
+    }
+
+    #[test]
+    fn test_svd_reconstructs_and_sorts() {
+        let a = array![[3.0, 2.0, 2.0], [2.0, 3.0, -2.0]];
+        let d = svd(&a);
+        let reconstructed = d.u.dot(&Array2::from_diag(&d.s)).dot(&d.v.t());
+        assert!(max_abs_diff(&reconstructed, &a) < 1e-12);
+        assert!((d.s[0] - 5.0).abs() < 1e-12);
+        assert!((d.s[1] - 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_triangular_solves() {
+        let u = array![[2.0, 1.0], [0.0, 4.0]];
+        let x = solve_upper_triangular(&u, &array![4.0, 8.0]);
+        assert!((x[0] - 1.0).abs() < 1e-12 && (x[1] - 2.0).abs() < 1e-12);
+        let l = u.t().to_owned();
+        let y = solve_lower_triangular(&l, &array![2.0, 9.0]);
+        assert!((y[0] - 1.0).abs() < 1e-12 && (y[1] - 2.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,246 @@
+//! Probability distributions needed for regression inference.
+
+/// Natural log of the gamma function (Lanczos approximation, g = 7).
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        // Reflection formula.
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFS[0], |acc, (i, &c)| acc + c / (x + i as f64 + 1.0));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// Regularised incomplete beta function I_x(a, b).
+pub fn regularized_incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    // The continued fraction converges fastest for x < (a + 1) / (a + b + 2).
+    if x < (a + 1.0) / (a + b + 2.0) {
+        ln_front.exp() * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - ln_front.exp() * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+/// Lentz evaluation of the continued fraction for I_x(a, b).
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    const MAX_ITER: usize = 300;
+
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..=MAX_ITER {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+
+        // Even step.
+        let num = m * (b - m) * x / ((a + m2 - 1.0) * (a + m2));
+        d = 1.0 + num * d;
+        d = if d.abs() < TINY { 1.0 / TINY } else { 1.0 / d };
+        c = 1.0 + num / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        h *= d * c;
+
+        // Odd step.
+        let num = -(a + m) * (a + b + m) * x / ((a + m2) * (a + m2 + 1.0));
+        d = 1.0 + num * d;
+        d = if d.abs() < TINY { 1.0 / TINY } else { 1.0 / d };
+        c = 1.0 + num / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < 1e-15 {
+            break;
+        }
+    }
+    h
+}
+
+/// Cumulative distribution function of Student's t with `dof` degrees of freedom.
+pub fn student_t_cdf(t: f64, dof: f64) -> f64 {
+    let x = dof / (dof + t * t);
+    let tail = 0.5 * regularized_incomplete_beta(x, 0.5 * dof, 0.5);
+    if t > 0.0 {
+        1.0 - tail
+    } else {
+        tail
+    }
+}
+
+/// Quantile (inverse CDF) of Student's t with `dof` degrees of freedom.
+pub fn student_t_quantile(p: f64, dof: f64) -> f64 {
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    if p < 0.5 {
+        return -student_t_quantile(1.0 - p, dof);
+    }
+
+    let mut hi = 1.0;
+    while student_t_cdf(hi, dof) < p {
+        hi *= 2.0;
+    }
+    let mut lo = 0.0;
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        if student_t_cdf(mid, dof) < p {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+        if hi - lo < 1e-14 * hi.max(1.0) {
+            break;
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+/// Regularised lower incomplete gamma function P(a, x).
+pub fn regularized_lower_gamma(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    let ln_front = a * x.ln() - x - ln_gamma(a);
+
+    if x < a + 1.0 {
+        // Series: P = e^{-x} x^a Σ x^n / Γ(a + n + 1).
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        let mut ap = a;
+        for _ in 0..500 {
+            ap += 1.0;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-16 {
+                break;
+            }
+        }
+        sum * ln_front.exp()
+    } else {
+        // Continued fraction for Q = 1 - P (Lentz).
+        const TINY: f64 = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / TINY;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..=500 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = b + an / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < 1e-16 {
+                break;
+            }
+        }
+        1.0 - ln_front.exp() * h
+    }
+}
+
+/// Cumulative distribution function of the chi-squared distribution.
+pub fn chi_squared_cdf(x: f64, dof: f64) -> f64 {
+    regularized_lower_gamma(0.5 * dof, 0.5 * x)
+}
+
+/// Cumulative distribution function of the F distribution with (d1, d2) degrees of freedom.
+pub fn f_cdf(x: f64, d1: f64, d2: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    regularized_incomplete_beta(d1 * x / (d1 * x + d2), 0.5 * d1, 0.5 * d2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ln_gamma_factorials() {
+        assert!((ln_gamma(1.0)).abs() < 1e-12);
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-12);
+        assert!((ln_gamma(0.5) - std::f64::consts::PI.sqrt().ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_incomplete_beta_symmetry() {
+        // I_x(a, a) at x = 1/2 is exactly 1/2; I_x(1, 1) = x.
+        assert!((regularized_incomplete_beta(0.5, 3.0, 3.0) - 0.5).abs() < 1e-12);
+        assert!((regularized_incomplete_beta(0.3, 1.0, 1.0) - 0.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_student_t_table_values() {
+        // Two-sided 95% critical values.
+        assert!((student_t_quantile(0.975, 1.0) - 12.706_204_736).abs() < 1e-6);
+        assert!((student_t_quantile(0.975, 10.0) - 2.228_138_852).abs() < 1e-8);
+        assert!((student_t_quantile(0.975, 1000.0) - 1.962_339_081).abs() < 1e-8);
+        assert!((student_t_cdf(0.0, 5.0) - 0.5).abs() < 1e-15);
+        assert!((student_t_quantile(0.025, 10.0) + 2.228_138_852).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_chi_squared_cdf() {
+        // dof = 2 is exponential: F(x) = 1 - e^{-x/2}.
+        assert!((chi_squared_cdf(3.0, 2.0) - (1.0 - (-1.5_f64).exp())).abs() < 1e-12);
+        // 95th percentiles from tables.
+        assert!((chi_squared_cdf(3.841_458_821, 1.0) - 0.95).abs() < 1e-9);
+        assert!((chi_squared_cdf(18.307_038_05, 10.0) - 0.95).abs() < 1e-9);
+        assert!((chi_squared_cdf(124.342_113_4, 100.0) - 0.95).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_f_cdf() {
+        // 95th percentiles from tables; F(1, d) is the square of t(d).
+        assert!((f_cdf(4.964_602_744, 1.0, 10.0) - 0.95).abs() < 1e-9);
+        assert!((f_cdf(3.325_834_530, 5.0, 10.0) - 0.95).abs() < 1e-9);
+        assert!((f_cdf(4.0, 1.0, 7.0) - (2.0 * student_t_cdf(2.0, 7.0) - 1.0)).abs() < 1e-12);
+        assert_eq!(f_cdf(-1.0, 2.0, 3.0), 0.0);
+    }
+}
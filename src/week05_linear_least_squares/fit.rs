@@ -0,0 +1,78 @@
+//! Fitting basis-function models to tabulated data.
+
+use ndarray::{Array1, ArrayView1};
+
+use super::basis::Basis;
+use super::lstsq::lstsq;
+use super::types::{LstsqMethod, LstsqResult};
+
+/// A fitted linear model y(x) = Σ c_k φ_k(x).
+pub struct FittedModel {
+    /// Basis the model is expressed in.
+    pub basis: Basis,
+    /// Least-squares solution for the coefficients c.
+    pub fit: LstsqResult,
+}
+
+impl FittedModel {
+    /// Fitted coefficients c.
+    pub fn coefficients(&self) -> ArrayView1<'_, f64> {
+        self.fit.coefficients.view()
+    }
+
+    /// Evaluate the model at `x`.
+    pub fn eval(&self, x: f64) -> f64 {
+        self.basis
+            .evaluate(x)
+            .iter()
+            .zip(self.fit.coefficients.iter())
+            .map(|(phi, c)| phi * c)
+            .sum()
+    }
+
+    /// Evaluate the model at every point of `xs`.
+    pub fn predict(&self, xs: &[f64]) -> Vec<f64> {
+        xs.iter().map(|&x| self.eval(x)).collect()
+    }
+
+    /// Borrow the model as a plain closure.
+    pub fn as_fn(&self) -> impl Fn(f64) -> f64 + '_ {
+        move |x| self.eval(x)
+    }
+}
+
+/// Fits `basis` to the data (x, y) by linear least squares.
+pub fn fit(basis: Basis, x: &[f64], y: &[f64], method: LstsqMethod) -> FittedModel {
+    let a = basis.design_matrix(x);
+    let fit = lstsq(&a, &Array1::from(y.to_vec()), method);
+    FittedModel { basis, fit }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::week01_graphing::problems::{X_TABLE, Y_TABLE};
+
+    #[test]
+    fn test_recovers_week01_quadratic() {
+        let model = fit(Basis::Monomial { degree: 2 }, &X_TABLE, &Y_TABLE, LstsqMethod::Qr);
+        for c in model.coefficients() {
+            assert!((c - 1.0).abs() < 1e-10);
+        }
+        assert!(model.fit.rss < 1e-18);
+        assert!((model.eval(10.0) - 111.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_bases_agree_on_predictions() {
+        let domain = (1.0, 9.0);
+        let legendre = fit(Basis::Legendre { degree: 2, domain }, &X_TABLE, &Y_TABLE, LstsqMethod::Svd);
+        let chebyshev = fit(Basis::Chebyshev { degree: 2, domain }, &X_TABLE, &Y_TABLE, LstsqMethod::Svd);
+        let f = chebyshev.as_fn();
+        for x in [0.5, 4.2, 12.0] {
+            let exact = x * x + x + 1.0;
+            assert!((legendre.eval(x) - exact).abs() < 1e-9);
+            assert!((f(x) - exact).abs() < 1e-9);
+        }
+    }
+}
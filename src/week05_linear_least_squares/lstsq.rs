@@ -0,0 +1,206 @@
+//! Linear least-squares solvers: normal equations, Householder QR and SVD.
+//!
+//! Each solver minimises ||A x - y||₂ for an m x n design matrix A,
+//! normally with m >= n. They differ in how much the conditioning of A hurts them: the
+//! normal equations work with AᵀA and so square the condition number, QR
+//! works with A directly, and the SVD can additionally detect and truncate
+//! rank deficiency.
+
+use ndarray::{s, Array1, Array2};
+
+use super::decomposition::{cholesky, householder_qr, solve_lower_triangular, solve_upper_triangular, svd};
+use super::types::{LstsqMethod, LstsqResult};
+
+/// Relative tolerance below which a pivot or singular value counts as zero.
+pub fn default_rank_tolerance(a: &Array2<f64>) -> f64 {
+    let (m, n) = a.dim();
+    m.max(n) as f64 * f64::EPSILON
+}
+
+/// Solves min ||A x - y||₂ with the chosen method.
+pub fn lstsq(a: &Array2<f64>, y: &Array1<f64>, method: LstsqMethod) -> LstsqResult {
+    match method {
+        LstsqMethod::NormalEquations => normal_equations(a, y),
+        LstsqMethod::Qr => qr_lstsq(a, y),
+        LstsqMethod::Svd => svd_lstsq(a, y, default_rank_tolerance(a)),
+    }
+}
+
+/// Solves the normal equations AᵀA x = Aᵀy by Cholesky factorisation.
+///
+/// The condition estimate is the ratio of the largest to smallest Cholesky
+/// pivot, which approximates cond(A). If the factorisation breaks down
+/// (AᵀA is not numerically positive definite) the coefficients are NaN and
+/// the rank counts the pivots completed before the breakdown.
+pub fn normal_equations(a: &Array2<f64>, y: &Array1<f64>) -> LstsqResult {
+    let n = a.ncols();
+    let ata = a.t().dot(a);
+    let aty = a.t().dot(y);
+
+    let (coefficients, rank, condition_number) = match cholesky(&ata) {
+        Some(l) => {
+            let z = solve_lower_triangular(&l, &aty);
+            let x = solve_upper_triangular(&l.t().to_owned(), &z);
+            let diag: Vec<f64> = (0..n).map(|i| l[[i, i]]).collect();
+            let max = diag.iter().cloned().fold(0.0, f64::max);
+            let min = diag.iter().cloned().fold(f64::INFINITY, f64::min);
+            let tol = default_rank_tolerance(a).sqrt() * max;
+            (x, diag.iter().filter(|&&d| d > tol).count(), max / min)
+        }
+        None => {
+            // Leading k x k block factors iff the first k pivots are positive.
+            let completed = (1..=n)
+                .take_while(|&k| cholesky(&ata.slice(s![..k, ..k]).to_owned()).is_some())
+                .count();
+            (Array1::from_elem(n, f64::NAN), completed, f64::INFINITY)
+        }
+    };
+
+    finish(a, y, coefficients, rank, condition_number, LstsqMethod::NormalEquations)
+}
+
+/// Solves the least-squares problem via Householder QR: R x = Qᵀy.
+///
+/// No column pivoting is used, so the rank and the condition estimate
+/// (ratio of the largest to smallest |R_ii|) are indicative only. R cannot
+/// be back-substituted when A has fewer rows than columns or a zero pivot,
+/// so those cases return the minimum-norm [`svd_lstsq`] solution instead,
+/// with `method` set to [`LstsqMethod::Svd`].
+pub fn qr_lstsq(a: &Array2<f64>, y: &Array1<f64>) -> LstsqResult {
+    let (m, n) = a.dim();
+    if m < n {
+        return svd_lstsq(a, y, default_rank_tolerance(a));
+    }
+    let qr = householder_qr(a);
+
+    let diag: Vec<f64> = (0..n).map(|i| qr.r[[i, i]].abs()).collect();
+    let max = diag.iter().cloned().fold(0.0, f64::max);
+    let min = diag.iter().cloned().fold(f64::INFINITY, f64::min);
+    let tol = default_rank_tolerance(a) * max;
+    let rank = diag.iter().filter(|&&d| d > tol).count();
+    if rank < n {
+        return svd_lstsq(a, y, default_rank_tolerance(a));
+    }
+
+    let x = solve_upper_triangular(&qr.r, &qr.q.t().dot(y));
+    finish(a, y, x, rank, max / min, LstsqMethod::Qr)
+}
+
+/// Solves the least-squares problem via the SVD, x = Σ (u_iᵀy / σ_i) v_i.
+///
+/// Singular values below `rcond * σ_max` are treated as zero, giving the
+/// minimum-norm solution when A is rank deficient. The condition number
+/// σ_max / σ_min is exact.
+pub fn svd_lstsq(a: &Array2<f64>, y: &Array1<f64>, rcond: f64) -> LstsqResult {
+    let d = svd(a);
+    let n = a.ncols();
+    let s_max = d.s.first().copied().unwrap_or(0.0);
+    let tol = rcond * s_max;
+
+    let mut x = Array1::<f64>::zeros(n);
+    let mut rank = 0;
+    for (k, &sigma) in d.s.iter().enumerate() {
+        if sigma > tol {
+            let coeff = d.u.column(k).dot(y) / sigma;
+            x.scaled_add(coeff, &d.v.column(k));
+            rank += 1;
+        }
+    }
+    let s_min = d.s.last().copied().unwrap_or(0.0);
+
+    finish(a, y, x, rank, s_max / s_min, LstsqMethod::Svd)
+}
+
+fn finish(
+    a: &Array2<f64>,
+    y: &Array1<f64>,
+    coefficients: Array1<f64>,
+    rank: usize,
+    condition_number: f64,
+    method: LstsqMethod,
+) -> LstsqResult {
+    let residuals = y - &a.dot(&coefficients);
+    let rss = residuals.dot(&residuals);
+    LstsqResult {
+        coefficients,
+        residuals,
+        rss,
+        rank,
+        condition_number,
+        method,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    const METHODS: [LstsqMethod; 3] = [LstsqMethod::NormalEquations, LstsqMethod::Qr, LstsqMethod::Svd];
+
+    #[test]
+    fn test_exact_line_fit() {
+        // y = 1 + 2x
+        let a = array![[1.0, 0.0], [1.0, 1.0], [1.0, 2.0], [1.0, 3.0]];
+        let y = array![1.0, 3.0, 5.0, 7.0];
+        for method in METHODS {
+            let result = lstsq(&a, &y, method);
+            assert!((result.coefficients[0] - 1.0).abs() < 1e-10, "{method:?}");
+            assert!((result.coefficients[1] - 2.0).abs() < 1e-10, "{method:?}");
+            assert!(result.rss < 1e-20);
+            assert_eq!(result.rank, 2);
+        }
+    }
+
+    #[test]
+    fn test_overdetermined_residuals_orthogonal() {
+        let a = array![[1.0, 0.0], [1.0, 1.0], [1.0, 2.0], [1.0, 3.0]];
+        let y = array![0.0, 2.0, 1.0, 4.0];
+        for method in METHODS {
+            let result = lstsq(&a, &y, method);
+            let at_r = a.t().dot(&result.residuals);
+            assert!(at_r.iter().all(|v| v.abs() < 1e-10), "{method:?}");
+        }
+    }
+
+    #[test]
+    fn test_svd_detects_rank_deficiency() {
+        // Second column is twice the first.
+        let a = array![[1.0, 2.0], [2.0, 4.0], [3.0, 6.0]];
+        let y = array![1.0, 2.0, 3.0];
+        let result = lstsq(&a, &y, LstsqMethod::Svd);
+        assert_eq!(result.rank, 1);
+        // Minimum-norm solution of x1 + 2 x2 = 1 is (0.2, 0.4).
+        assert!((result.coefficients[0] - 0.2).abs() < 1e-10);
+        assert!((result.coefficients[1] - 0.4).abs() < 1e-10);
+
+        let normal = lstsq(&a, &y, LstsqMethod::NormalEquations);
+        assert!(normal.rank < 2);
+
+        let qr = lstsq(&a, &y, LstsqMethod::Qr);
+        assert_eq!(qr.method, LstsqMethod::Svd);
+        assert!((qr.coefficients[0] - 0.2).abs() < 1e-10);
+        assert!((qr.coefficients[1] - 0.4).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_qr_underdetermined_falls_back_to_svd() {
+        // One equation x1 + x2 + x3 = 3; minimum-norm solution (1, 1, 1).
+        let a = array![[1.0, 1.0, 1.0]];
+        let y = array![3.0];
+        let result = lstsq(&a, &y, LstsqMethod::Qr);
+        assert_eq!(result.method, LstsqMethod::Svd);
+        for c in result.coefficients.iter() {
+            assert!((c - 1.0).abs() < 1e-12);
+        }
+        assert!(result.rss < 1e-20);
+    }
+
+    #[test]
+    fn test_condition_number_of_diagonal() {
+        let a = array![[10.0, 0.0], [0.0, 0.1], [0.0, 0.0]];
+        let y = array![1.0, 1.0, 0.0];
+        let result = lstsq(&a, &y, LstsqMethod::Svd);
+        assert!((result.condition_number - 100.0).abs() < 1e-9);
+    }
+}
@@ -1,3 +1,32 @@
 //! Week 5: Linear Least Squares
 //!
 //! Fitting linear models using least squares minimization.
+
+pub mod types;
+pub mod decomposition;
+pub mod lstsq;
+pub mod basis;
+pub mod fit;
+pub mod distributions;
+pub mod statistics;
+pub mod weighted;
+pub mod regularization;
+pub mod robust;
+pub mod bounded;
+pub mod orthogonal;
+pub mod crossval;
+pub mod recursive;
+pub mod problems;
+
+pub use types::{LstsqMethod, LstsqResult};
+pub use lstsq::{lstsq, normal_equations, qr_lstsq, svd_lstsq};
+pub use basis::{Basis, BasisFn};
+pub use fit::{fit, FittedModel};
+pub use statistics::{regression_statistics, Interval, PredictionBands, RegressionStatistics, ResidualDiagnostics};
+pub use weighted::{fit_weighted, weighted_lstsq, Measurements, WeightedLstsqResult, Weighting};
+pub use regularization::{difference_operator, log_spaced, LCurve, Tikhonov, TikhonovSolution};
+pub use robust::{fit_robust, mad_scale, robust_lstsq, RobustConfig, RobustLoss, RobustResult};
+pub use bounded::{bvls, nnls, BoundInfo, BoundStatus, BoundedLstsqResult};
+pub use orthogonal::{odr, tls_line, total_least_squares, OdrConfig, OdrResult, TlsLine, TlsResult};
+pub use crossval::{cross_validate, loo_residuals, select_model, CrossValidation, CvScore, ModelSelection};
+pub use recursive::{replay_csv, RecursiveLeastSquares, RlsUpdate};
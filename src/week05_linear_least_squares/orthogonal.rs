@@ -0,0 +1,338 @@
+//! Errors-in-variables regression: total least squares and orthogonal
+//! distance regression.
+//!
+//! Ordinary least squares attributes all the error to y, so when x is also
+//! measured with error the fitted slope is biased towards zero
+//! ("attenuation"). Total least squares instead finds the smallest
+//! perturbation of both A and y that makes A x = y consistent; it comes
+//! straight from the SVD of the augmented matrix [A y]. Orthogonal distance
+//! regression extends the idea to nonlinear models y = f(x; β) by
+//! estimating a correction δ_i to every x_i alongside β.
+//!
+//! The damped Levenberg-Marquardt loop in [`odr`] duplicates the week06
+//! `levenberg_marquardt` solver, which comes later in the course and so is
+//! not available here.
+
+use ndarray::{concatenate, s, Array1, Array2, Axis};
+
+use super::decomposition::svd;
+use super::lstsq::lstsq;
+use super::types::LstsqMethod;
+
+/// Result of a total least-squares solve.
+#[derive(Debug, Clone)]
+pub struct TlsResult {
+    /// Coefficients x of the corrected system (A + ΔA) x = y + Δy; NaN if
+    /// the problem has no TLS solution.
+    pub coefficients: Array1<f64>,
+    /// Smallest singular value of [A y]; its square is the TLS objective.
+    pub smallest_singular_value: f64,
+    /// Minimal correction ΔA to the design matrix.
+    pub correction_a: Array2<f64>,
+    /// Minimal correction Δy to the observations.
+    pub correction_y: Array1<f64>,
+}
+
+/// Total least squares: min ||[ΔA Δy]||_F subject to (A + ΔA) x = y + Δy.
+///
+/// The solution comes from the right singular vector v of [A y] belonging
+/// to the smallest singular value: x = -v[..n] / v[n]. If v[n] vanishes the
+/// problem is nongeneric and the coefficients are NaN.
+pub fn total_least_squares(a: &Array2<f64>, y: &Array1<f64>) -> TlsResult {
+    let (m, n) = a.dim();
+    assert!(m > n, "total least squares needs more observations than coefficients");
+
+    let augmented = concatenate(Axis(1), &[a.view(), y.view().insert_axis(Axis(1))]).expect("row counts match");
+    let d = svd(&augmented);
+    let v = d.v.column(n);
+    let sigma = d.s[n];
+
+    let coefficients = if v[n].abs() > f64::EPSILON * v.iter().fold(0.0_f64, |acc, x| acc.max(x.abs())) {
+        v.slice(s![..n]).mapv(|x| -x / v[n])
+    } else {
+        Array1::from_elem(n, f64::NAN)
+    };
+
+    // [ΔA Δy] = -σ u vᵀ removes the smallest singular component.
+    let u = d.u.column(n);
+    let correction = Array2::from_shape_fn((m, n + 1), |(i, j)| -sigma * u[i] * v[j]);
+
+    TlsResult {
+        coefficients,
+        smallest_singular_value: sigma,
+        correction_a: correction.slice(s![.., ..n]).to_owned(),
+        correction_y: correction.column(n).to_owned(),
+    }
+}
+
+/// Straight line fitted by total least squares.
+#[derive(Debug, Clone, Copy)]
+pub struct TlsLine {
+    pub intercept: f64,
+    pub slope: f64,
+    /// Sum of squared perpendicular distances from the data to the line.
+    pub orthogonal_rss: f64,
+}
+
+impl TlsLine {
+    /// Foot of the perpendicular from (x, y) onto the line.
+    pub fn project(&self, x: f64, y: f64) -> (f64, f64) {
+        let t = (x + self.slope * (y - self.intercept)) / (1.0 + self.slope * self.slope);
+        (t, self.intercept + self.slope * t)
+    }
+}
+
+/// Fits y = a + b x minimising perpendicular distances.
+///
+/// The intercept carries no error, so the data are centred first and TLS is
+/// applied to the slope alone; the line passes through the centroid.
+pub fn tls_line(x: &[f64], y: &[f64]) -> TlsLine {
+    let n = x.len() as f64;
+    let (x_mean, y_mean) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+    let a = Array2::from_shape_fn((x.len(), 1), |(i, _)| x[i] - x_mean);
+    let b = Array1::from_shape_fn(y.len(), |i| y[i] - y_mean);
+    let tls = total_least_squares(&a, &b);
+    let slope = tls.coefficients[0];
+    TlsLine {
+        intercept: y_mean - slope * x_mean,
+        slope,
+        orthogonal_rss: tls.smallest_singular_value.powi(2),
+    }
+}
+
+/// Settings for [`odr`].
+#[derive(Debug, Clone)]
+pub struct OdrConfig {
+    /// Maximum number of Levenberg-Marquardt steps.
+    pub max_iter: usize,
+    /// Stop when the relative decrease of the objective falls below this.
+    pub tol: f64,
+    /// Initial damping parameter.
+    pub initial_damping: f64,
+    /// Hold δ = 0, giving ordinary (vertical) nonlinear least squares.
+    pub fix_x: bool,
+}
+
+impl Default for OdrConfig {
+    fn default() -> Self {
+        Self { max_iter: 200, tol: 1e-12, initial_damping: 1e-3, fix_x: false }
+    }
+}
+
+impl OdrConfig {
+    pub fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    pub fn with_tol(mut self, tol: f64) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    /// Treat x as exact, fitting vertical residuals only.
+    pub fn with_fixed_x(mut self) -> Self {
+        self.fix_x = true;
+        self
+    }
+}
+
+/// Result of an orthogonal distance regression.
+#[derive(Debug, Clone)]
+pub struct OdrResult {
+    /// Model parameters β.
+    pub beta: Array1<f64>,
+    /// Estimated corrections δ to the x values.
+    pub delta: Array1<f64>,
+    /// Points (x + δ, f(x + δ; β)) on the fitted curve closest to the data.
+    pub fitted: Vec<(f64, f64)>,
+    /// Σ (ε_i / σ_y)² + (δ_i / σ_x)² at the solution.
+    pub weighted_sum_squares: f64,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+/// Orthogonal distance regression of y = f(x; β) with errors in x and y.
+///
+/// Minimises Σ [(f(x_i + δ_i; β) - y_i) / σ_y]² + [δ_i / σ_x]² jointly over
+/// β and δ by Levenberg-Marquardt with finite-difference derivatives. The
+/// ratio σ_x / σ_y sets how far the fit may move the x values.
+pub fn odr(
+    model: &dyn Fn(f64, &[f64]) -> f64,
+    x: &[f64],
+    y: &[f64],
+    sigma: (f64, f64),
+    beta0: &[f64],
+    config: &OdrConfig,
+) -> OdrResult {
+    let (sigma_x, sigma_y) = sigma;
+    let (m, p) = (x.len(), beta0.len());
+    let n_delta = if config.fix_x { 0 } else { m };
+    let n_params = p + n_delta;
+
+    let residuals = |theta: &Array1<f64>| -> Array1<f64> {
+        let beta = theta.slice(s![..p]).to_vec();
+        let mut r = Array1::<f64>::zeros(m + n_delta);
+        for i in 0..m {
+            let delta = if config.fix_x { 0.0 } else { theta[p + i] };
+            r[i] = (model(x[i] + delta, &beta) - y[i]) / sigma_y;
+            if !config.fix_x {
+                r[m + i] = delta / sigma_x;
+            }
+        }
+        r
+    };
+
+    let jacobian = |theta: &Array1<f64>| -> Array2<f64> {
+        let mut j = Array2::<f64>::zeros((m + n_delta, n_params));
+        let beta = theta.slice(s![..p]).to_vec();
+        for i in 0..m {
+            let xi = x[i] + if config.fix_x { 0.0 } else { theta[p + i] };
+            for k in 0..p {
+                let h = f64::EPSILON.cbrt() * beta[k].abs().max(1.0);
+                let mut plus = beta.clone();
+                let mut minus = beta.clone();
+                plus[k] += h;
+                minus[k] -= h;
+                j[[i, k]] = (model(xi, &plus) - model(xi, &minus)) / (2.0 * h * sigma_y);
+            }
+            if !config.fix_x {
+                let h = f64::EPSILON.cbrt() * xi.abs().max(1.0);
+                j[[i, p + i]] = (model(xi + h, &beta) - model(xi - h, &beta)) / (2.0 * h * sigma_y);
+                j[[m + i, p + i]] = 1.0 / sigma_x;
+            }
+        }
+        j
+    };
+
+    let mut theta = Array1::<f64>::zeros(n_params);
+    theta.slice_mut(s![..p]).assign(&Array1::from(beta0.to_vec()));
+    let mut r = residuals(&theta);
+    let mut cost = r.dot(&r);
+    let mut damping = config.initial_damping;
+    let mut converged = false;
+    let mut iterations = 0;
+
+    while iterations < config.max_iter {
+        iterations += 1;
+        let j = jacobian(&theta);
+
+        // Marquardt scaling: damp each parameter by its own curvature.
+        let scale = j.map_axis(Axis(0), |col| col.dot(&col).sqrt().max(1e-12));
+        let mut accepted = false;
+        while damping < 1e16 {
+            let damp = Array2::from_diag(&scale.mapv(|s| s * damping.sqrt()));
+            let lhs = concatenate(Axis(0), &[j.view(), damp.view()]).expect("column counts match");
+            let rhs = concatenate(Axis(0), &[r.mapv(|v| -v).view(), Array1::zeros(n_params).view()])
+                .expect("vectors concatenate");
+            let step = lstsq(&lhs, &rhs, LstsqMethod::Qr).coefficients;
+
+            let trial = &theta + &step;
+            let r_trial = residuals(&trial);
+            let cost_trial = r_trial.dot(&r_trial);
+            if cost_trial.is_finite() && cost_trial < cost {
+                let decrease = (cost - cost_trial) / cost.max(f64::MIN_POSITIVE);
+                theta = trial;
+                r = r_trial;
+                cost = cost_trial;
+                damping = (damping / 3.0).max(1e-15);
+                accepted = true;
+                if decrease < config.tol {
+                    converged = true;
+                }
+                break;
+            }
+            damping *= 4.0;
+        }
+        // With no step accepted the fit is stuck (or the cost is not finite),
+        // so stop without claiming convergence.
+        if converged || !accepted {
+            break;
+        }
+    }
+
+    let beta = theta.slice(s![..p]).to_owned();
+    let delta = if config.fix_x { Array1::zeros(m) } else { theta.slice(s![p..]).to_owned() };
+    let beta_vec = beta.to_vec();
+    let fitted = (0..m).map(|i| (x[i] + delta[i], model(x[i] + delta[i], &beta_vec))).collect();
+
+    OdrResult { beta, delta, fitted, weighted_sum_squares: cost, iterations, converged }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::rng::Rng;
+    use ndarray::array;
+
+    #[test]
+    fn test_tls_exact_data() {
+        let a = array![[1.0, 0.0], [0.0, 1.0], [1.0, 1.0], [2.0, 1.0]];
+        let y = a.dot(&array![2.0, -1.0]);
+        let tls = total_least_squares(&a, &y);
+        assert!((tls.coefficients[0] - 2.0).abs() < 1e-12);
+        assert!((tls.coefficients[1] + 1.0).abs() < 1e-12);
+        assert!(tls.smallest_singular_value < 1e-12);
+    }
+
+    #[test]
+    fn test_tls_corrections_make_system_consistent() {
+        let a = array![[1.0, 0.1], [0.2, 1.0], [1.1, 0.9], [2.0, 1.2], [0.4, 0.3]];
+        let y = array![1.9, -0.8, 1.4, 2.9, 0.5];
+        let tls = total_least_squares(&a, &y);
+        let lhs = (&a + &tls.correction_a).dot(&tls.coefficients);
+        let rhs = &y + &tls.correction_y;
+        assert!((&lhs - &rhs).iter().all(|v| v.abs() < 1e-10));
+        let frobenius = tls.correction_a.iter().chain(tls.correction_y.iter()).map(|v| v * v).sum::<f64>();
+        assert!((frobenius - tls.smallest_singular_value.powi(2)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_tls_line_is_symmetric_in_x_and_y() {
+        // Swapping the axes gives the reciprocal slope, unlike OLS.
+        let x = [0.0, 1.0, 2.0, 3.0, 4.0];
+        let y = [0.3, 1.6, 4.4, 5.7, 8.2];
+        let line = tls_line(&x, &y);
+        let swapped = tls_line(&y, &x);
+        assert!((line.slope * swapped.slope - 1.0).abs() < 1e-12);
+        let (px, py) = line.project(x[0], y[0]);
+        // The projection lies on the line and the offset is perpendicular.
+        assert!((py - (line.intercept + line.slope * px)).abs() < 1e-12);
+        assert!(((x[0] - px) + line.slope * (y[0] - py)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_odr_line_matches_tls() {
+        let mut rng = Rng::new(38);
+        let x: Vec<f64> = (0..30).map(|i| i as f64 * 0.2 + 0.3 * rng.normal()).collect();
+        let y: Vec<f64> = (0..30).map(|i| 1.0 + 2.0 * (i as f64 * 0.2) + 0.3 * rng.normal()).collect();
+        let line = |x: f64, b: &[f64]| b[0] + b[1] * x;
+        let result = odr(&line, &x, &y, (1.0, 1.0), &[0.0, 1.0], &OdrConfig::default());
+        let tls = tls_line(&x, &y);
+        assert!(result.converged);
+        assert!((result.beta[0] - tls.intercept).abs() < 1e-6);
+        assert!((result.beta[1] - tls.slope).abs() < 1e-6);
+        assert!((result.weighted_sum_squares - tls.orthogonal_rss).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_odr_fixed_x_is_ordinary_least_squares() {
+        let x = [0.0, 1.0, 2.0, 3.0];
+        let y = [1.1, 2.9, 5.2, 6.8];
+        let line = |x: f64, b: &[f64]| b[0] + b[1] * x;
+        let result = odr(&line, &x, &y, (1.0, 1.0), &[0.0, 0.0], &OdrConfig::default().with_fixed_x());
+        // OLS slope = Sxy / Sxx = 9.7 / 5.
+        assert!((result.beta[1] - 1.94).abs() < 1e-8);
+        assert!(result.delta.iter().all(|&d| d == 0.0));
+    }
+
+    #[test]
+    fn test_odr_non_finite_start_does_not_converge() {
+        let x = [1.0, 2.0, 3.0];
+        let y = [0.5, 1.0, 1.5];
+        let model = |x: f64, b: &[f64]| b[0].ln() * x;
+        let result = odr(&model, &x, &y, (1.0, 1.0), &[-1.0], &OdrConfig::default());
+        assert!(!result.converged);
+        assert!(result.weighted_sum_squares.is_nan());
+    }
+}
@@ -0,0 +1,874 @@
+//! EG551T Practical Problems - Linear Least Squares
+//!
+//! Fit p(x) = 1 + x + x² + ... + x^10 sampled at 30 points on [0, 1].
+//! The monomial design matrix is badly conditioned, which separates the
+//! normal equations from QR and SVD.
+//!
+//! Recover y = x² + x + 1 from the week 1 table data.
+//!
+//! Report regression statistics for a quadratic fitted to noisy data
+//! generated from y = 2 + 1.5x - 0.12x² + N(0, 0.4²).
+//!
+//! Calibrate a straight line against data/week05_calibration.csv, whose
+//! uncertainties grow with x, by ordinary, weighted and generalised LS.
+//!
+//! Regularise a degree-12 polynomial fit to noisy samples of sin(2πx), and
+//! deblur a signal convolved with a Gaussian, choosing λ by the L-curve and
+//! by generalised cross-validation.
+//!
+//! Fit a line to data with gross outliers by IRLS with Huber, Tukey and L1
+//! losses, and flag the points the robust fits discount.
+//!
+//! Unmix a noisy absorption spectrum into four overlapping component
+//! spectra with non-negative and box-bounded concentrations.
+//!
+//! Fit a line and an exponential to data with errors in both x and y,
+//! comparing vertical (ordinary) with orthogonal (TLS / ODR) residuals.
+//!
+//! Choose the degree of a polynomial fit to noisy sin(2πx) by k-fold and
+//! leave-one-out cross-validation, and plot the bias-variance tradeoff.
+//!
+//! Replay logged data through recursive least squares with forgetting to
+//! track a gain that changes part-way through the record.
+
+use ndarray::{Array1, Array2};
+
+use crate::common::csv::read_csv;
+use crate::common::rng::Rng;
+
+use crate::week01_graphing::problems::{X_TABLE, Y_TABLE};
+use crate::week05_linear_least_squares::statistics::RegressionStatistics;
+use crate::week05_linear_least_squares::{
+    bvls, difference_operator, fit, fit_robust, fit_weighted, lstsq, nnls, odr, replay_csv, select_model, svd_lstsq,
+    tls_line, Basis, BoundedLstsqResult, CrossValidation, FittedModel, LstsqMethod, LstsqResult, Measurements,
+    ModelSelection, OdrConfig, OdrResult, RecursiveLeastSquares, RobustConfig, RobustLoss, RobustResult, Tikhonov,
+    TikhonovSolution, WeightedLstsqResult, Weighting,
+};
+
+#[cfg(feature = "plotting")]
+use crate::common::csv::CsvRowReader;
+#[cfg(feature = "plotting")]
+use crate::common::plot::{self, PlotConfig};
+
+/// Noisy samples of y = 2 + 1.5x - 0.12x² on [0, 10].
+pub const NOISY_X: [f64; 21] = [
+    0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.5, 4.0, 4.5, 5.0, 5.5, 6.0, 6.5, 7.0, 7.5, 8.0, 8.5, 9.0, 9.5, 10.0,
+];
+pub const NOISY_Y: [f64; 21] = [
+    1.405, 2.674, 3.409, 3.916, 4.58, 6.359, 4.878, 6.181, 6.154, 6.315, 6.654, 6.353, 6.279, 5.862, 6.965, 6.249,
+    5.844, 6.141, 6.234, 5.558, 4.684,
+];
+
+/// Calibration data with per-point standard deviations.
+pub const CALIBRATION_CSV: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/week05_calibration.csv");
+
+/// Compare normal equations, QR and SVD on an ill-conditioned polynomial fit.
+pub fn problem_ill_conditioned_fit() -> Vec<LstsqResult> {
+    let degree = 10;
+    let x = Array1::linspace(0.0, 1.0, 30);
+    let a = Basis::Monomial { degree }.design_matrix(x.as_slice().unwrap());
+    let true_coefficients = Array1::<f64>::ones(degree + 1);
+    let y = a.dot(&true_coefficients);
+
+    println!("  Degree {} monomial fit, {} points on [0, 1], all true coefficients = 1", degree, x.len());
+    println!("  {:<18} {:>5} {:>12} {:>12} {:>14}", "method", "rank", "cond(A)", "RSS", "max |x - x*|");
+
+    [LstsqMethod::NormalEquations, LstsqMethod::Qr, LstsqMethod::Svd]
+        .into_iter()
+        .map(|method| {
+            let result = lstsq(&a, &y, method);
+            let error = (&result.coefficients - &true_coefficients)
+                .iter()
+                .fold(0.0_f64, |acc, v| acc.max(v.abs()));
+            println!(
+                "  {:<18} {:>5} {:>12.3e} {:>12.3e} {:>14.3e}",
+                format!("{:?}", method),
+                result.rank,
+                result.condition_number,
+                result.rss,
+                error
+            );
+            result
+        })
+        .collect()
+}
+
+/// Fit several bases to the week 1 table and print the coefficients.
+///
+/// Returns the monomial fit, whose coefficients should all be 1.
+pub fn problem_table_fit() -> FittedModel {
+    let domain = (X_TABLE[0], X_TABLE[X_TABLE.len() - 1]);
+    let bases = [
+        Basis::Legendre { degree: 2, domain },
+        Basis::Chebyshev { degree: 2, domain },
+        Basis::Monomial { degree: 2 },
+    ];
+
+    println!("  Week 1 table data, degree-2 fits:");
+    let mut models: Vec<FittedModel> = bases
+        .into_iter()
+        .map(|basis| {
+            let model = fit(basis, &X_TABLE, &Y_TABLE, LstsqMethod::Qr);
+            let coefficients: Vec<String> = model.coefficients().iter().map(|c| format!("{:.6}", c)).collect();
+            println!("    {:<22} c = [{}]  RSS = {:.3e}", model.basis.label(), coefficients.join(", "), model.fit.rss);
+            model
+        })
+        .collect();
+    models.pop().unwrap()
+}
+
+/// Plot the week 1 table data, its quadratic fit and the residuals.
+#[cfg(feature = "plotting")]
+pub fn problem_table_fit_plot(dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let model = fit(Basis::Monomial { degree: 2 }, &X_TABLE, &Y_TABLE, LstsqMethod::Qr);
+    let x_fit: Vec<f64> = Array1::linspace(X_TABLE[0], X_TABLE[X_TABLE.len() - 1], 200).to_vec();
+    let y_fit = model.predict(&x_fit);
+
+    let c = model.coefficients();
+    let config = PlotConfig {
+        title: format!("Table Data: y = {:.3} + {:.3}x + {:.3}x²", c[0], c[1], c[2]),
+        x_label: String::from("x"),
+        y_label: String::from("y"),
+        ..PlotConfig::default()
+    };
+
+    plot::fit_residual_plot(
+        &format!("{}/table_fit.png", dir),
+        &X_TABLE,
+        &Y_TABLE,
+        &x_fit,
+        &y_fit,
+        model.fit.residuals.as_slice().unwrap(),
+        &config,
+    )
+}
+
+/// Fit a quadratic to the noisy data and print a regression report.
+pub fn problem_regression_report() -> RegressionStatistics {
+    let model = fit(Basis::Monomial { degree: 2 }, &NOISY_X, &NOISY_Y, LstsqMethod::Qr);
+    let stats = model.statistics(&NOISY_X, &NOISY_Y);
+
+    println!(
+        "  Quadratic fit to {} noisy points (true c = [2, 1.5, -0.12]), 95% intervals:",
+        stats.n_observations
+    );
+    println!(
+        "  {:<6} {:>10} {:>10} {:>9} {:>10} {:>22}",
+        "coef", "estimate", "std err", "t", "p-value", "95% CI"
+    );
+    for (k, interval) in stats.parameter_intervals(0.95).iter().enumerate() {
+        println!(
+            "  c{:<5} {:>10.4} {:>10.4} {:>9.2} {:>10.2e}   [{:>8.4}, {:>8.4}]",
+            k, interval.estimate, stats.standard_errors[k], stats.t_statistics[k], stats.p_values[k], interval.lower,
+            interval.upper
+        );
+    }
+    println!(
+        "  s = {:.4} on {} dof, R² = {:.4}, adj R² = {:.4}, AIC = {:.2}, BIC = {:.2}",
+        stats.diagnostics.std_dev, stats.dof, stats.r_squared, stats.adj_r_squared, stats.aic, stats.bic
+    );
+
+    let d = &stats.diagnostics;
+    let (worst, cook) = d
+        .cooks_distance
+        .iter()
+        .enumerate()
+        .fold((0, 0.0), |best, (i, &c)| if c > best.1 { (i, c) } else { best });
+    println!(
+        "  Durbin-Watson = {:.3}, max |studentised residual| = {:.3}, max Cook's D = {:.3} at x = {}",
+        d.durbin_watson, d.max_abs_studentized, cook, NOISY_X[worst]
+    );
+
+    let row = model.basis.evaluate(12.0);
+    let ci = stats.confidence_interval(&row, 0.95);
+    let pi = stats.prediction_interval(&row, 0.95);
+    println!(
+        "  At x = 12: mean response {:.3} in [{:.3}, {:.3}], new observation in [{:.3}, {:.3}]",
+        ci.estimate, ci.lower, ci.upper, pi.lower, pi.upper
+    );
+    stats
+}
+
+/// Fit the calibration line ignoring, using, and correlating the uncertainties.
+///
+/// Returns the weighted fit.
+pub fn problem_weighted_calibration() -> Result<WeightedLstsqResult, Box<dyn std::error::Error>> {
+    let data = Measurements::from_csv(CALIBRATION_CSV, "x", "y", Some("sigma"))?;
+    let sigma = data.sigma.clone().unwrap();
+    let basis = || Basis::Monomial { degree: 1 };
+
+    println!("  {} calibration points, σ from {} to {}", data.x.len(), sigma[0], sigma[sigma.len() - 1]);
+    println!("  {:<26} {:>18} {:>18} {:>8} {:>8}", "fit", "intercept", "slope", "χ²/ν", "p");
+
+    let ols = fit(basis(), &data.x, &data.y, LstsqMethod::Qr);
+    let ols_stats = ols.statistics(&data.x, &data.y);
+    let c = ols.coefficients();
+    println!(
+        "  {:<26} {:>8.4} ± {:<7.4} {:>8.4} ± {:<7.4}",
+        "ordinary (s² estimated)", c[0], ols_stats.standard_errors[0], c[1], ols_stats.standard_errors[1]
+    );
+
+    let print_weighted = |label: &str, result: &WeightedLstsqResult| {
+        let c = &result.fit.coefficients;
+        let se = &result.standard_errors;
+        println!(
+            "  {:<26} {:>8.4} ± {:<7.4} {:>8.4} ± {:<7.4} {:>8.3} {:>8.3}",
+            label, c[0], se[0], c[1], se[1], result.reduced_chi_squared, result.p_value
+        );
+    };
+
+    let (_, weighted) = fit_weighted(basis(), &data.x, &data.y, &data.weighting().unwrap(), LstsqMethod::Qr)?;
+    print_weighted("weighted (1/σ²)", &weighted);
+
+    // Same σ, but neighbouring errors correlated with ρ^|i-j|, ρ = 0.5.
+    let n = sigma.len();
+    let covariance =
+        ndarray::Array2::from_shape_fn((n, n), |(i, j)| sigma[i] * sigma[j] * 0.5_f64.powi((i as i32 - j as i32).abs()));
+    let (_, generalised) = fit_weighted(basis(), &data.x, &data.y, &Weighting::Covariance(covariance), LstsqMethod::Qr)?;
+    print_weighted("generalised (AR(1), ρ=0.5)", &generalised);
+
+    Ok(weighted)
+}
+
+/// Degree of the over-flexible polynomial in the ridge problem.
+const RIDGE_DEGREE: usize = 12;
+
+/// 30 samples of sin(2πx) on [0, 1] with N(0, 0.05²) noise.
+fn noisy_sine_samples() -> (Vec<f64>, Vec<f64>) {
+    let mut rng = Rng::new(551);
+    let x: Vec<f64> = Array1::linspace(0.0, 1.0, 30).to_vec();
+    let y = x
+        .iter()
+        .map(|&x| (2.0 * std::f64::consts::PI * x).sin() + 0.05 * rng.normal())
+        .collect();
+    (x, y)
+}
+
+/// Largest |p(x) - sin(2πx)| over a fine grid on [0, 1].
+fn sine_error(coefficients: &Array1<f64>) -> f64 {
+    let basis = Basis::Monomial { degree: RIDGE_DEGREE };
+    Array1::linspace(0.0, 1.0, 201).iter().fold(0.0_f64, |acc, &x| {
+        let p: f64 = basis.evaluate(x).iter().zip(coefficients.iter()).map(|(b, c)| b * c).sum();
+        acc.max((p - (2.0 * std::f64::consts::PI * x).sin()).abs())
+    })
+}
+
+/// Ridge-regularise a degree-12 monomial fit, choosing λ by L-curve and GCV.
+pub fn problem_ridge_polynomial() -> Vec<TikhonovSolution> {
+    let (x, y) = noisy_sine_samples();
+    let a = Basis::Monomial { degree: RIDGE_DEGREE }.design_matrix(&x);
+    let y = Array1::from(y);
+
+    let tikhonov = Tikhonov::ridge(&a, &y);
+    let lambdas = tikhonov.default_lambdas(141);
+    let corner = tikhonov.l_curve(&lambdas).corner_lambda();
+    let gcv = tikhonov.gcv_lambda(&lambdas);
+
+    let plain = svd_lstsq(&a, &y, 0.0);
+    println!("  Degree {} monomial fit to {} noisy samples of sin(2πx), σ = 0.05", RIDGE_DEGREE, x.len());
+    println!(
+        "  {:<16} {:>10} {:>12} {:>12} {:>10} {:>14}",
+        "choice", "λ", "||x||", "||Ax - y||", "eff. p", "max |p - sin|"
+    );
+    println!(
+        "  {:<16} {:>10} {:>12.3e} {:>12.4e} {:>10} {:>14.3e}",
+        "unregularised",
+        "0",
+        plain.coefficients.dot(&plain.coefficients).sqrt(),
+        plain.rss.sqrt(),
+        RIDGE_DEGREE + 1,
+        sine_error(&plain.coefficients)
+    );
+
+    [("L-curve corner", corner), ("GCV minimum", gcv)]
+        .into_iter()
+        .map(|(label, lambda)| {
+            let sol = tikhonov.solve(lambda);
+            println!(
+                "  {:<16} {:>10.2e} {:>12.3e} {:>12.4e} {:>10.2} {:>14.3e}",
+                label,
+                lambda,
+                sol.solution_seminorm,
+                sol.residual_norm,
+                sol.effective_parameters,
+                sine_error(&sol.coefficients)
+            );
+            sol
+        })
+        .collect()
+}
+
+/// Gaussian blurring matrix on n points of [0, 1] and a piecewise-smooth signal.
+fn blur_problem(n: usize) -> (Array2<f64>, Array1<f64>, Array1<f64>) {
+    let width: f64 = 0.03;
+    let t = Array1::<f64>::linspace(0.0, 1.0, n);
+    let h = 1.0 / (n - 1) as f64;
+    let norm = h / (width * (2.0 * std::f64::consts::PI).sqrt());
+    let a = Array2::from_shape_fn((n, n), |(i, j)| norm * (-(t[i] - t[j]).powi(2) / (2.0 * width * width)).exp());
+    let truth = t.mapv(|t| (-((t - 0.3) / 0.08).powi(2)).exp() + 0.6 * (-((t - 0.7) / 0.15).powi(2)).exp());
+
+    let mut rng = Rng::new(35);
+    let blurred = a.dot(&truth).mapv(|v| v + 1e-3 * rng.normal());
+    (a, truth, blurred)
+}
+
+/// Deblur a noisy signal with identity and second-difference regularisation.
+///
+/// Returns the reconstruction closest to the true signal.
+pub fn problem_deblur() -> TikhonovSolution {
+    let n = 80;
+    let (a, truth, blurred) = blur_problem(n);
+    let relative_error = |x: &Array1<f64>| {
+        let e = x - &truth;
+        (e.dot(&e) / truth.dot(&truth)).sqrt()
+    };
+
+    let plain = svd_lstsq(&a, &blurred, 0.0);
+    println!("  Gaussian blur of width 0.03 on {} points, noise σ = 1e-3, cond(A) = {:.2e}", n, plain.condition_number);
+    println!("  {:<28} {:>10} {:>10} {:>10} {:>14}", "operator / choice", "λ", "||Ax - y||", "eff. p", "relative error");
+    println!(
+        "  {:<28} {:>10} {:>10.3e} {:>10} {:>14.3e}",
+        "unregularised",
+        "0",
+        plain.rss.sqrt(),
+        n,
+        relative_error(&plain.coefficients)
+    );
+
+    let mut best: Option<(f64, TikhonovSolution)> = None;
+    for (name, l) in [("identity", Array2::eye(n)), ("second difference", difference_operator(n, 2))] {
+        let tikhonov = Tikhonov::new(&a, &blurred, &l);
+        let lambdas = tikhonov.default_lambdas(161);
+        let corner = tikhonov.l_curve(&lambdas).corner_lambda();
+        let gcv = tikhonov.gcv_lambda(&lambdas);
+        for (choice, lambda) in [("L-curve", corner), ("GCV", gcv)] {
+            let sol = tikhonov.solve(lambda);
+            let error = relative_error(&sol.coefficients);
+            println!(
+                "  {:<28} {:>10.2e} {:>10.3e} {:>10.2} {:>14.3e}",
+                format!("{}, {}", name, choice),
+                lambda,
+                sol.residual_norm,
+                sol.effective_parameters,
+                error
+            );
+            if !matches!(best, Some((best_error, _)) if best_error <= error) {
+                best = Some((error, sol));
+            }
+        }
+    }
+    best.expect("at least one reconstruction").1
+}
+
+/// Plot the L-curve of the ridge problem and the deblurred signal.
+#[cfg(feature = "plotting")]
+pub fn problem_regularization_plots(dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (x, y) = noisy_sine_samples();
+    let a = Basis::Monomial { degree: RIDGE_DEGREE }.design_matrix(&x);
+    let tikhonov = Tikhonov::ridge(&a, &Array1::from(y));
+    let curve = tikhonov.l_curve(&tikhonov.default_lambdas(141));
+
+    let log_rho: Vec<f64> = curve.residual_norms.iter().map(|r| r.log10()).collect();
+    let log_eta: Vec<f64> = curve.solution_seminorms.iter().map(|e| e.log10()).collect();
+    let config = PlotConfig {
+        title: format!("L-curve, degree {} ridge fit (corner λ = {:.1e})", RIDGE_DEGREE, curve.corner_lambda()),
+        x_label: String::from("log10 ||Ax - y||"),
+        y_label: String::from("log10 ||x||"),
+        ..PlotConfig::default()
+    };
+    plot::line_plot_with_markers(
+        &format!("{}/l_curve.png", dir),
+        &log_rho,
+        &log_eta,
+        &[(log_rho[curve.corner], log_eta[curve.corner])],
+        &config,
+    )?;
+
+    let n = 80;
+    let (a, truth, blurred) = blur_problem(n);
+    let tikhonov = Tikhonov::new(&a, &blurred, &difference_operator(n, 2));
+    let reconstruction = tikhonov.solve(tikhonov.gcv_lambda(&tikhonov.default_lambdas(161)));
+    let t: Vec<f64> = Array1::linspace(0.0, 1.0, n).to_vec();
+    let config = PlotConfig {
+        title: format!("Deblurring, second-difference Tikhonov (λ = {:.1e})", reconstruction.lambda),
+        x_label: String::from("t"),
+        y_label: String::from("signal"),
+        ..PlotConfig::default()
+    };
+    plot::multi_line_plot(
+        &format!("{}/deblur.png", dir),
+        &t,
+        &[
+            ("True signal", truth.as_slice().unwrap()),
+            ("Blurred + noise", blurred.as_slice().unwrap()),
+            ("Reconstruction (GCV)", reconstruction.coefficients.as_slice().unwrap()),
+        ],
+        &config,
+    )
+}
+
+/// Weight below which a robust fit is taken to have rejected a point.
+const OUTLIER_WEIGHT: f64 = 0.5;
+
+/// 25 noisy points on y = 1 + 0.5x with five gross outliers.
+fn contaminated_samples() -> (Vec<f64>, Vec<f64>) {
+    let mut rng = Rng::new(36);
+    let x: Vec<f64> = Array1::linspace(0.0, 12.0, 25).to_vec();
+    let mut y: Vec<f64> = x.iter().map(|&x| 1.0 + 0.5 * x + 0.2 * rng.normal()).collect();
+    for (i, shift) in [(2, -3.0), (9, 4.0), (19, 5.0), (21, 6.5), (23, 7.0)] {
+        y[i] += shift;
+    }
+    (x, y)
+}
+
+/// Compare ordinary least squares with Huber, Tukey and L1 IRLS fits.
+///
+/// Returns the Tukey fit.
+pub fn problem_robust_line() -> RobustResult {
+    let (x, y) = contaminated_samples();
+    let basis = || Basis::Monomial { degree: 1 };
+
+    let ols = fit(basis(), &x, &y, LstsqMethod::Qr);
+    println!("  Line y = 1 + 0.5x, {} points, 5 gross outliers at indices 2, 9, 19, 21, 23", x.len());
+    println!(
+        "  {:<18} {:>10} {:>10} {:>8} {:>6}   flagged (w < {})",
+        "fit", "intercept", "slope", "scale", "iters", OUTLIER_WEIGHT
+    );
+    println!("  {:<18} {:>10.4} {:>10.4} {:>8} {:>6}", "ordinary", ols.coefficients()[0], ols.coefficients()[1], "-", "-");
+
+    let mut tukey = None;
+    for loss in [RobustLoss::huber(), RobustLoss::tukey(), RobustLoss::L1] {
+        let (model, result) = fit_robust(basis(), &x, &y, loss, &RobustConfig::default());
+        let flagged = if loss == RobustLoss::L1 {
+            String::from("(weights unbounded)")
+        } else {
+            format!("{:?}", result.outliers(OUTLIER_WEIGHT))
+        };
+        println!(
+            "  {:<18} {:>10.4} {:>10.4} {:>8.4} {:>6}   {}",
+            loss.label(),
+            model.coefficients()[0],
+            model.coefficients()[1],
+            result.scale,
+            result.iterations,
+            flagged
+        );
+        if loss == RobustLoss::tukey() {
+            tukey = Some(result);
+        }
+    }
+    tukey.unwrap()
+}
+
+/// Plot the contaminated data, colouring points the Tukey fit rejects.
+#[cfg(feature = "plotting")]
+pub fn problem_robust_line_plot(dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (x, y) = contaminated_samples();
+    let ols = fit(Basis::Monomial { degree: 1 }, &x, &y, LstsqMethod::Qr);
+    let (tukey, result) =
+        fit_robust(Basis::Monomial { degree: 1 }, &x, &y, RobustLoss::tukey(), &RobustConfig::default());
+
+    let mut inliers = Vec::new();
+    let mut outliers = Vec::new();
+    for ((&x, &y), &w) in x.iter().zip(y.iter()).zip(result.weights.iter()) {
+        if w < OUTLIER_WEIGHT {
+            outliers.push((x, y));
+        } else {
+            inliers.push((x, y));
+        }
+    }
+    let line = |model: &FittedModel| -> Vec<(f64, f64)> { [0.0, 12.0].iter().map(|&x| (x, model.eval(x))).collect() };
+
+    let config = PlotConfig {
+        title: String::from("Robust Line Fit (Tukey bisquare IRLS)"),
+        x_label: String::from("x"),
+        y_label: String::from("y"),
+        ..PlotConfig::default()
+    };
+    plot::scatter_plot(
+        &format!("{}/robust_fit.png", dir),
+        &[("Data", &inliers), (&format!("Outliers (w < {})", OUTLIER_WEIGHT), &outliers)],
+        &[("Ordinary LS", &line(&ols)), ("Tukey IRLS", &line(&tukey))],
+        &config,
+    )
+}
+
+/// Estimate component concentrations in a mixture spectrum.
+///
+/// The component spectra overlap strongly, so unconstrained least squares
+/// returns a negative concentration for the absent component. NNLS pins it
+/// at zero; BVLS additionally enforces a solubility limit of 0.3 on C.
+pub fn problem_spectral_unmixing() -> BoundedLstsqResult {
+    let names = ["A", "B", "C", "D"];
+    let centres = [480.0, 505.0, 560.0, 600.0];
+    let truth = Array1::from(vec![0.8, 0.0, 0.3, 0.6]);
+
+    let wavelengths = Array1::<f64>::linspace(400.0, 700.0, 61);
+    let a = Array2::from_shape_fn((wavelengths.len(), centres.len()), |(i, j)| {
+        (-((wavelengths[i] - centres[j]) / 40.0).powi(2)).exp()
+    });
+    let mut rng = Rng::new(1);
+    let y = a.dot(&truth).mapv(|v| v + 0.02 * rng.normal());
+
+    let ols = lstsq(&a, &y, LstsqMethod::Qr);
+    let non_negative = nnls(&a, &y);
+    let bounded = bvls(&a, &y, &Array1::zeros(4), &Array1::from(vec![1.0, 1.0, 0.3, 1.0]));
+
+    println!("  Four overlapping Gaussian bands (width 40 nm), 61 wavelengths, noise σ = 0.02");
+    println!("  {:<10} {:>8} {:>8} {:>8} {:>8} {:>12}", "solver", "A", "B", "C", "D", "RSS");
+    let row = |label: &str, c: &Array1<f64>, rss: String| {
+        println!("  {:<10} {:>8.4} {:>8.4} {:>8.4} {:>8.4} {:>12}", label, c[0], c[1], c[2], c[3], rss);
+    };
+    row("true", &truth, String::from("-"));
+    row("ordinary", &ols.coefficients, format!("{:.4e}", ols.rss));
+    row("NNLS", &non_negative.coefficients, format!("{:.4e}", non_negative.rss));
+    row("BVLS", &bounded.coefficients, format!("{:.4e}", bounded.rss));
+
+    println!("  BVLS active set (0 <= c <= 1, C <= 0.3):");
+    for line in bounded.active_set_report(&names) {
+        println!("    {}", line);
+    }
+    bounded
+}
+
+/// 25 points on y = 1 + 2x with N(0, 0.4²) errors in both x and y.
+fn errors_in_variables_line(rng: &mut Rng) -> (Vec<f64>, Vec<f64>) {
+    let x_true = Array1::<f64>::linspace(0.0, 5.0, 25);
+    let x = x_true.iter().map(|&x| x + 0.4 * rng.normal()).collect();
+    let y = x_true.iter().map(|&x| 1.0 + 2.0 * x + 0.4 * rng.normal()).collect();
+    (x, y)
+}
+
+/// 20 points on y = 2 exp(0.3x) with σ_x = 0.15 and σ_y = 0.3.
+fn errors_in_variables_exponential() -> (Vec<f64>, Vec<f64>) {
+    let mut rng = Rng::new(380);
+    let x_true = Array1::<f64>::linspace(0.0, 6.0, 20);
+    let x = x_true.iter().map(|&x| x + 0.15 * rng.normal()).collect();
+    let y = x_true.iter().map(|&x| 2.0 * (0.3 * x).exp() + 0.3 * rng.normal()).collect();
+    (x, y)
+}
+
+fn exponential(x: f64, beta: &[f64]) -> f64 {
+    beta[0] * (beta[1] * x).exp()
+}
+
+/// Compare OLS with TLS on a line and with ODR on an exponential.
+///
+/// Returns the ODR fit of the exponential.
+pub fn problem_errors_in_variables() -> OdrResult {
+    let mut rng = Rng::new(38);
+    let (x, y) = errors_in_variables_line(&mut rng);
+    let ols = fit(Basis::Monomial { degree: 1 }, &x, &y, LstsqMethod::Qr);
+    let tls = tls_line(&x, &y);
+    println!("  Line y = 1 + 2x, 25 points, σ_x = σ_y = 0.4");
+    println!("    ordinary:  intercept {:.4}, slope {:.4}", ols.coefficients()[0], ols.coefficients()[1]);
+    println!("    total LS:  intercept {:.4}, slope {:.4}", tls.intercept, tls.slope);
+
+    // Attenuation is systematic: average over many replicate data sets.
+    let trials = 500;
+    let (mut ols_mean, mut tls_mean) = (0.0, 0.0);
+    for _ in 0..trials {
+        let (x, y) = errors_in_variables_line(&mut rng);
+        ols_mean += fit(Basis::Monomial { degree: 1 }, &x, &y, LstsqMethod::Qr).coefficients()[1] / trials as f64;
+        tls_mean += tls_line(&x, &y).slope / trials as f64;
+    }
+    println!("    mean slope over {} replicates: ordinary {:.4}, total LS {:.4}", trials, ols_mean, tls_mean);
+
+    let (x, y) = errors_in_variables_exponential();
+    let sigma = (0.15, 0.3);
+    let vertical = odr(&exponential, &x, &y, sigma, &[1.0, 0.1], &OdrConfig::default().with_fixed_x());
+    let orthogonal = odr(&exponential, &x, &y, sigma, &[1.0, 0.1], &OdrConfig::default());
+    println!("  Exponential y = 2 exp(0.3x), 20 points, σ_x = 0.15, σ_y = 0.3");
+    for (label, result) in [("vertical", &vertical), ("ODR", &orthogonal)] {
+        println!(
+            "    {:<9} a = {:.4}, b = {:.4}, weighted SS = {:.3}, {} iterations",
+            format!("{}:", label),
+            result.beta[0],
+            result.beta[1],
+            result.weighted_sum_squares,
+            result.iterations
+        );
+    }
+    orthogonal
+}
+
+/// Plot vertical against orthogonal residuals for the line and the exponential.
+#[cfg(feature = "plotting")]
+pub fn problem_errors_in_variables_plot(dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (x, y) = errors_in_variables_line(&mut Rng::new(38));
+    let ols = fit(Basis::Monomial { degree: 1 }, &x, &y, LstsqMethod::Qr);
+    let tls = tls_line(&x, &y);
+    let ends = [-1.0, 6.0];
+    let ols_curve: Vec<(f64, f64)> = ends.iter().map(|&x| (x, ols.eval(x))).collect();
+    let tls_curve: Vec<(f64, f64)> = ends.iter().map(|&x| (x, tls.intercept + tls.slope * x)).collect();
+    let vertical: Vec<plot::Segment> = x.iter().zip(y.iter()).map(|(&x, &y)| ((x, y), (x, ols.eval(x)))).collect();
+    let orthogonal: Vec<plot::Segment> = x.iter().zip(y.iter()).map(|(&x, &y)| ((x, y), tls.project(x, y))).collect();
+
+    let config = PlotConfig {
+        title: String::from("Errors in x and y: vertical vs orthogonal residuals"),
+        x_label: String::from("x"),
+        y_label: String::from("y"),
+        width: 1200,
+        ..PlotConfig::default()
+    }
+    .with_x_range(-1.0, 6.0)
+    .with_y_range(-2.0, 13.0);
+    plot::residual_comparison_plot(
+        &format!("{}/tls_vs_ols.png", dir),
+        &x,
+        &y,
+        &[
+            (&format!("Ordinary LS: slope {:.3}", ols.coefficients()[1]), &ols_curve, &vertical),
+            (&format!("Total LS: slope {:.3}", tls.slope), &tls_curve, &orthogonal),
+        ],
+        &config,
+    )?;
+
+    let (x, y) = errors_in_variables_exponential();
+    let sigma = (0.15, 0.3);
+    let vertical = odr(&exponential, &x, &y, sigma, &[1.0, 0.1], &OdrConfig::default().with_fixed_x());
+    let orthogonal = odr(&exponential, &x, &y, sigma, &[1.0, 0.1], &OdrConfig::default());
+    let grid: Vec<f64> = Array1::linspace(-0.5, 6.5, 100).to_vec();
+    let curve = |result: &OdrResult| -> Vec<(f64, f64)> {
+        let beta = result.beta.to_vec();
+        grid.iter().map(|&x| (x, exponential(x, &beta))).collect()
+    };
+    let segments = |result: &OdrResult| -> Vec<plot::Segment> {
+        x.iter().zip(y.iter()).zip(result.fitted.iter()).map(|((&x, &y), &p)| ((x, y), p)).collect()
+    };
+
+    let config = PlotConfig {
+        title: String::from("Exponential fit: ordinary vs orthogonal distance regression"),
+        x_label: String::from("x"),
+        y_label: String::from("y"),
+        width: 1200,
+        ..PlotConfig::default()
+    };
+    plot::residual_comparison_plot(
+        &format!("{}/odr_vs_ols.png", dir),
+        &x,
+        &y,
+        &[
+            (
+                &format!("Vertical: {:.3} exp({:.3}x)", vertical.beta[0], vertical.beta[1]),
+                &curve(&vertical),
+                &segments(&vertical),
+            ),
+            (
+                &format!("ODR: {:.3} exp({:.3}x)", orthogonal.beta[0], orthogonal.beta[1]),
+                &curve(&orthogonal),
+                &segments(&orthogonal),
+            ),
+        ],
+        &config,
+    )
+}
+
+/// Plot the noisy data with 95% confidence and prediction bands.
+#[cfg(feature = "plotting")]
+pub fn problem_prediction_band_plot(dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let model = fit(Basis::Monomial { degree: 2 }, &NOISY_X, &NOISY_Y, LstsqMethod::Qr);
+    let stats = model.statistics(&NOISY_X, &NOISY_Y);
+    let xs: Vec<f64> = Array1::linspace(0.0, 10.0, 200).to_vec();
+    let bands = model.prediction_bands(&stats, &xs, 0.95);
+
+    let config = PlotConfig {
+        title: format!("Quadratic Fit, 95% Bands (R² = {:.3})", stats.r_squared),
+        x_label: String::from("x"),
+        y_label: String::from("y"),
+        ..PlotConfig::default()
+    };
+
+    plot::band_plot(
+        &format!("{}/prediction_bands.png", dir),
+        &NOISY_X,
+        &NOISY_Y,
+        &bands.x,
+        &bands.fit,
+        &[
+            ("95% prediction", &bands.prediction.0, &bands.prediction.1),
+            ("95% confidence", &bands.confidence.0, &bands.confidence.1),
+        ],
+        &config,
+    )
+}
+
+/// Largest polynomial degree considered in the model selection problem.
+const MAX_SELECTION_DEGREE: usize = 10;
+
+/// Candidate Legendre polynomials of degree 0..=MAX_SELECTION_DEGREE on [0, 1].
+fn selection_family() -> Vec<Basis> {
+    (0..=MAX_SELECTION_DEGREE).map(|degree| Basis::Legendre { degree, domain: (0.0, 1.0) }).collect()
+}
+
+/// Mean squared difference between the full-data fit and sin(2πx) on [0, 1].
+fn sine_mse(basis: Basis, x: &[f64], y: &[f64]) -> f64 {
+    let model = fit(basis, x, y, LstsqMethod::Qr);
+    let grid = Array1::linspace(0.0, 1.0, 201);
+    grid.iter().map(|&x| (model.eval(x) - (2.0 * std::f64::consts::PI * x).sin()).powi(2)).sum::<f64>()
+        / grid.len() as f64
+}
+
+/// Choose the polynomial degree for noisy sin(2πx) by 5-fold and leave-one-out CV.
+///
+/// Returns the 5-fold selection.
+pub fn problem_model_selection() -> ModelSelection {
+    let (x, y) = noisy_sine_samples();
+    let k_fold = select_model(&selection_family(), &x, &y, CrossValidation::KFold { k: 5, seed: 39 }, LstsqMethod::Qr);
+    let loo = select_model(&selection_family(), &x, &y, CrossValidation::LeaveOneOut, LstsqMethod::Qr);
+
+    println!("  Legendre fits to {} noisy samples of sin(2πx), σ = 0.05 (σ² = 2.5e-3)", x.len());
+    println!(
+        "  {:>6} {:>12} {:>12} {:>12} {:>12} {:>12}",
+        "degree", "training", "5-fold CV", "± s.e.", "LOO CV", "true error"
+    );
+    for (degree, basis) in selection_family().into_iter().enumerate() {
+        let marker = match (degree == k_fold.selected, degree == loo.selected) {
+            (true, true) => " <- 5-fold, LOO",
+            (true, false) => " <- 5-fold",
+            (false, true) => " <- LOO",
+            (false, false) => "",
+        };
+        println!(
+            "  {:>6} {:>12.3e} {:>12.3e} {:>12.1e} {:>12.3e} {:>12.3e}{}",
+            degree,
+            k_fold.training_errors[degree],
+            k_fold.scores[degree].mean_squared_error,
+            k_fold.scores[degree].standard_error,
+            loo.scores[degree].mean_squared_error,
+            sine_mse(basis, &x, &y),
+            marker
+        );
+    }
+    println!(
+        "  Selected degree: 5-fold {} (one-s.e. rule {}), leave-one-out {} (one-s.e. rule {})",
+        k_fold.selected,
+        k_fold.one_standard_error(),
+        loo.selected,
+        loo.one_standard_error()
+    );
+    k_fold
+}
+
+/// Plot training, cross-validation and true error against polynomial degree.
+#[cfg(feature = "plotting")]
+pub fn problem_model_selection_plot(dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (x, y) = noisy_sine_samples();
+    let k_fold = select_model(&selection_family(), &x, &y, CrossValidation::KFold { k: 5, seed: 39 }, LstsqMethod::Qr);
+    let loo = select_model(&selection_family(), &x, &y, CrossValidation::LeaveOneOut, LstsqMethod::Qr);
+    let true_error: Vec<f64> = selection_family().into_iter().map(|basis| sine_mse(basis, &x, &y)).collect();
+    let degrees: Vec<f64> = (0..=MAX_SELECTION_DEGREE).map(|d| d as f64).collect();
+
+    let config = PlotConfig {
+        title: format!("Bias-Variance Tradeoff (5-fold CV selects degree {})", k_fold.selected),
+        x_label: String::from("polynomial degree"),
+        y_label: String::from("mean squared error"),
+        y_log_scale: true,
+        ..PlotConfig::default()
+    };
+
+    plot::multi_line_plot(
+        &format!("{}/model_selection.png", dir),
+        &degrees,
+        &[
+            ("training", &k_fold.training_errors),
+            ("5-fold CV", &k_fold.cv_errors()),
+            ("leave-one-out CV", &loo.cv_errors()),
+            ("error vs sin(2πx)", &true_error),
+        ],
+        &config,
+    )
+}
+
+/// Logged input u and response y = 0.5 + g u + N(0, 0.1²), where the gain g
+/// drops from 2.0 to 1.4 at t = 100.
+pub const LOGGER_CSV: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/week05_logger.csv");
+
+/// Forgetting factors compared in the streaming problem.
+const FORGETTING_FACTORS: [f64; 3] = [1.0, 0.98, 0.9];
+
+/// Replay the logger data through RLS with several forgetting factors,
+/// writing the evolving estimates to `dir`/rls_lambda_*.csv.
+///
+/// Returns the estimator with λ = 0.98.
+pub fn problem_streaming_rls(dir: &str) -> Result<RecursiveLeastSquares, Box<dyn std::error::Error>> {
+    let basis = Basis::Monomial { degree: 1 };
+    println!("  Logger data y = 0.5 + g u, gain g: 2.0 -> 1.4 at sample 200");
+    println!(
+        "  {:>6} {:>8} {:>14} {:>12} {:>16} {:>12}",
+        "λ", "memory", "gain @ 199", "final gain", "settle (±0.05)", "RMS error"
+    );
+
+    let mut tracked = None;
+    for forgetting in FORGETTING_FACTORS {
+        let mut rls = RecursiveLeastSquares::new(basis.len(), forgetting, 1e6);
+        let path = format!("{}/rls_lambda_{:.2}.csv", dir, forgetting);
+        let samples = replay_csv(LOGGER_CSV, &path, &basis, "u", "y", &mut rls)?;
+
+        let log = read_csv(&path)?;
+        let gain = log.column("c1").ok_or("missing c1")?;
+        let errors = log.column("error").ok_or("missing error")?;
+        let settle = (200..samples)
+            .find(|&k| gain[k..].iter().all(|g| (g - 1.4).abs() < 0.05))
+            .map_or_else(|| String::from("never"), |k| format!("{} samples", k - 200));
+        let rms = (errors.iter().map(|e| e * e).sum::<f64>() / samples as f64).sqrt();
+        println!(
+            "  {:>6.2} {:>8.0} {:>14.4} {:>12.4} {:>16} {:>12.4}",
+            forgetting,
+            rls.effective_memory(),
+            gain[199],
+            gain[samples - 1],
+            settle,
+            rms
+        );
+        if forgetting == 0.98 {
+            tracked = Some(rls);
+        }
+    }
+    println!("  Estimates written to {}/rls_lambda_*.csv", dir);
+    Ok(tracked.unwrap())
+}
+
+/// Plot the RLS gain estimate against time for each forgetting factor.
+#[cfg(feature = "plotting")]
+pub fn problem_streaming_rls_plot(dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let basis = Basis::Monomial { degree: 1 };
+    let mut t = Vec::new();
+    let mut truth = Vec::new();
+    let mut estimates: Vec<Vec<f64>> = vec![Vec::new(); FORGETTING_FACTORS.len()];
+    let mut estimators: Vec<RecursiveLeastSquares> = FORGETTING_FACTORS
+        .iter()
+        .map(|&forgetting| RecursiveLeastSquares::new(basis.len(), forgetting, 1e6))
+        .collect();
+
+    let reader = CsvRowReader::open(LOGGER_CSV)?;
+    let (ti, ui, yi) = (
+        reader.column_index("t").ok_or("missing t")?,
+        reader.column_index("u").ok_or("missing u")?,
+        reader.column_index("y").ok_or("missing y")?,
+    );
+    for row in reader {
+        let row = row?;
+        let phi = Array1::from(basis.evaluate(row[ui]));
+        for (rls, history) in estimators.iter_mut().zip(estimates.iter_mut()) {
+            rls.update(phi.view(), row[yi]);
+            history.push(rls.coefficients()[1]);
+        }
+        t.push(row[ti]);
+        truth.push(if row[ti] < 100.0 { 2.0 } else { 1.4 });
+    }
+
+    let labels: Vec<String> = FORGETTING_FACTORS.iter().map(|l| format!("λ = {l}")).collect();
+    let mut series: Vec<(&str, &[f64])> = vec![("true gain", &truth)];
+    series.extend(labels.iter().map(String::as_str).zip(estimates.iter().map(Vec::as_slice)));
+
+    let config = PlotConfig {
+        title: String::from("Recursive Least Squares: Tracking a Gain Change"),
+        x_label: String::from("t"),
+        y_label: String::from("estimated gain"),
+        ..PlotConfig::default()
+    }
+    .with_y_range(1.0, 2.5);
+
+    plot::multi_line_plot(&format!("{}/rls_tracking.png", dir), &t, &series, &config)
+}
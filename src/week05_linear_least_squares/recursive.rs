@@ -0,0 +1,237 @@
+//! Recursive least squares (RLS) for data that arrive one sample at a time.
+//!
+//! After each new row φ and observation y the estimate is corrected by the
+//! a-priori error e = y - φᵀx through the gain k = Pφ / (λ + φᵀPφ):
+//!
+//!   x ← x + k e,    P ← (P - k φᵀP) / λ,
+//!
+//! where P is proportional to the coefficient covariance. Each update costs
+//! O(p²) and no past data are stored. With forgetting factor λ = 1 the
+//! estimate converges to the batch least-squares solution; λ < 1 discounts a
+//! sample k steps old by λᵏ, an effective memory of about 1 / (1 - λ)
+//! samples, so the estimate can follow coefficients that drift.
+
+use std::error::Error;
+use std::path::Path;
+
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2};
+
+use super::basis::Basis;
+use crate::common::csv::{CsvRowReader, CsvRowWriter};
+
+/// Outcome of one RLS update.
+#[derive(Debug, Clone, Copy)]
+pub struct RlsUpdate {
+    /// Prediction φᵀx made before the sample was seen.
+    pub prediction: f64,
+    /// A-priori error y - φᵀx.
+    pub error: f64,
+    /// A-posteriori error y - φᵀx with the updated coefficients.
+    pub posterior_error: f64,
+}
+
+/// Streaming least-squares estimator with exponential forgetting.
+#[derive(Debug, Clone)]
+pub struct RecursiveLeastSquares {
+    coefficients: Array1<f64>,
+    p: Array2<f64>,
+    forgetting: f64,
+    samples: usize,
+}
+
+impl RecursiveLeastSquares {
+    /// Estimator for `n_parameters` coefficients starting from x = 0 and
+    /// P = δI. A large δ (say 1e6) says the prior is worth almost nothing.
+    pub fn new(n_parameters: usize, forgetting: f64, initial_variance: f64) -> Self {
+        assert!(initial_variance > 0.0, "initial variance must be positive");
+        Self::with_prior(
+            Array1::zeros(n_parameters),
+            Array2::eye(n_parameters) * initial_variance,
+            forgetting,
+        )
+    }
+
+    /// Estimator starting from prior coefficients and (scaled) covariance.
+    pub fn with_prior(coefficients: Array1<f64>, covariance: Array2<f64>, forgetting: f64) -> Self {
+        assert!(forgetting > 0.0 && forgetting <= 1.0, "forgetting factor must lie in (0, 1]");
+        assert_eq!(covariance.dim(), (coefficients.len(), coefficients.len()), "covariance must be p x p");
+        Self { coefficients, p: covariance, forgetting, samples: 0 }
+    }
+
+    /// Incorporate one observation y ≈ φᵀx.
+    pub fn update(&mut self, phi: ArrayView1<f64>, y: f64) -> RlsUpdate {
+        assert_eq!(phi.len(), self.coefficients.len(), "row length must match the number of coefficients");
+        let prediction = phi.dot(&self.coefficients);
+        let error = y - prediction;
+
+        let p_phi = self.p.dot(&phi);
+        let gain = &p_phi / (self.forgetting + phi.dot(&p_phi));
+        self.coefficients.scaled_add(error, &gain);
+
+        let n = gain.len();
+        for i in 0..n {
+            for j in 0..n {
+                self.p[[i, j]] = (self.p[[i, j]] - gain[i] * p_phi[j]) / self.forgetting;
+            }
+        }
+        // Keep P symmetric against rounding drift.
+        for i in 0..n {
+            for j in 0..i {
+                let mean = 0.5 * (self.p[[i, j]] + self.p[[j, i]]);
+                self.p[[i, j]] = mean;
+                self.p[[j, i]] = mean;
+            }
+        }
+
+        self.samples += 1;
+        RlsUpdate { prediction, error, posterior_error: y - phi.dot(&self.coefficients) }
+    }
+
+    /// Current coefficient estimate.
+    pub fn coefficients(&self) -> ArrayView1<'_, f64> {
+        self.coefficients.view()
+    }
+
+    /// Current matrix P, the coefficient covariance divided by the noise variance.
+    pub fn covariance(&self) -> ArrayView2<'_, f64> {
+        self.p.view()
+    }
+
+    pub fn forgetting(&self) -> f64 {
+        self.forgetting
+    }
+
+    /// Number of samples seen so far.
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    /// Approximate number of recent samples the estimate depends on (∞ for λ = 1).
+    pub fn effective_memory(&self) -> f64 {
+        1.0 / (1.0 - self.forgetting)
+    }
+}
+
+/// Replays logged (x, y) data through `rls` row by row.
+///
+/// Reads columns `x_column` and `y_column` of `input`, expands x in `basis`,
+/// updates the estimator, and writes one row per sample to `output` with
+/// columns x, y, prediction, error, c0, c1, ... (the a-priori prediction
+/// and the coefficients after the update). Returns the number of samples.
+pub fn replay_csv<P: AsRef<Path>, Q: AsRef<Path>>(
+    input: P,
+    output: Q,
+    basis: &Basis,
+    x_column: &str,
+    y_column: &str,
+    rls: &mut RecursiveLeastSquares,
+) -> Result<usize, Box<dyn Error>> {
+    assert_eq!(basis.len(), rls.coefficients().len(), "basis size must match the estimator");
+    let reader = CsvRowReader::open(input)?;
+    let column = |name: &str| reader.column_index(name).ok_or_else(|| format!("missing column '{name}'"));
+    let (xi, yi) = (column(x_column)?, column(y_column)?);
+
+    let mut headers = vec![String::from("x"), String::from("y"), String::from("prediction"), String::from("error")];
+    headers.extend((0..basis.len()).map(|k| format!("c{k}")));
+    let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+    let mut writer = CsvRowWriter::create(output, &headers)?;
+
+    let mut count = 0;
+    for row in reader {
+        let row = row?;
+        let (x, y) = (row[xi], row[yi]);
+        let update = rls.update(Array1::from(basis.evaluate(x)).view(), y);
+
+        let mut out = vec![x, y, update.prediction, update.error];
+        out.extend(rls.coefficients().iter());
+        writer.write_row(&out)?;
+        count += 1;
+    }
+    writer.flush()?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::week05_linear_least_squares::lstsq::qr_lstsq;
+
+    fn quadratic_rows() -> (Array2<f64>, Array1<f64>) {
+        let x = Array1::linspace(-1.0, 2.0, 40);
+        let a = Basis::Monomial { degree: 2 }.design_matrix(x.as_slice().unwrap());
+        let y = x.mapv(|x| 0.3 - x + 0.7 * x * x + 0.02 * (9.0 * x).sin());
+        (a, y)
+    }
+
+    #[test]
+    fn test_matches_batch_solution() {
+        let (a, y) = quadratic_rows();
+        let mut rls = RecursiveLeastSquares::new(3, 1.0, 1e8);
+        for (row, &yi) in a.rows().into_iter().zip(y.iter()) {
+            rls.update(row, yi);
+        }
+        let batch = qr_lstsq(&a, &y);
+        for (r, b) in rls.coefficients().iter().zip(batch.coefficients.iter()) {
+            assert!((r - b).abs() < 1e-6);
+        }
+        assert_eq!(rls.samples(), 40);
+        assert!(rls.effective_memory().is_infinite());
+    }
+
+    #[test]
+    fn test_covariance_tracks_inverse_normal_matrix() {
+        // With a vague prior P0 = δI, P approaches (AᵀA)⁻¹.
+        let (a, y) = quadratic_rows();
+        let mut rls = RecursiveLeastSquares::new(3, 1.0, 1e10);
+        for (row, &yi) in a.rows().into_iter().zip(y.iter()) {
+            let update = rls.update(row, yi);
+            assert!(update.posterior_error.abs() <= update.error.abs() + 1e-12);
+        }
+        let ata = a.t().dot(&a);
+        let identity = rls.covariance().dot(&ata);
+        for i in 0..3 {
+            for j in 0..3 {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((identity[[i, j]] - expected).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_forgetting_tracks_step_change() {
+        let gain = |k: usize| if k < 100 { 2.0 } else { -1.0 };
+        let mut slow = RecursiveLeastSquares::new(1, 1.0, 1e6);
+        let mut fast = RecursiveLeastSquares::new(1, 0.9, 1e6);
+        for k in 0..150 {
+            let u = 1.0 + (k % 7) as f64;
+            let phi = Array1::from(vec![u]);
+            slow.update(phi.view(), gain(k) * u);
+            fast.update(phi.view(), gain(k) * u);
+        }
+        // Old samples still carry weight 0.9^50 ≈ 0.005.
+        assert!((fast.coefficients()[0] + 1.0).abs() < 0.05);
+        assert!((slow.coefficients()[0] + 1.0).abs() > 0.5);
+        assert!((fast.effective_memory() - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_replay_csv() {
+        let input = "/tmp/test_rls_input.csv";
+        let output = "/tmp/test_rls_output.csv";
+        std::fs::write(input, "t,y\n0,1\n1,3\n2,5\n3,7\n").unwrap();
+
+        let basis = Basis::Monomial { degree: 1 };
+        let mut rls = RecursiveLeastSquares::new(2, 1.0, 1e8);
+        assert_eq!(replay_csv(input, output, &basis, "t", "y", &mut rls).unwrap(), 4);
+        assert!((rls.coefficients()[0] - 1.0).abs() < 1e-6);
+        assert!((rls.coefficients()[1] - 2.0).abs() < 1e-6);
+
+        let table = crate::common::csv::read_csv(output).unwrap();
+        assert_eq!(table.headers, vec!["x", "y", "prediction", "error", "c0", "c1"]);
+        assert_eq!(table.rows.len(), 4);
+        assert!(replay_csv(input, output, &basis, "u", "y", &mut rls).is_err());
+
+        std::fs::remove_file(input).unwrap();
+        std::fs::remove_file(output).unwrap();
+    }
+}
@@ -0,0 +1,324 @@
+//! Tikhonov (ridge) regularisation with L-curve and GCV parameter choice.
+//!
+//! Minimises ||A x - y||² + λ² ||L x||² for a regularisation operator L
+//! (the identity for ridge regression, a difference operator to penalise
+//! roughness). The problem is reduced once to a form where every λ costs
+//! only O(n²):
+//!
+//! 1. QR-factor the stacked matrix [A; L] = [Q_A; Q_L] R.
+//! 2. Take the SVD Q_A = U diag(c) Wᵀ. Since Q_AᵀQ_A + Q_LᵀQ_L = I, the
+//!    same W diagonalises Q_L with values s_i = sqrt(1 - c_i²).
+//!
+//! The pairs (c_i, s_i) are the generalised singular values of (A, L), and
+//! with z = R x the solution is z = W diag(c_i / (c_i² + λ² s_i²)) Uᵀ y.
+//! Residual norm, seminorm ||L x|| and the GCV function are all closed-form
+//! sums over i, so sweeping λ for the L-curve or GCV is cheap.
+
+use ndarray::{concatenate, s, Array1, Array2, Axis};
+
+use super::decomposition::{householder_qr, solve_upper_triangular, svd};
+
+/// Tikhonov solution for one value of λ.
+#[derive(Debug, Clone)]
+pub struct TikhonovSolution {
+    pub lambda: f64,
+    pub coefficients: Array1<f64>,
+    /// ||A x - y||.
+    pub residual_norm: f64,
+    /// ||L x||.
+    pub solution_seminorm: f64,
+    /// Trace of the influence matrix, Σ f_i.
+    pub effective_parameters: f64,
+    /// Generalised cross-validation score ||A x - y||² / (m - Σ f_i)².
+    pub gcv: f64,
+}
+
+/// Points on the L-curve (||A x - y||, ||L x||) over a λ grid, which is
+/// analysed and plotted on log-log axes.
+#[derive(Debug, Clone)]
+pub struct LCurve {
+    pub lambdas: Vec<f64>,
+    /// Residual norms ||A x_λ - y||, not logarithms.
+    pub residual_norms: Vec<f64>,
+    /// Solution seminorms ||L x_λ||, not logarithms.
+    pub solution_seminorms: Vec<f64>,
+    /// Signed curvature of the log-log curve; NaN at the two end points.
+    pub curvature: Vec<f64>,
+    /// Index of maximum curvature, the "corner".
+    pub corner: usize,
+}
+
+impl LCurve {
+    /// λ at the corner of the L-curve.
+    pub fn corner_lambda(&self) -> f64 {
+        self.lambdas[self.corner]
+    }
+}
+
+/// Precomputed reduction of a Tikhonov problem, reusable for any λ.
+#[derive(Debug, Clone)]
+pub struct Tikhonov {
+    r: Array2<f64>,
+    w: Array2<f64>,
+    c: Array1<f64>,
+    s: Array1<f64>,
+    uty: Array1<f64>,
+    /// Part of ||y||² outside the range of A, which no λ can fit.
+    residual_floor: f64,
+    m: usize,
+}
+
+impl Tikhonov {
+    /// Prepares min ||A x - y||² + λ² ||L x||².
+    ///
+    /// L must have A's column count and [A; L] must have full column rank.
+    pub fn new(a: &Array2<f64>, y: &Array1<f64>, l: &Array2<f64>) -> Self {
+        let (m, n) = a.dim();
+        assert_eq!(l.ncols(), n, "regularisation operator must have one column per coefficient");
+
+        let stacked = concatenate(Axis(0), &[a.view(), l.view()]).expect("column counts match");
+        let qr = householder_qr(&stacked);
+        let q_a = qr.q.slice(s![..m, ..]).to_owned();
+        let d = svd(&q_a);
+
+        let uty = d.u.t().dot(y);
+        let residual_floor = (y.dot(y) - uty.dot(&uty)).max(0.0);
+        let c = d.s.mapv(|c| c.min(1.0));
+        let s = c.mapv(|c| (1.0 - c * c).max(0.0).sqrt());
+
+        Tikhonov { r: qr.r, w: d.v, c, s, uty, residual_floor, m }
+    }
+
+    /// Ridge regression, L = I.
+    pub fn ridge(a: &Array2<f64>, y: &Array1<f64>) -> Self {
+        Self::new(a, y, &Array2::eye(a.ncols()))
+    }
+
+    /// Range of the finite generalised singular values γ_i = c_i / s_i.
+    ///
+    /// λ only has an effect between these two values, so this is the natural
+    /// range to search for a regularisation parameter.
+    pub fn lambda_range(&self) -> (f64, f64) {
+        let gammas: Vec<f64> = (0..self.c.len())
+            .filter(|&i| self.s[i] > 0.0 && self.c[i] > 0.0)
+            .map(|i| self.c[i] / self.s[i])
+            .collect();
+        let max = gammas.iter().cloned().fold(0.0, f64::max);
+        let min = gammas.iter().cloned().fold(f64::INFINITY, f64::min);
+        (min.max(max * f64::EPSILON), max)
+    }
+
+    /// `count` log-spaced values of λ spanning [`Self::lambda_range`].
+    pub fn default_lambdas(&self, count: usize) -> Vec<f64> {
+        let (min, max) = self.lambda_range();
+        log_spaced(min, max, count)
+    }
+
+    /// Filter factors f_i = c_i² / (c_i² + λ² s_i²); 1 keeps a component, 0 removes it.
+    pub fn filter_factors(&self, lambda: f64) -> Array1<f64> {
+        let l2 = lambda * lambda;
+        Array1::from_shape_fn(self.c.len(), |i| {
+            let c2 = self.c[i] * self.c[i];
+            let denom = c2 + l2 * self.s[i] * self.s[i];
+            if denom > 0.0 {
+                c2 / denom
+            } else {
+                0.0
+            }
+        })
+    }
+
+    /// ||A x_λ - y||.
+    pub fn residual_norm(&self, lambda: f64) -> f64 {
+        let f = self.filter_factors(lambda);
+        let fitted_out: f64 = f.iter().zip(self.uty.iter()).map(|(f, b)| ((1.0 - f) * b).powi(2)).sum();
+        (fitted_out + self.residual_floor).sqrt()
+    }
+
+    /// ||L x_λ||.
+    pub fn solution_seminorm(&self, lambda: f64) -> f64 {
+        let l2 = lambda * lambda;
+        (0..self.c.len())
+            .map(|i| {
+                let denom = self.c[i] * self.c[i] + l2 * self.s[i] * self.s[i];
+                if denom > 0.0 {
+                    (self.s[i] * self.c[i] * self.uty[i] / denom).powi(2)
+                } else {
+                    0.0
+                }
+            })
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Effective number of parameters Σ f_i.
+    pub fn effective_parameters(&self, lambda: f64) -> f64 {
+        self.filter_factors(lambda).sum()
+    }
+
+    /// GCV score ||A x_λ - y||² / (m - Σ f_i)².
+    pub fn gcv(&self, lambda: f64) -> f64 {
+        let rho = self.residual_norm(lambda);
+        rho * rho / (self.m as f64 - self.effective_parameters(lambda)).powi(2)
+    }
+
+    /// Solves the regularised problem for one λ.
+    pub fn solve(&self, lambda: f64) -> TikhonovSolution {
+        let l2 = lambda * lambda;
+        let scaled = Array1::from_shape_fn(self.c.len(), |i| {
+            let denom = self.c[i] * self.c[i] + l2 * self.s[i] * self.s[i];
+            if denom > 0.0 {
+                self.c[i] * self.uty[i] / denom
+            } else {
+                0.0
+            }
+        });
+        let z = self.w.dot(&scaled);
+        let coefficients = solve_upper_triangular(&self.r, &z);
+
+        TikhonovSolution {
+            lambda,
+            coefficients,
+            residual_norm: self.residual_norm(lambda),
+            solution_seminorm: self.solution_seminorm(lambda),
+            effective_parameters: self.effective_parameters(lambda),
+            gcv: self.gcv(lambda),
+        }
+    }
+
+    /// Evaluates the L-curve over `lambdas` (ascending) and locates its corner.
+    pub fn l_curve(&self, lambdas: &[f64]) -> LCurve {
+        let residual_norms: Vec<f64> = lambdas.iter().map(|&l| self.residual_norm(l)).collect();
+        let solution_seminorms: Vec<f64> = lambdas.iter().map(|&l| self.solution_seminorm(l)).collect();
+
+        let t: Vec<f64> = lambdas.iter().map(|l| l.log10()).collect();
+        let x: Vec<f64> = residual_norms.iter().map(|r| r.log10()).collect();
+        let y: Vec<f64> = solution_seminorms.iter().map(|e| e.log10()).collect();
+
+        let mut curvature = vec![f64::NAN; lambdas.len()];
+        for i in 1..lambdas.len().saturating_sub(1) {
+            let (dx, ddx) = three_point_derivatives(&t[i - 1..=i + 1], &x[i - 1..=i + 1]);
+            let (dy, ddy) = three_point_derivatives(&t[i - 1..=i + 1], &y[i - 1..=i + 1]);
+            curvature[i] = (dx * ddy - dy * ddx) / (dx * dx + dy * dy).powf(1.5);
+        }
+        let corner = curvature
+            .iter()
+            .enumerate()
+            .filter(|(_, k)| k.is_finite())
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map_or(0, |(i, _)| i);
+
+        LCurve { lambdas: lambdas.to_vec(), residual_norms, solution_seminorms, curvature, corner }
+    }
+
+    /// λ from `lambdas` that minimises the GCV score.
+    pub fn gcv_lambda(&self, lambdas: &[f64]) -> f64 {
+        lambdas
+            .iter()
+            .copied()
+            .min_by(|&a, &b| self.gcv(a).total_cmp(&self.gcv(b)))
+            .expect("lambda grid must not be empty")
+    }
+}
+
+/// First and second derivative at the middle of three points of a parabola.
+fn three_point_derivatives(t: &[f64], v: &[f64]) -> (f64, f64) {
+    let (h0, h1) = (t[1] - t[0], t[2] - t[1]);
+    let first = (-h1 / (h0 * (h0 + h1))) * v[0] + ((h1 - h0) / (h0 * h1)) * v[1] + (h0 / (h1 * (h0 + h1))) * v[2];
+    let second = 2.0 * (v[0] / (h0 * (h0 + h1)) - v[1] / (h0 * h1) + v[2] / (h1 * (h0 + h1)));
+    (first, second)
+}
+
+/// Forward difference operator of the given order, (n - order) x n.
+///
+/// Order 1 rows are [-1, 1], order 2 rows are [1, -2, 1], and so on.
+pub fn difference_operator(n: usize, order: usize) -> Array2<f64> {
+    let mut d = Array2::<f64>::eye(n);
+    for _ in 0..order {
+        let rows = d.nrows() - 1;
+        d = &d.slice(s![1.., ..]) - &d.slice(s![..rows, ..]);
+    }
+    d
+}
+
+/// `count` values spaced evenly in log10 between `min` and `max`.
+pub fn log_spaced(min: f64, max: f64, count: usize) -> Vec<f64> {
+    Array1::logspace(10.0, min.log10(), max.log10(), count).to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::linalg::solve;
+    use ndarray::array;
+
+    fn test_problem() -> (Array2<f64>, Array1<f64>) {
+        let a = array![[1.0, 0.5, 0.2], [1.0, 1.0, 1.0], [1.0, 1.5, 2.2], [1.0, 2.0, 4.1], [1.0, 2.5, 6.0]];
+        let y = array![1.1, 2.3, 2.9, 4.2, 4.8];
+        (a, y)
+    }
+
+    fn normal_equation_solution(a: &Array2<f64>, y: &Array1<f64>, l: &Array2<f64>, lambda: f64) -> Array1<f64> {
+        let lhs = a.t().dot(a) + lambda * lambda * l.t().dot(l);
+        solve(&lhs, &a.t().dot(y)).unwrap()
+    }
+
+    #[test]
+    fn test_ridge_matches_normal_equations() {
+        let (a, y) = test_problem();
+        let tik = Tikhonov::ridge(&a, &y);
+        for lambda in [0.0, 0.1, 1.0, 10.0] {
+            let sol = tik.solve(lambda);
+            let expected = normal_equation_solution(&a, &y, &Array2::eye(3), lambda);
+            for k in 0..3 {
+                assert!((sol.coefficients[k] - expected[k]).abs() < 1e-9, "lambda = {lambda}");
+            }
+            let residual = &a.dot(&sol.coefficients) - &y;
+            assert!((sol.residual_norm - residual.dot(&residual).sqrt()).abs() < 1e-10);
+            assert!((sol.solution_seminorm - sol.coefficients.dot(&sol.coefficients).sqrt()).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_general_operator() {
+        let (a, y) = test_problem();
+        let l = difference_operator(3, 1);
+        let tik = Tikhonov::new(&a, &y, &l);
+        let sol = tik.solve(2.0);
+        let expected = normal_equation_solution(&a, &y, &l, 2.0);
+        for k in 0..3 {
+            assert!((sol.coefficients[k] - expected[k]).abs() < 1e-9);
+        }
+        let lx = l.dot(&sol.coefficients);
+        assert!((sol.solution_seminorm - lx.dot(&lx).sqrt()).abs() < 1e-10);
+        // L has a one-dimensional null space, so one filter factor stays at 1.
+        assert!((tik.effective_parameters(1e6) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_difference_operator() {
+        let d = difference_operator(4, 2);
+        assert_eq!(d, array![[1.0, -2.0, 1.0, 0.0], [0.0, 1.0, -2.0, 1.0]]);
+        assert_eq!(difference_operator(3, 0), Array2::<f64>::eye(3));
+    }
+
+    #[test]
+    fn test_parameter_choice_is_interior() {
+        // Noisy samples of a smooth function with an over-flexible basis.
+        let mut rng = crate::common::rng::Rng::new(35);
+        let x: Vec<f64> = (0..40).map(|i| i as f64 / 39.0).collect();
+        let y: Array1<f64> = x.iter().map(|&x| (3.0 * x).sin() + 0.05 * rng.normal()).collect();
+        let a = crate::week05_linear_least_squares::Basis::Monomial { degree: 15 }.design_matrix(&x);
+
+        let tik = Tikhonov::ridge(&a, &y);
+        let lambdas = tik.default_lambdas(111);
+        let curve = tik.l_curve(&lambdas);
+        assert!(curve.corner > 0 && curve.corner < lambdas.len() - 1);
+        let gcv = tik.gcv_lambda(&lambdas);
+        assert!(gcv > lambdas[0] && gcv < lambdas[lambdas.len() - 1]);
+
+        // Residual norm grows and the seminorm shrinks with λ.
+        assert!(curve.residual_norms.windows(2).all(|w| w[1] >= w[0] - 1e-12));
+        assert!(curve.solution_seminorms.windows(2).all(|w| w[1] <= w[0] + 1e-12));
+    }
+}
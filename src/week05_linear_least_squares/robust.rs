@@ -0,0 +1,298 @@
+//! Robust regression by iteratively reweighted least squares (IRLS).
+//!
+//! An M-estimator minimises Σ ρ(r_i / s) for a loss ρ that grows more slowly
+//! than r² for large residuals. Setting the gradient to zero gives weighted
+//! normal equations with weights w(u) = ρ'(u) / u, so the fit alternates
+//! between estimating the scale s from the residuals (by the median
+//! absolute deviation), recomputing the weights, and solving a weighted
+//! least-squares problem. Observations with small final weights are the
+//! ones the fit has discounted as outliers.
+
+use ndarray::{Array1, Array2};
+
+use super::basis::Basis;
+use super::fit::FittedModel;
+use super::lstsq::lstsq;
+use super::types::{LstsqMethod, LstsqResult};
+use super::weighted::Weighting;
+
+/// Loss function of an M-estimator, in units of the residual scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RobustLoss {
+    /// Quadratic for |u| <= k, linear beyond.
+    Huber { k: f64 },
+    /// Tukey's bisquare: bounded, so gross outliers get zero weight.
+    Tukey { c: f64 },
+    /// Least absolute deviations.
+    L1,
+}
+
+impl RobustLoss {
+    /// Huber loss with k = 1.345 (95% efficiency for normal errors).
+    pub fn huber() -> Self {
+        RobustLoss::Huber { k: 1.345 }
+    }
+
+    /// Tukey bisquare with c = 4.685 (95% efficiency for normal errors).
+    pub fn tukey() -> Self {
+        RobustLoss::Tukey { c: 4.685 }
+    }
+
+    /// Loss ρ(u) for a scaled residual u.
+    pub fn rho(&self, u: f64) -> f64 {
+        let a = u.abs();
+        match *self {
+            RobustLoss::Huber { k } => {
+                if a <= k {
+                    0.5 * u * u
+                } else {
+                    k * a - 0.5 * k * k
+                }
+            }
+            RobustLoss::Tukey { c } => {
+                let c2 = c * c / 6.0;
+                if a <= c {
+                    c2 * (1.0 - (1.0 - (u / c).powi(2)).powi(3))
+                } else {
+                    c2
+                }
+            }
+            RobustLoss::L1 => a,
+        }
+    }
+
+    /// IRLS weight w(u) = ρ'(u) / u.
+    pub fn weight(&self, u: f64) -> f64 {
+        let a = u.abs();
+        match *self {
+            RobustLoss::Huber { k } => {
+                if a <= k {
+                    1.0
+                } else {
+                    k / a
+                }
+            }
+            RobustLoss::Tukey { c } => {
+                if a < c {
+                    (1.0 - (u / c).powi(2)).powi(2)
+                } else {
+                    0.0
+                }
+            }
+            // Capped so that exactly fitted points do not get infinite weight.
+            RobustLoss::L1 => 1.0 / a.max(L1_MIN_RESIDUAL),
+        }
+    }
+
+    /// Short human-readable name.
+    pub fn label(&self) -> String {
+        match self {
+            RobustLoss::Huber { k } => format!("Huber (k = {k})"),
+            RobustLoss::Tukey { c } => format!("Tukey (c = {c})"),
+            RobustLoss::L1 => String::from("L1"),
+        }
+    }
+}
+
+/// Smallest scaled residual used when forming L1 weights.
+const L1_MIN_RESIDUAL: f64 = 1e-6;
+
+/// Settings for [`robust_lstsq`].
+#[derive(Debug, Clone)]
+pub struct RobustConfig {
+    /// Maximum number of reweighting steps.
+    pub max_iter: usize,
+    /// Stop when no coefficient changes by more than tol * (1 + |x|).
+    pub tol: f64,
+    /// Solver used for each weighted subproblem.
+    pub method: LstsqMethod,
+}
+
+impl Default for RobustConfig {
+    fn default() -> Self {
+        Self { max_iter: 100, tol: 1e-8, method: LstsqMethod::Qr }
+    }
+}
+
+impl RobustConfig {
+    pub fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    pub fn with_tol(mut self, tol: f64) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    pub fn with_method(mut self, method: LstsqMethod) -> Self {
+        self.method = method;
+        self
+    }
+}
+
+/// Result of a robust fit.
+#[derive(Debug, Clone)]
+pub struct RobustResult {
+    /// Solution with residuals and RSS in the original units.
+    pub fit: LstsqResult,
+    /// Final IRLS weight of each observation, in [0, 1] for Huber and Tukey.
+    pub weights: Array1<f64>,
+    /// Robust residual scale (MAD-based) at the solution.
+    pub scale: f64,
+    pub loss: RobustLoss,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+impl RobustResult {
+    /// Indices of observations whose final weight is below `threshold`.
+    pub fn outliers(&self, threshold: f64) -> Vec<usize> {
+        self.weights
+            .iter()
+            .enumerate()
+            .filter(|(_, &w)| w < threshold)
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Median of a slice (NaN if empty).
+pub fn median(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return f64::NAN;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 1 {
+        sorted[mid]
+    } else {
+        0.5 * (sorted[mid - 1] + sorted[mid])
+    }
+}
+
+/// Robust scale estimate 1.4826 * median |r_i - median(r)|.
+///
+/// The factor makes it consistent with the standard deviation for normal data.
+pub fn mad_scale(residuals: &[f64]) -> f64 {
+    let centre = median(residuals);
+    let deviations: Vec<f64> = residuals.iter().map(|r| (r - centre).abs()).collect();
+    1.482_6 * median(&deviations)
+}
+
+/// Robust linear regression of `y` on the columns of `a` by IRLS.
+///
+/// Starts from the ordinary least-squares fit. If the MAD of the residuals
+/// is zero (more than half the points fitted exactly) the current fit is
+/// returned as converged.
+pub fn robust_lstsq(a: &Array2<f64>, y: &Array1<f64>, loss: RobustLoss, config: &RobustConfig) -> RobustResult {
+    let mut fit = lstsq(a, y, config.method);
+    let mut weights = Array1::<f64>::ones(y.len());
+    let mut scale = mad_scale(fit.residuals.as_slice().unwrap());
+    let mut converged = false;
+    let mut iterations = 0;
+
+    while iterations < config.max_iter {
+        if scale == 0.0 || !scale.is_finite() {
+            converged = scale == 0.0;
+            break;
+        }
+        iterations += 1;
+
+        weights = fit.residuals.mapv(|r| loss.weight(r / scale));
+        let (a_white, y_white) = Weighting::Weights(weights.clone()).whiten(a, y).expect("loss weights are non-negative");
+        let next = lstsq(&a_white, &y_white, config.method);
+
+        let change = (&next.coefficients - &fit.coefficients)
+            .iter()
+            .zip(next.coefficients.iter())
+            .fold(0.0_f64, |acc, (d, x)| acc.max(d.abs() / (1.0 + x.abs())));
+
+        let residuals = y - &a.dot(&next.coefficients);
+        let rss = residuals.dot(&residuals);
+        fit = LstsqResult { residuals, rss, ..next };
+        scale = mad_scale(fit.residuals.as_slice().unwrap());
+
+        if change <= config.tol {
+            converged = true;
+            break;
+        }
+    }
+
+    RobustResult { fit, weights, scale, loss, iterations, converged }
+}
+
+/// Fits `basis` to (x, y) robustly; the model carries the robust coefficients.
+pub fn fit_robust(
+    basis: Basis,
+    x: &[f64],
+    y: &[f64],
+    loss: RobustLoss,
+    config: &RobustConfig,
+) -> (FittedModel, RobustResult) {
+    let a = basis.design_matrix(x);
+    let result = robust_lstsq(&a, &Array1::from(y.to_vec()), loss, config);
+    let model = FittedModel { basis, fit: result.fit.clone() };
+    (model, result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// y = 1 + 0.5x with a small deterministic wiggle and three gross outliers.
+    fn contaminated_line() -> (Vec<f64>, Vec<f64>) {
+        let x: Vec<f64> = (0..20).map(|i| i as f64).collect();
+        let mut y: Vec<f64> = x.iter().map(|&x| 1.0 + 0.5 * x + 0.05 * (1.7 * x).sin()).collect();
+        y[3] += 8.0;
+        y[11] -= 6.0;
+        y[17] += 10.0;
+        (x, y)
+    }
+
+    #[test]
+    fn test_median_and_mad() {
+        assert_eq!(median(&[3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(&[4.0, 1.0, 2.0, 3.0]), 2.5);
+        // Deviations from 2 are 1, 1, 0, 1, 98 -> median 1.
+        assert!((mad_scale(&[1.0, 3.0, 2.0, 1.0, 100.0]) - 1.4826).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_losses_resist_outliers() {
+        let (x, y) = contaminated_line();
+        let basis = || Basis::Monomial { degree: 1 };
+        let ols = crate::week05_linear_least_squares::fit(basis(), &x, &y, LstsqMethod::Qr);
+        assert!((ols.coefficients()[1] - 0.5).abs() > 0.02);
+
+        for loss in [RobustLoss::huber(), RobustLoss::tukey(), RobustLoss::L1] {
+            let (model, result) = fit_robust(basis(), &x, &y, loss, &RobustConfig::default());
+            assert!(result.converged, "{}", loss.label());
+            assert!((model.coefficients()[0] - 1.0).abs() < 0.1, "{}", loss.label());
+            assert!((model.coefficients()[1] - 0.5).abs() < 0.01, "{}", loss.label());
+        }
+    }
+
+    #[test]
+    fn test_tukey_flags_outliers() {
+        let (x, y) = contaminated_line();
+        let (_, result) = fit_robust(Basis::Monomial { degree: 1 }, &x, &y, RobustLoss::tukey(), &RobustConfig::default());
+        assert_eq!(result.outliers(0.5), vec![3, 11, 17]);
+        assert_eq!(result.weights[17], 0.0);
+        // Scale reflects the inliers' wiggle, not the outliers.
+        assert!(result.scale < 0.1);
+    }
+
+    #[test]
+    fn test_clean_data_matches_ols() {
+        let x: Vec<f64> = (0..15).map(|i| i as f64 * 0.3).collect();
+        let y: Vec<f64> = x.iter().map(|&x| 2.0 - x + 0.01 * (5.0 * x).cos()).collect();
+        let a = Basis::Monomial { degree: 1 }.design_matrix(&x);
+        let y = Array1::from(y);
+        let ols = lstsq(&a, &y, LstsqMethod::Qr);
+        let huber = robust_lstsq(&a, &y, RobustLoss::Huber { k: 100.0 }, &RobustConfig::default());
+        assert!(huber.weights.iter().all(|&w| w == 1.0));
+        assert!((huber.fit.coefficients[1] - ols.coefficients[1]).abs() < 1e-12);
+    }
+}
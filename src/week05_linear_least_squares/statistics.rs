@@ -0,0 +1,321 @@
+//! Inference for linear least-squares fits.
+//!
+//! Under the usual assumptions (independent errors with common variance σ²)
+//! the coefficient estimate x̂ has covariance σ² (AᵀA)⁻¹, and σ² is
+//! estimated by s² = RSS / (n - p). Everything here follows from that: the
+//! standard errors, t-statistics, confidence and prediction intervals, and
+//! the leverage-based residual diagnostics.
+
+use ndarray::{Array1, Array2};
+
+use super::decomposition::svd;
+use super::distributions::{student_t_cdf, student_t_quantile};
+use super::fit::FittedModel;
+use super::lstsq::default_rank_tolerance;
+use super::types::LstsqResult;
+
+/// A point estimate with a two-sided interval.
+#[derive(Debug, Clone, Copy)]
+pub struct Interval {
+    pub estimate: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+/// Diagnostics computed from the residuals of a fit.
+#[derive(Debug, Clone)]
+pub struct ResidualDiagnostics {
+    /// Mean residual (zero when the model has an intercept).
+    pub mean: f64,
+    /// Residual standard error s = sqrt(RSS / (n - p)).
+    pub std_dev: f64,
+    /// Durbin-Watson statistic; values far from 2 suggest serial correlation.
+    pub durbin_watson: f64,
+    /// Leverage h_ii, the diagonal of the hat matrix A (AᵀA)⁻¹ Aᵀ.
+    pub leverage: Array1<f64>,
+    /// Internally studentised residuals r_i / (s sqrt(1 - h_ii)).
+    pub studentized: Array1<f64>,
+    /// Largest |studentised residual|, a quick outlier flag.
+    pub max_abs_studentized: f64,
+    /// Cook's distance of each observation.
+    pub cooks_distance: Array1<f64>,
+}
+
+/// Summary statistics for a linear least-squares fit.
+#[derive(Debug, Clone)]
+pub struct RegressionStatistics {
+    /// Fitted coefficients x̂.
+    pub coefficients: Array1<f64>,
+    /// Number of observations n.
+    pub n_observations: usize,
+    /// Number of estimated parameters p (the numerical rank of A).
+    pub n_parameters: usize,
+    /// Residual degrees of freedom n - p.
+    pub dof: usize,
+    /// Unbiased residual variance s² = RSS / (n - p).
+    pub residual_variance: f64,
+    /// Parameter covariance s² (AᵀA)⁻¹.
+    pub covariance: Array2<f64>,
+    /// Standard error of each coefficient.
+    pub standard_errors: Array1<f64>,
+    /// t-statistic x̂_k / se_k for the hypothesis x_k = 0.
+    pub t_statistics: Array1<f64>,
+    /// Two-sided p-value of each t-statistic.
+    pub p_values: Array1<f64>,
+    /// Coefficient of determination 1 - RSS / TSS.
+    pub r_squared: f64,
+    /// R² adjusted for the number of parameters.
+    pub adj_r_squared: f64,
+    /// Akaike information criterion n ln(RSS / n) + 2p.
+    pub aic: f64,
+    /// Bayesian information criterion n ln(RSS / n) + p ln n.
+    pub bic: f64,
+    pub diagnostics: ResidualDiagnostics,
+}
+
+impl RegressionStatistics {
+    /// Two-sided t critical value for the given confidence `level`.
+    pub fn critical_value(&self, level: f64) -> f64 {
+        student_t_quantile(0.5 * (1.0 + level), self.dof as f64)
+    }
+
+    /// Confidence intervals for every coefficient.
+    pub fn parameter_intervals(&self, level: f64) -> Vec<Interval> {
+        let t = self.critical_value(level);
+        self.coefficients
+            .iter()
+            .zip(self.standard_errors.iter())
+            .map(|(&c, &se)| Interval { estimate: c, lower: c - t * se, upper: c + t * se })
+            .collect()
+    }
+
+    /// Confidence interval for the mean response at design row `row`.
+    pub fn confidence_interval(&self, row: &[f64], level: f64) -> Interval {
+        let variance = self.mean_response_variance(row);
+        self.interval(row, variance.sqrt(), level)
+    }
+
+    /// Prediction interval for a new observation at design row `row`.
+    ///
+    /// Wider than the confidence interval by the residual variance s².
+    pub fn prediction_interval(&self, row: &[f64], level: f64) -> Interval {
+        let variance = self.mean_response_variance(row) + self.residual_variance;
+        self.interval(row, variance.sqrt(), level)
+    }
+
+    fn mean_response_variance(&self, row: &[f64]) -> f64 {
+        let a = Array1::from(row.to_vec());
+        a.dot(&self.covariance.dot(&a))
+    }
+
+    fn interval(&self, row: &[f64], se: f64, level: f64) -> Interval {
+        let estimate: f64 = row.iter().zip(self.coefficients.iter()).map(|(a, c)| a * c).sum();
+        let half_width = self.critical_value(level) * se;
+        Interval { estimate, lower: estimate - half_width, upper: estimate + half_width }
+    }
+}
+
+/// Computes regression statistics for the fit of `y` by design matrix `a`.
+///
+/// The covariance is formed from the SVD of A as s² V diag(1/σ²) Vᵀ, so
+/// rank-deficient directions are dropped rather than blowing up. The
+/// effective number of parameters is the numerical rank.
+pub fn regression_statistics(a: &Array2<f64>, y: &Array1<f64>, fit: &LstsqResult) -> RegressionStatistics {
+    let n = a.nrows();
+    let (unscaled, leverage, p) = normal_matrix_pseudo_inverse(a);
+    let dof = n.saturating_sub(p);
+
+    let rss = fit.rss;
+    let residual_variance = if dof > 0 { rss / dof as f64 } else { f64::NAN };
+    let s = residual_variance.sqrt();
+    let covariance = unscaled * residual_variance;
+
+    let standard_errors = covariance.diag().mapv(f64::sqrt);
+    let t_statistics = &fit.coefficients / &standard_errors;
+    let p_values = t_statistics.mapv(|t| 2.0 * (1.0 - student_t_cdf(t.abs(), dof as f64)));
+
+    let mean_y = y.mean().unwrap_or(0.0);
+    let tss: f64 = y.iter().map(|v| (v - mean_y).powi(2)).sum();
+    let r_squared = 1.0 - rss / tss;
+    let adj_r_squared = if dof > 0 { 1.0 - (1.0 - r_squared) * (n as f64 - 1.0) / dof as f64 } else { f64::NAN };
+    let log_likelihood_term = n as f64 * (rss / n as f64).ln();
+    let aic = log_likelihood_term + 2.0 * p as f64;
+    let bic = log_likelihood_term + p as f64 * (n as f64).ln();
+
+    let r = &fit.residuals;
+    let studentized = Array1::from_shape_fn(n, |i| r[i] / (s * (1.0 - leverage[i]).sqrt()));
+    let cooks_distance =
+        Array1::from_shape_fn(n, |i| studentized[i].powi(2) * leverage[i] / (p as f64 * (1.0 - leverage[i])));
+    let successive: f64 = r.windows(2).into_iter().map(|w| (w[1] - w[0]).powi(2)).sum();
+
+    let diagnostics = ResidualDiagnostics {
+        mean: r.mean().unwrap_or(0.0),
+        std_dev: s,
+        durbin_watson: successive / rss,
+        max_abs_studentized: studentized.iter().fold(0.0, |acc, v| acc.max(v.abs())),
+        leverage,
+        studentized,
+        cooks_distance,
+    };
+
+    RegressionStatistics {
+        coefficients: fit.coefficients.clone(),
+        n_observations: n,
+        n_parameters: p,
+        dof,
+        residual_variance,
+        covariance,
+        standard_errors,
+        t_statistics,
+        p_values,
+        r_squared,
+        adj_r_squared,
+        aic,
+        bic,
+        diagnostics,
+    }
+}
+
+/// (AᵀA)⁺ = V diag(1/σ²) Vᵀ from the SVD of A, together with the
+/// hat-matrix diagonal h_ii = Σ_k U_ik² and the numerical rank.
+pub(crate) fn normal_matrix_pseudo_inverse(a: &Array2<f64>) -> (Array2<f64>, Array1<f64>, usize) {
+    let (n, cols) = a.dim();
+    let d = svd(a);
+    let tol = default_rank_tolerance(a) * d.s.first().copied().unwrap_or(0.0);
+
+    let mut inverse = Array2::<f64>::zeros((cols, cols));
+    let mut leverage = Array1::<f64>::zeros(n);
+    let mut rank = 0;
+    for k in (0..d.s.len()).filter(|&k| d.s[k] > tol) {
+        let v = d.v.column(k);
+        let weight = 1.0 / (d.s[k] * d.s[k]);
+        for i in 0..cols {
+            for j in 0..cols {
+                inverse[[i, j]] += weight * v[i] * v[j];
+            }
+        }
+        leverage += &d.u.column(k).mapv(|u| u * u);
+        rank += 1;
+    }
+    (inverse, leverage, rank)
+}
+
+/// Pointwise confidence and prediction bands of a fitted model.
+#[derive(Debug, Clone)]
+pub struct PredictionBands {
+    /// Points at which the bands are evaluated.
+    pub x: Vec<f64>,
+    /// Model prediction at each point.
+    pub fit: Vec<f64>,
+    /// Confidence band for the mean response, (lower, upper).
+    pub confidence: (Vec<f64>, Vec<f64>),
+    /// Prediction band for a new observation, (lower, upper).
+    pub prediction: (Vec<f64>, Vec<f64>),
+}
+
+impl FittedModel {
+    /// Regression statistics of this model on the data it was fitted to.
+    pub fn statistics(&self, x: &[f64], y: &[f64]) -> RegressionStatistics {
+        regression_statistics(&self.basis.design_matrix(x), &Array1::from(y.to_vec()), &self.fit)
+    }
+
+    /// Confidence and prediction bands at `xs` for the given `level`.
+    pub fn prediction_bands(&self, stats: &RegressionStatistics, xs: &[f64], level: f64) -> PredictionBands {
+        let mut bands = PredictionBands {
+            x: xs.to_vec(),
+            fit: Vec::with_capacity(xs.len()),
+            confidence: (Vec::with_capacity(xs.len()), Vec::with_capacity(xs.len())),
+            prediction: (Vec::with_capacity(xs.len()), Vec::with_capacity(xs.len())),
+        };
+        for &x in xs {
+            let row = self.basis.evaluate(x);
+            let confidence = stats.confidence_interval(&row, level);
+            let prediction = stats.prediction_interval(&row, level);
+            bands.fit.push(confidence.estimate);
+            bands.confidence.0.push(confidence.lower);
+            bands.confidence.1.push(confidence.upper);
+            bands.prediction.0.push(prediction.lower);
+            bands.prediction.1.push(prediction.upper);
+        }
+        bands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::week05_linear_least_squares::{fit, Basis, LstsqMethod};
+
+    // Straight line with known textbook statistics.
+    const X: [f64; 5] = [1.0, 2.0, 3.0, 4.0, 5.0];
+    const Y: [f64; 5] = [2.0, 4.0, 5.0, 4.0, 5.0];
+
+    #[test]
+    fn test_simple_linear_regression() {
+        // ŷ = 2.2 + 0.6x, RSS = 2.4, s² = 0.8, Sxx = 10.
+        let model = fit(Basis::Monomial { degree: 1 }, &X, &Y, LstsqMethod::Qr);
+        let stats = model.statistics(&X, &Y);
+        assert_eq!(stats.dof, 3);
+        assert!((stats.residual_variance - 0.8).abs() < 1e-12);
+        assert!((stats.standard_errors[1] - (0.8_f64 / 10.0).sqrt()).abs() < 1e-12);
+        assert!((stats.standard_errors[0] - (0.8_f64 * (1.0 / 5.0 + 9.0 / 10.0)).sqrt()).abs() < 1e-12);
+        assert!((stats.r_squared - 0.6).abs() < 1e-12);
+        assert!((stats.adj_r_squared - (1.0 - 0.4 * 4.0 / 3.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_no_residual_degrees_of_freedom() {
+        // A line through two points leaves nothing to estimate the variance from.
+        let model = fit(Basis::Monomial { degree: 1 }, &X[..2], &Y[..2], LstsqMethod::Qr);
+        let stats = model.statistics(&X[..2], &Y[..2]);
+        assert_eq!(stats.dof, 0);
+        assert!(stats.residual_variance.is_nan());
+        assert!(stats.adj_r_squared.is_nan());
+    }
+
+    #[test]
+    fn test_intervals_and_p_values() {
+        let model = fit(Basis::Monomial { degree: 1 }, &X, &Y, LstsqMethod::Svd);
+        let stats = model.statistics(&X, &Y);
+        // t = 0.6 / sqrt(0.08) on 3 dof.
+        let t = 0.6 / 0.08_f64.sqrt();
+        assert!((stats.t_statistics[1] - t).abs() < 1e-10);
+        assert!((stats.p_values[1] - 2.0 * (1.0 - student_t_cdf(t, 3.0))).abs() < 1e-12);
+
+        let slope = stats.parameter_intervals(0.95)[1];
+        assert!((slope.upper - slope.lower - 2.0 * 3.182_446_305 * 0.08_f64.sqrt()).abs() < 1e-8);
+
+        // Prediction intervals are always wider than confidence intervals.
+        let row = [1.0, 3.0];
+        let ci = stats.confidence_interval(&row, 0.95);
+        let pi = stats.prediction_interval(&row, 0.95);
+        assert!((ci.estimate - 4.0).abs() < 1e-12);
+        assert!(pi.lower < ci.lower && pi.upper > ci.upper);
+    }
+
+    #[test]
+    fn test_residual_diagnostics() {
+        let model = fit(Basis::Monomial { degree: 1 }, &X, &Y, LstsqMethod::Qr);
+        let stats = model.statistics(&X, &Y);
+        let d = &stats.diagnostics;
+        // Leverages sum to p and h_ii = 1/n + (x_i - x̄)²/Sxx.
+        assert!((d.leverage.sum() - 2.0).abs() < 1e-12);
+        assert!((d.leverage[0] - (0.2 + 0.4)).abs() < 1e-12);
+        assert!(d.mean.abs() < 1e-12);
+        // Residuals -0.8, 0.6, 1.0, -0.6, -0.2.
+        let dw = (1.4_f64.powi(2) + 0.4_f64.powi(2) + 1.6_f64.powi(2) + 0.4_f64.powi(2)) / 2.4;
+        assert!((d.durbin_watson - dw).abs() < 1e-12);
+        assert!(d.cooks_distance.iter().all(|&c| c >= 0.0));
+    }
+
+    #[test]
+    fn test_information_criteria_prefer_true_model() {
+        let x: Vec<f64> = (0..20).map(|i| i as f64 * 0.5).collect();
+        // Quadratic plus a small deterministic wiggle.
+        let y: Vec<f64> = x.iter().map(|&x| 1.0 + 0.5 * x - 0.2 * x * x + 0.05 * (7.0 * x).sin()).collect();
+        let bic: Vec<f64> = (1..=5)
+            .map(|degree| fit(Basis::Monomial { degree }, &x, &y, LstsqMethod::Qr).statistics(&x, &y).bic)
+            .collect();
+        assert!(bic[1] < bic[0]);
+    }
+}
@@ -0,0 +1,31 @@
+//! Core data structures for linear least squares.
+
+use ndarray::Array1;
+
+/// Algorithm used to solve min ||A x - y||₂.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LstsqMethod {
+    /// Cholesky factorisation of AᵀA x = Aᵀy. Fast but squares the condition number.
+    NormalEquations,
+    /// Householder QR: R x = Qᵀy.
+    Qr,
+    /// Singular value decomposition with small singular values truncated.
+    Svd,
+}
+
+/// Result from a linear least-squares solve.
+#[derive(Debug, Clone)]
+pub struct LstsqResult {
+    /// Fitted coefficients x.
+    pub coefficients: Array1<f64>,
+    /// Residuals y - A x.
+    pub residuals: Array1<f64>,
+    /// Residual sum of squares ||y - A x||².
+    pub rss: f64,
+    /// Numerical rank of A as seen by the method.
+    pub rank: usize,
+    /// Estimate of the 2-norm condition number of A.
+    pub condition_number: f64,
+    /// Which method produced this result.
+    pub method: LstsqMethod,
+}
@@ -0,0 +1,256 @@
+//! Weighted and generalised least squares.
+//!
+//! With measurement covariance C the best linear unbiased estimate minimises
+//! (y - A x)ᵀ C⁻¹ (y - A x). Factoring C = L Lᵀ and "whitening" both sides,
+//! Ã = L⁻¹ A and ỹ = L⁻¹ y, turns this into an ordinary problem that any of
+//! the week05 solvers can handle. Independent errors with standard deviations
+//! σ_i are the diagonal case, where whitening just divides row i by σ_i.
+//!
+//! Because σ is known rather than estimated, the parameter covariance is
+//! (Aᵀ C⁻¹ A)⁻¹ without an s² factor, and the minimised objective is a
+//! chi-squared statistic on n - p degrees of freedom.
+
+use ndarray::{Array1, Array2, Axis};
+
+use super::basis::Basis;
+use super::decomposition::{cholesky, solve_lower_triangular};
+use super::distributions::chi_squared_cdf;
+use super::fit::FittedModel;
+use super::lstsq::lstsq;
+use super::statistics::normal_matrix_pseudo_inverse;
+use super::types::{LstsqMethod, LstsqResult};
+
+/// How the measurement errors are described.
+#[derive(Debug, Clone)]
+pub enum Weighting {
+    /// Independent errors with weights w_i = 1 / σ_i².
+    Weights(Array1<f64>),
+    /// Full symmetric positive definite error covariance C.
+    Covariance(Array2<f64>),
+}
+
+impl Weighting {
+    /// Independent errors with the given standard deviations.
+    pub fn from_sigma(sigma: &[f64]) -> Self {
+        Weighting::Weights(sigma.iter().map(|s| 1.0 / (s * s)).collect())
+    }
+
+    /// Returns (L⁻¹ A, L⁻¹ y) where C = L Lᵀ.
+    ///
+    /// Fails if the weights or covariance do not match the number of rows, a
+    /// weight is negative or not finite, or the covariance is not symmetric
+    /// positive definite.
+    pub fn whiten(&self, a: &Array2<f64>, y: &Array1<f64>) -> Result<(Array2<f64>, Array1<f64>), String> {
+        let n = a.nrows();
+        if y.len() != n {
+            return Err(format!("{} observations for a design matrix with {} rows", y.len(), n));
+        }
+        match self {
+            Weighting::Weights(w) => {
+                if w.len() != n {
+                    return Err(format!("{} weights for {} observations", w.len(), n));
+                }
+                if let Some(bad) = w.iter().find(|&&w| !(w >= 0.0 && w.is_finite())) {
+                    return Err(format!("weights must be non-negative and finite, found {}", bad));
+                }
+                let root = w.mapv(f64::sqrt);
+                let a_white = a * &root.view().insert_axis(Axis(1));
+                Ok((a_white, y * &root))
+            }
+            Weighting::Covariance(c) => {
+                if c.dim() != (n, n) {
+                    return Err(format!("{:?} covariance for {} observations", c.dim(), n));
+                }
+                let l = cholesky(c).ok_or("covariance must be symmetric positive definite")?;
+                let mut a_white = Array2::<f64>::zeros(a.dim());
+                for (k, column) in a.axis_iter(Axis(1)).enumerate() {
+                    a_white.column_mut(k).assign(&solve_lower_triangular(&l, &column.to_owned()));
+                }
+                Ok((a_white, solve_lower_triangular(&l, y)))
+            }
+        }
+    }
+}
+
+/// Result of a weighted or generalised least-squares solve.
+#[derive(Debug, Clone)]
+pub struct WeightedLstsqResult {
+    /// Solution, with `residuals` and `rss` in the original (unweighted) units.
+    pub fit: LstsqResult,
+    /// Whitened residuals L⁻¹ (y - A x); these are N(0, 1) if the model is right.
+    pub normalized_residuals: Array1<f64>,
+    /// χ² = (y - A x)ᵀ C⁻¹ (y - A x).
+    pub chi_squared: f64,
+    /// Degrees of freedom n - p.
+    pub dof: usize,
+    /// χ² / (n - p); close to 1 when the stated uncertainties are right.
+    pub reduced_chi_squared: f64,
+    /// Probability of a χ² at least this large if the model and errors are correct.
+    pub p_value: f64,
+    /// Parameter covariance (Aᵀ C⁻¹ A)⁻¹.
+    pub covariance: Array2<f64>,
+    /// Standard error of each coefficient.
+    pub standard_errors: Array1<f64>,
+}
+
+/// Solves min (y - A x)ᵀ C⁻¹ (y - A x) by whitening and the chosen method.
+///
+/// Fails if `weighting` cannot be applied; see [`Weighting::whiten`].
+pub fn weighted_lstsq(
+    a: &Array2<f64>,
+    y: &Array1<f64>,
+    weighting: &Weighting,
+    method: LstsqMethod,
+) -> Result<WeightedLstsqResult, String> {
+    let (a_white, y_white) = weighting.whiten(a, y)?;
+    let white = lstsq(&a_white, &y_white, method);
+    let (covariance, _, rank) = normal_matrix_pseudo_inverse(&a_white);
+
+    let dof = a.nrows().saturating_sub(rank);
+    let chi_squared = white.rss;
+    let residuals = y - &a.dot(&white.coefficients);
+    let rss = residuals.dot(&residuals);
+
+    Ok(WeightedLstsqResult {
+        standard_errors: covariance.diag().mapv(f64::sqrt),
+        covariance,
+        normalized_residuals: white.residuals,
+        chi_squared,
+        dof,
+        reduced_chi_squared: chi_squared / dof as f64,
+        p_value: 1.0 - chi_squared_cdf(chi_squared, dof as f64),
+        fit: LstsqResult { residuals, rss, ..white },
+    })
+}
+
+/// Fits `basis` to (x, y) with the given error model.
+///
+/// The returned model carries the same solution as the weighted result, with
+/// residuals in the original units.
+pub fn fit_weighted(
+    basis: Basis,
+    x: &[f64],
+    y: &[f64],
+    weighting: &Weighting,
+    method: LstsqMethod,
+) -> Result<(FittedModel, WeightedLstsqResult), String> {
+    let a = basis.design_matrix(x);
+    let result = weighted_lstsq(&a, &Array1::from(y.to_vec()), weighting, method)?;
+    let model = FittedModel { basis, fit: result.fit.clone() };
+    Ok((model, result))
+}
+
+/// Tabulated measurements y(x) with optional standard deviations.
+#[derive(Debug, Clone)]
+pub struct Measurements {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub sigma: Option<Vec<f64>>,
+}
+
+impl Measurements {
+    /// Loads the named columns from a CSV file with a header row.
+    pub fn from_csv(
+        path: &str,
+        x_column: &str,
+        y_column: &str,
+        sigma_column: Option<&str>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let table = crate::common::csv::read_csv(path)?;
+        let column = |name: &str| table.column(name).ok_or_else(|| format!("{}: no column named '{}'", path, name));
+
+        let x = column(x_column)?;
+        let y = column(y_column)?;
+        let sigma = sigma_column.map(column).transpose()?;
+        if let Some(sigma) = &sigma {
+            if let Some(bad) = sigma.iter().find(|&&s| s <= 0.0 || !s.is_finite()) {
+                return Err(format!("{}: uncertainties must be positive, found {}", path, bad).into());
+            }
+        }
+        Ok(Measurements { x, y, sigma })
+    }
+
+    /// Diagonal weighting from the σ column, if there is one.
+    pub fn weighting(&self) -> Option<Weighting> {
+        self.sigma.as_deref().map(Weighting::from_sigma)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_weighted_mean() {
+        // Fitting a constant gives the inverse-variance weighted mean.
+        let a = Array2::ones((3, 1));
+        let y = array![1.0, 2.0, 4.0];
+        let sigma = [1.0, 0.5, 2.0];
+        let result = weighted_lstsq(&a, &y, &Weighting::from_sigma(&sigma), LstsqMethod::Qr).unwrap();
+
+        let w: Vec<f64> = sigma.iter().map(|s| 1.0 / (s * s)).collect();
+        let w_sum: f64 = w.iter().sum();
+        let mean = (w[0] * 1.0 + w[1] * 2.0 + w[2] * 4.0) / w_sum;
+        assert!((result.fit.coefficients[0] - mean).abs() < 1e-12);
+        assert!((result.standard_errors[0] - (1.0 / w_sum).sqrt()).abs() < 1e-12);
+        assert!((result.fit.residuals[2] - (4.0 - mean)).abs() < 1e-12);
+        assert!((result.normalized_residuals[2] - (4.0 - mean) / 2.0).abs() < 1e-12);
+        assert_eq!(result.dof, 2);
+    }
+
+    #[test]
+    fn test_diagonal_covariance_matches_weights() {
+        let a = array![[1.0, 0.0], [1.0, 1.0], [1.0, 2.0], [1.0, 3.0]];
+        let y = array![0.9, 3.2, 4.8, 7.3];
+        let sigma = [0.1, 0.2, 0.3, 0.4];
+        let weights = weighted_lstsq(&a, &y, &Weighting::from_sigma(&sigma), LstsqMethod::Svd).unwrap();
+        let cov = Array2::from_diag(&Array1::from(sigma.iter().map(|s| s * s).collect::<Vec<_>>()));
+        let gls = weighted_lstsq(&a, &y, &Weighting::Covariance(cov), LstsqMethod::NormalEquations).unwrap();
+        for k in 0..2 {
+            assert!((weights.fit.coefficients[k] - gls.fit.coefficients[k]).abs() < 1e-10);
+            assert!((weights.standard_errors[k] - gls.standard_errors[k]).abs() < 1e-10);
+        }
+        assert!((weights.chi_squared - gls.chi_squared).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_correlated_errors_change_estimate() {
+        // Two measurements of one quantity with strongly correlated errors.
+        let a = Array2::ones((2, 1));
+        let y = array![1.0, 2.0];
+        let cov = array![[1.0, 0.9], [0.9, 4.0]];
+        let result = weighted_lstsq(&a, &y, &Weighting::Covariance(cov.clone()), LstsqMethod::Qr).unwrap();
+        // BLUE weights ∝ C⁻¹ 1 = [3.1, 0.1] / det.
+        let expected = (3.1 * 1.0 + 0.1 * 2.0) / 3.2;
+        assert!((result.fit.coefficients[0] - expected).abs() < 1e-12);
+        let det = 4.0 - 0.81;
+        assert!((result.covariance[[0, 0]] - det / 3.2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_invalid_weighting_is_an_error() {
+        let a = Array2::ones((2, 1));
+        let y = array![1.0, 2.0];
+        let solve = |weighting: Weighting| weighted_lstsq(&a, &y, &weighting, LstsqMethod::Qr);
+        assert!(solve(Weighting::Weights(array![1.0, -1.0])).is_err());
+        assert!(solve(Weighting::Weights(array![1.0])).is_err());
+        assert!(solve(Weighting::Covariance(array![[1.0, 2.0], [2.0, 1.0]])).is_err());
+        assert!(solve(Weighting::Covariance(Array2::eye(3))).is_err());
+        assert!(weighted_lstsq(&a, &array![1.0], &Weighting::from_sigma(&[1.0, 1.0]), LstsqMethod::Qr).is_err());
+    }
+
+    #[test]
+    fn test_measurements_from_csv() {
+        let path = "/tmp/test_measurements.csv";
+        std::fs::write(path, "t,signal,err\n0,1.0,0.1\n1,2.0,0.2\n").unwrap();
+        let data = Measurements::from_csv(path, "t", "signal", Some("err")).unwrap();
+        assert_eq!(data.x, vec![0.0, 1.0]);
+        assert!(matches!(data.weighting(), Some(Weighting::Weights(w)) if (w[1] - 25.0).abs() < 1e-9));
+        assert!(Measurements::from_csv(path, "t", "missing", None).is_err());
+
+        std::fs::write(path, "x,y,s\n0,1,0\n").unwrap();
+        assert!(Measurements::from_csv(path, "x", "y", Some("s")).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,298 @@
+//! Fitting a model to columns of a CSV file, as done by `eg551 fit`.
+//!
+//! Polynomials are linear in their coefficients and are solved directly by
+//! the week05 least-squares routines; library curves are fitted by
+//! Levenberg-Marquardt from their automatic initial guess. Both are then
+//! wrapped as the same weighted [`CurveFit`], so parameter uncertainties
+//! are computed one way: from the residual variance, or from σ when the
+//! file has an uncertainty column.
+
+use std::io;
+use std::path::Path;
+
+use super::levenberg_marquardt::levenberg_marquardt;
+use super::models::CurveModel;
+use super::types::{CurveFit, LevenbergMarquardtConfig, NlsResult};
+use super::uncertainty::{parameter_uncertainty, parameter_uncertainty_known_sigma, ParameterUncertainty};
+use crate::common::csv::write_csv;
+use crate::week05_linear_least_squares::{fit, fit_weighted, Basis, LstsqMethod, Measurements};
+
+#[cfg(feature = "plotting")]
+use crate::common::plot::{self, PlotConfig};
+
+/// A model that can be fitted from the command line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FitModel {
+    /// c0 + c1 x + ... + c_degree x^degree, solved by linear least squares.
+    Polynomial { degree: usize },
+    /// A nonlinear model from the curve library.
+    Curve(CurveModel),
+}
+
+impl FitModel {
+    /// Parses "linear", "quadratic", "cubic", "poly<N>" or a [`CurveModel`] name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let lower = name.trim().to_lowercase();
+        let degree = match lower.as_str() {
+            "linear" | "line" => Some(1),
+            "quadratic" => Some(2),
+            "cubic" => Some(3),
+            _ => lower.strip_prefix("poly").and_then(|d| d.trim_start_matches("nomial").parse().ok()),
+        };
+        match degree {
+            Some(degree) => Some(FitModel::Polynomial { degree }),
+            None => CurveModel::from_name(&lower).map(FitModel::Curve),
+        }
+    }
+
+    /// Names accepted by [`FitModel::from_name`], for usage messages.
+    pub fn available_names() -> Vec<String> {
+        let mut names: Vec<String> = ["linear", "quadratic", "cubic", "poly<N>"].iter().map(|s| s.to_string()).collect();
+        names.extend(CurveModel::ALL.iter().map(|m| m.name().to_string()));
+        names
+    }
+
+    /// The model formula, for reports.
+    pub fn formula(&self) -> String {
+        match self {
+            FitModel::Polynomial { degree } => {
+                let terms: Vec<String> = (0..=*degree)
+                    .map(|k| match k {
+                        0 => String::from("c0"),
+                        1 => String::from("c1 x"),
+                        _ => format!("c{k} x^{k}"),
+                    })
+                    .collect();
+                format!("y = {}", terms.join(" + "))
+            }
+            FitModel::Curve(model) => model.formula().to_string(),
+        }
+    }
+
+    /// Parameter names, in the order of the parameter vector.
+    pub fn param_names(&self) -> Vec<String> {
+        match self {
+            FitModel::Polynomial { degree } => (0..=*degree).map(|k| format!("c{k}")).collect(),
+            FitModel::Curve(model) => model.param_names().iter().map(|n| n.to_string()).collect(),
+        }
+    }
+
+    /// Evaluate m(x; p).
+    pub fn evaluate(&self, x: f64, p: &[f64]) -> f64 {
+        match self {
+            FitModel::Polynomial { .. } => p.iter().rev().fold(0.0, |acc, c| acc * x + c),
+            FitModel::Curve(model) => model.evaluate(x, p),
+        }
+    }
+
+    /// The weighted curve-fitting problem for `data`, with analytic Jacobian.
+    pub fn curve_fit<'a>(self, data: &Measurements) -> CurveFit<'a> {
+        let fit = match self {
+            FitModel::Polynomial { degree } => {
+                let basis = Basis::Monomial { degree };
+                CurveFit::new(&data.x, &data.y, degree + 1, move |x, p| self.evaluate(x, p))
+                    .with_gradient(move |x, _| basis.evaluate(x))
+            }
+            FitModel::Curve(model) => model.curve_fit(&data.x, &data.y),
+        };
+        let names = self.param_names();
+        let fit = fit.with_names(&names.iter().map(String::as_str).collect::<Vec<_>>());
+        match &data.sigma {
+            Some(sigma) => fit.with_sigma(sigma),
+            None => fit,
+        }
+    }
+
+    /// Starting point: the exact (weighted) least-squares solution for a
+    /// polynomial, the library heuristic for a curve.
+    ///
+    /// The polynomial is solved by SVD, so a degree too high for the data
+    /// gives the minimum-norm coefficients rather than failing. Uncertainties
+    /// that cannot be used as weights give the unweighted solution.
+    pub fn initial_guess(&self, data: &Measurements) -> Vec<f64> {
+        match self {
+            FitModel::Polynomial { degree } => {
+                let basis = || Basis::Monomial { degree: *degree };
+                let weighted = data
+                    .weighting()
+                    .and_then(|weighting| fit_weighted(basis(), &data.x, &data.y, &weighting, LstsqMethod::Svd).ok());
+                let model = match weighted {
+                    Some((model, _)) => model,
+                    None => fit(basis(), &data.x, &data.y, LstsqMethod::Svd),
+                };
+                model.coefficients().to_vec()
+            }
+            FitModel::Curve(model) => model.initial_guess(&data.x, &data.y),
+        }
+    }
+}
+
+/// A model fitted to tabulated measurements, with parameter uncertainties.
+pub struct CsvFit {
+    pub model: FitModel,
+    pub data: Measurements,
+    pub result: NlsResult,
+    /// From σ if the data has uncertainties, otherwise from the residual variance.
+    pub uncertainty: ParameterUncertainty,
+}
+
+/// Fits `model` to `data`, from `start` if given or else the model's initial guess.
+pub fn fit_measurements(
+    model: FitModel,
+    data: Measurements,
+    start: Option<&[f64]>,
+    config: &LevenbergMarquardtConfig,
+) -> CsvFit {
+    let problem = model.curve_fit(&data);
+    let p0 = start.map_or_else(|| model.initial_guess(&data), |p| p.to_vec());
+    let result = levenberg_marquardt(&problem, &p0, config);
+    let uncertainty = match data.sigma {
+        Some(_) => parameter_uncertainty_known_sigma(&problem, &result),
+        None => parameter_uncertainty(&problem, &result),
+    };
+    CsvFit { model, data, result, uncertainty }
+}
+
+impl CsvFit {
+    /// Model values at the data points.
+    pub fn fitted(&self) -> Vec<f64> {
+        self.data.x.iter().map(|&x| self.model.evaluate(x, &self.result.params)).collect()
+    }
+
+    /// The fitted curve at `n` evenly spaced points spanning the data.
+    pub fn curve(&self, n: usize) -> (Vec<f64>, Vec<f64>) {
+        let x_min = self.data.x.iter().fold(f64::INFINITY, |a, &b| a.min(b));
+        let x_max = self.data.x.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+        let x: Vec<f64> = (0..n).map(|i| x_min + (x_max - x_min) * i as f64 / (n - 1) as f64).collect();
+        let y = x.iter().map(|&x| self.model.evaluate(x, &self.result.params)).collect();
+        (x, y)
+    }
+
+    /// Report lines: model, convergence, parameters with `level` intervals and fit quality.
+    pub fn report(&self, level: f64) -> Vec<String> {
+        let mut lines = vec![
+            format!("Model: {}", self.model.formula()),
+            format!(
+                "{} points, {} parameters: {:?} after {} iterations",
+                self.data.x.len(),
+                self.uncertainty.names.len(),
+                self.result.stop_reason,
+                self.result.iterations
+            ),
+        ];
+        lines.extend(self.uncertainty.report(level));
+        match self.uncertainty.chi_squared {
+            Some((chi_squared, p_value)) => lines.push(format!(
+                "chi^2 = {:.4} on {} degrees of freedom (reduced {:.4}, p = {:.4})",
+                chi_squared,
+                self.uncertainty.dof,
+                chi_squared / self.uncertainty.dof as f64,
+                p_value
+            )),
+            None => lines.push(format!(
+                "Residual standard deviation s = {:.6} on {} degrees of freedom",
+                self.uncertainty.residual_variance.sqrt(),
+                self.uncertainty.dof
+            )),
+        }
+        lines
+    }
+
+    /// Writes x, y, (sigma,) fit and residual y - fit at each data point.
+    pub fn write_csv<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let fitted = self.fitted();
+        let mut headers = vec!["x", "y"];
+        if self.data.sigma.is_some() {
+            headers.push("sigma");
+        }
+        headers.extend(["fit", "residual"]);
+        let rows: Vec<Vec<f64>> = (0..self.data.x.len())
+            .map(|i| {
+                let mut row = vec![self.data.x[i], self.data.y[i]];
+                if let Some(sigma) = &self.data.sigma {
+                    row.push(sigma[i]);
+                }
+                row.extend([fitted[i], self.data.y[i] - fitted[i]]);
+                row
+            })
+            .collect();
+        write_csv(path, &headers, &rows)
+    }
+
+    /// Plots the data and fitted curve above the residuals.
+    #[cfg(feature = "plotting")]
+    pub fn plot(&self, path: &str, x_label: &str, y_label: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let (x_fit, y_fit) = self.curve(200);
+        let residuals: Vec<f64> = self.data.y.iter().zip(self.fitted()).map(|(y, f)| y - f).collect();
+        let config = PlotConfig {
+            title: self.model.formula(),
+            x_label: String::from(x_label),
+            y_label: String::from(y_label),
+            ..PlotConfig::default()
+        };
+        plot::fit_residual_plot(path, &self.data.x, &self.data.y, &x_fit, &y_fit, &residuals, &config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::week05_linear_least_squares::regression_statistics;
+    use ndarray::Array1;
+
+    #[test]
+    fn test_model_names() {
+        assert_eq!(FitModel::from_name("linear"), Some(FitModel::Polynomial { degree: 1 }));
+        assert_eq!(FitModel::from_name("poly4"), Some(FitModel::Polynomial { degree: 4 }));
+        assert_eq!(FitModel::from_name("polynomial2"), Some(FitModel::Polynomial { degree: 2 }));
+        assert_eq!(FitModel::from_name("Logistic"), Some(FitModel::Curve(CurveModel::Logistic)));
+        assert_eq!(FitModel::from_name("spline"), None);
+        assert_eq!(FitModel::Polynomial { degree: 2 }.formula(), "y = c0 + c1 x + c2 x^2");
+    }
+
+    #[test]
+    fn test_polynomial_matches_linear_regression() {
+        let x: Vec<f64> = (0..15).map(|i| 0.5 * i as f64).collect();
+        let y: Vec<f64> = x.iter().map(|&x| 1.0 - 2.0 * x + 0.3 * x * x + 0.4 * (3.0 * x).sin()).collect();
+        let data = Measurements { x: x.clone(), y: y.clone(), sigma: None };
+        let result = fit_measurements(FitModel::Polynomial { degree: 2 }, data, None, &LevenbergMarquardtConfig::default());
+
+        let linear = fit(Basis::Monomial { degree: 2 }, &x, &y, LstsqMethod::Qr);
+        let stats = regression_statistics(&linear.basis.design_matrix(&x), &Array1::from(y), &linear.fit);
+        for k in 0..3 {
+            assert!((result.result.params[k] - linear.fit.coefficients[k]).abs() < 1e-10);
+            assert!((result.uncertainty.standard_errors[k] - stats.standard_errors[k]).abs() < 1e-10);
+        }
+
+        // More coefficients than points: minimum-norm start instead of a QR failure.
+        let guess = FitModel::Polynomial { degree: 30 }.initial_guess(&result.data);
+        assert_eq!(guess.len(), 31);
+        assert!(guess.iter().all(|c| c.is_finite()));
+    }
+
+    #[test]
+    fn test_weighted_decay_from_csv() {
+        let data = Measurements::from_csv("data/week06_decay.csv", "t", "counts", Some("sigma")).unwrap();
+        let result = fit_measurements(
+            FitModel::Curve(CurveModel::Exponential),
+            data,
+            None,
+            &LevenbergMarquardtConfig::default(),
+        );
+        assert!(result.result.converged());
+        // Generated from 120 exp(-0.35 t) + 8 with Poisson-like noise.
+        let intervals = result.uncertainty.intervals(0.99);
+        for (interval, truth) in intervals.iter().zip([120.0, 0.35, 8.0]) {
+            assert!(interval.lower < truth && truth < interval.upper);
+        }
+        let (chi_squared, _) = result.uncertainty.chi_squared.unwrap();
+        assert!((chi_squared / result.uncertainty.dof as f64 - 1.0).abs() < 0.8);
+
+        let path = "/tmp/test_csv_fit.csv";
+        result.write_csv(path).unwrap();
+        let table = crate::common::csv::read_csv(path).unwrap();
+        assert_eq!(table.headers, ["x", "y", "sigma", "fit", "residual"]);
+        assert_eq!(table.rows.len(), 24);
+        std::fs::remove_file(path).unwrap();
+    }
+}
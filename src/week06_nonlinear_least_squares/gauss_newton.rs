@@ -0,0 +1,153 @@
+//! Gauss-Newton method with step halving.
+//!
+//! Near p the residuals are approximately linear, r(p + δ) ≈ r + J δ, so the
+//! Gauss-Newton step solves the linear least-squares problem
+//! min ||J δ + r||₂ (here by SVD, which also copes with a rank-deficient J).
+//! Far from the solution the full step can overshoot, so it is halved until
+//! the cost ½||r||² decreases sufficiently (the Armijo condition).
+
+use ndarray::Array1;
+
+use super::types::{GaussNewtonConfig, NlsIteration, NlsResult, ResidualModel, StopReason};
+use crate::week05_linear_least_squares::lstsq::{default_rank_tolerance, svd_lstsq};
+
+/// Armijo constant: accept α when cost(p + αδ) <= cost(p) + ARMIJO α gᵀδ.
+const ARMIJO: f64 = 1e-4;
+
+/// Minimises ½||r(p)||² by Gauss-Newton iteration from `p0`.
+pub fn gauss_newton(model: &dyn ResidualModel, p0: &[f64], config: &GaussNewtonConfig) -> NlsResult {
+    assert_eq!(p0.len(), model.n_params(), "initial guess must have one value per parameter");
+    let mut p = Array1::from(p0.to_vec());
+    let mut r = model.residuals(p.as_slice().unwrap());
+    let mut cost = 0.5 * r.dot(&r);
+    let mut history = Vec::new();
+
+    let stop_reason = loop {
+        let j = model.jacobian(p.as_slice().unwrap());
+        let gradient = j.t().dot(&r);
+        if max_norm(&gradient) <= config.gradient_tol {
+            break StopReason::GradientTolerance;
+        }
+        if history.len() >= config.max_iter {
+            break StopReason::MaxIterations;
+        }
+
+        let step = svd_lstsq(&j, &r.mapv(|v| -v), default_rank_tolerance(&j)).coefficients;
+        let p_norm = p.dot(&p).sqrt();
+        if step.dot(&step).sqrt() <= config.step_tol * (p_norm + config.step_tol) {
+            break StopReason::StepTolerance;
+        }
+
+        // Step halving until the cost decreases sufficiently.
+        let slope = gradient.dot(&step);
+        let mut alpha = 1.0;
+        let mut accepted = None;
+        for _ in 0..=config.max_halvings {
+            let trial = &p + &(alpha * &step);
+            let trial_r = model.residuals(trial.as_slice().unwrap());
+            let trial_cost = 0.5 * trial_r.dot(&trial_r);
+            if trial_cost.is_finite() && trial_cost <= cost + ARMIJO * alpha * slope {
+                accepted = Some((trial, trial_r, trial_cost));
+                break;
+            }
+            alpha *= 0.5;
+        }
+        let Some((p_new, r_new, cost_new)) = accepted else {
+            break StopReason::LineSearchFailed;
+        };
+
+        let relative_step = alpha * step.dot(&step).sqrt() / (p_norm + config.step_tol);
+        let relative_cost_change = if cost > 0.0 { (cost - cost_new) / cost } else { 0.0 };
+        p = p_new;
+        r = r_new;
+        cost = cost_new;
+
+        let gradient_norm = max_norm(&model.jacobian(p.as_slice().unwrap()).t().dot(&r));
+        history.push(NlsIteration {
+            iteration: history.len() + 1,
+            params: p.to_vec(),
+            cost,
+            gradient_norm,
+            relative_step,
+            relative_cost_change,
+            step_length: alpha,
+            step_norm: alpha * step.dot(&step).sqrt(),
+            damping: 0.0,
+            accepted: true,
+        });
+
+        if relative_step <= config.step_tol {
+            break StopReason::StepTolerance;
+        }
+        if relative_cost_change <= config.cost_tol {
+            break StopReason::CostTolerance;
+        }
+    };
+
+    let gradient_norm = max_norm(&model.jacobian(p.as_slice().unwrap()).t().dot(&r));
+    NlsResult {
+        params: p.to_vec(),
+        residuals: r,
+        cost,
+        gradient_norm,
+        iterations: history.len(),
+        stop_reason,
+        history,
+    }
+}
+
+fn max_norm(v: &Array1<f64>) -> f64 {
+    v.iter().fold(0.0_f64, |acc, x| acc.max(x.abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::week06_nonlinear_least_squares::problems::michaelis_menten_model;
+    use crate::week06_nonlinear_least_squares::types::CurveFit;
+
+    #[test]
+    fn test_puromycin_reference_fit() {
+        // Reference values from Bates and Watts (1988).
+        let result = gauss_newton(&michaelis_menten_model(), &[205.0, 0.08], &GaussNewtonConfig::default());
+        assert!(result.converged(), "{:?}", result.stop_reason);
+        assert!((result.params[0] - 212.68).abs() < 0.01);
+        assert!((result.params[1] - 0.06412).abs() < 1e-5);
+        assert!((result.rss() - 1195.449).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_exact_exponential_recovered() {
+        let t: Vec<f64> = (0..15).map(|i| 0.5 * i as f64).collect();
+        let y: Vec<f64> = t.iter().map(|&t| 3.0 * (-0.7 * t).exp() + 0.5).collect();
+        let model = CurveFit::new(&t, &y, 3, |t, p| p[0] * (-p[1] * t).exp() + p[2])
+            .with_gradient(|t, p| vec![(-p[1] * t).exp(), -p[0] * t * (-p[1] * t).exp(), 1.0]);
+        let result = gauss_newton(&model, &[1.0, 0.3, 0.0], &GaussNewtonConfig::default());
+        assert!(result.converged());
+        for (p, expected) in result.params.iter().zip([3.0, 0.7, 0.5]) {
+            assert!((p - expected).abs() < 1e-8);
+        }
+        // Quadratic convergence on a zero-residual problem.
+        assert!(result.iterations < 15);
+        assert!(result.cost < 1e-20);
+    }
+
+    #[test]
+    fn test_step_halving_from_poor_start() {
+        let result = gauss_newton(&michaelis_menten_model(), &[100.0, 1.0], &GaussNewtonConfig::default());
+        assert!(result.converged());
+        assert!(result.history.iter().any(|it| it.step_length < 1.0));
+        // Every accepted step reduces the cost.
+        assert!(result.history.windows(2).all(|w| w[1].cost <= w[0].cost));
+        assert!((result.params[0] - 212.68).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_iteration_limit() {
+        let config = GaussNewtonConfig::default().with_max_iter(1);
+        let result = gauss_newton(&michaelis_menten_model(), &[100.0, 1.0], &config);
+        assert_eq!(result.stop_reason, StopReason::MaxIterations);
+        assert!(!result.converged());
+        assert_eq!(result.iterations, 1);
+    }
+}
@@ -0,0 +1,322 @@
+//! Global fitting of several datasets with shared parameters.
+//!
+//! Each dataset has its own model with the same m parameters. Some of them
+//! are shared, such as a rate constant common to every run, and the rest
+//! are local to each dataset, such as an amplitude. The global parameter
+//! vector holds the shared parameters once, followed by the local ones
+//! dataset by dataset:
+//!
+//!   p = (shared..., local of dataset 0..., local of dataset 1..., ...)
+//!
+//! and the residual vector stacks the residuals of every dataset, so the
+//! whole problem is a single [`ResidualModel`] for the week06 solvers.
+//!
+//! Whether sharing is justified is tested against independent fits of each
+//! dataset with the extra-sum-of-squares F statistic
+//!
+//!   F = ((RSS_global - RSS_separate) / (ν_global - ν_separate)) / (RSS_separate / ν_separate).
+
+use ndarray::{s, Array1, Array2};
+
+use super::levenberg_marquardt::levenberg_marquardt;
+use super::types::{LevenbergMarquardtConfig, NlsResult, ResidualModel};
+use super::uncertainty::{parameter_uncertainty, ParameterUncertainty};
+use crate::week05_linear_least_squares::distributions::f_cdf;
+
+/// Several datasets fitted together, with some model parameters shared.
+pub struct GlobalFit<'a> {
+    datasets: Vec<Box<dyn ResidualModel + 'a>>,
+    labels: Vec<String>,
+    shared: Vec<bool>,
+}
+
+impl<'a> GlobalFit<'a> {
+    /// A global fit of models with `shared.len()` parameters, where
+    /// `shared[k]` says whether parameter k is common to all datasets.
+    pub fn new(shared: &[bool]) -> Self {
+        Self { datasets: Vec::new(), labels: Vec::new(), shared: shared.to_vec() }
+    }
+
+    /// Add a dataset, given as its own residual model.
+    pub fn with_dataset(mut self, label: &str, model: impl ResidualModel + 'a) -> Self {
+        assert_eq!(model.n_params(), self.shared.len(), "dataset model has the wrong number of parameters");
+        self.datasets.push(Box::new(model));
+        self.labels.push(label.to_string());
+        self
+    }
+
+    /// Number of datasets.
+    pub fn n_datasets(&self) -> usize {
+        self.datasets.len()
+    }
+
+    /// Number of shared parameters.
+    pub fn n_shared(&self) -> usize {
+        self.shared.iter().filter(|&&s| s).count()
+    }
+
+    /// Number of local parameters per dataset.
+    pub fn n_local(&self) -> usize {
+        self.shared.len() - self.n_shared()
+    }
+
+    /// Dataset labels, in order.
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// The model of dataset `d`.
+    pub fn dataset(&self, d: usize) -> &dyn ResidualModel {
+        self.datasets[d].as_ref()
+    }
+
+    /// Position in the global parameter vector of parameter `k` of dataset `d`.
+    pub fn index(&self, d: usize, k: usize) -> usize {
+        let before = self.shared[..k].iter().filter(|&&s| s == self.shared[k]).count();
+        if self.shared[k] {
+            before
+        } else {
+            self.n_shared() + d * self.n_local() + before
+        }
+    }
+
+    /// The parameters of dataset `d` taken from the global vector `p`.
+    pub fn dataset_params(&self, p: &[f64], d: usize) -> Vec<f64> {
+        (0..self.shared.len()).map(|k| p[self.index(d, k)]).collect()
+    }
+
+    /// A global starting point from one start per dataset; shared
+    /// parameters start at the mean over datasets.
+    pub fn initial_params(&self, starts: &[Vec<f64>]) -> Vec<f64> {
+        assert_eq!(starts.len(), self.n_datasets(), "one start per dataset");
+        let mut p = vec![0.0; self.n_params()];
+        for (d, start) in starts.iter().enumerate() {
+            for (k, &value) in start.iter().enumerate() {
+                let weight = if self.shared[k] { 1.0 / self.n_datasets() as f64 } else { 1.0 };
+                p[self.index(d, k)] += weight * value;
+            }
+        }
+        p
+    }
+}
+
+impl ResidualModel for GlobalFit<'_> {
+    fn n_params(&self) -> usize {
+        self.n_shared() + self.n_datasets() * self.n_local()
+    }
+
+    fn n_residuals(&self) -> usize {
+        self.datasets.iter().map(|m| m.n_residuals()).sum()
+    }
+
+    fn residuals(&self, p: &[f64]) -> Array1<f64> {
+        let mut r = Vec::with_capacity(self.n_residuals());
+        for (d, model) in self.datasets.iter().enumerate() {
+            r.extend(model.residuals(&self.dataset_params(p, d)));
+        }
+        Array1::from(r)
+    }
+
+    /// Assembled from each dataset's Jacobian, analytic or finite-difference.
+    fn analytic_jacobian(&self, p: &[f64]) -> Option<Array2<f64>> {
+        let mut j = Array2::zeros((self.n_residuals(), self.n_params()));
+        let mut row = 0;
+        for (d, model) in self.datasets.iter().enumerate() {
+            let jd = model.jacobian(&self.dataset_params(p, d));
+            let rows = jd.nrows();
+            for (k, column) in jd.columns().into_iter().enumerate() {
+                let mut target = j.slice_mut(s![row..row + rows, self.index(d, k)]);
+                target += &column;
+            }
+            row += rows;
+        }
+        Some(j)
+    }
+
+    fn param_names(&self) -> Vec<String> {
+        let mut names = vec![String::new(); self.n_params()];
+        for (d, (model, label)) in self.datasets.iter().zip(&self.labels).enumerate() {
+            for (k, name) in model.param_names().into_iter().enumerate() {
+                names[self.index(d, k)] = if self.shared[k] { name } else { format!("{name}[{label}]") };
+            }
+        }
+        names
+    }
+}
+
+/// Fit quality of one dataset at the global solution.
+#[derive(Debug, Clone)]
+pub struct DatasetStatistics {
+    pub label: String,
+    pub n_points: usize,
+    /// The dataset's parameters, shared and local.
+    pub params: Vec<f64>,
+    /// Residual sum of squares (χ² if the dataset is weighted).
+    pub rss: f64,
+    /// Root-mean-square residual sqrt(RSS / n).
+    pub rms: f64,
+}
+
+/// Extra-sum-of-squares F test of the global fit against separate fits.
+#[derive(Debug, Clone)]
+pub struct SharingTest {
+    /// Total RSS when every dataset is fitted on its own.
+    pub rss_separate: f64,
+    /// Residual degrees of freedom of the separate fits.
+    pub dof_separate: usize,
+    pub f_statistic: f64,
+    /// Probability of an F at least this large if the shared parameters
+    /// really are common; small values reject sharing. NaN with nothing shared.
+    pub p_value: f64,
+}
+
+/// Result of [`global_fit`].
+#[derive(Debug, Clone)]
+pub struct GlobalFitResult {
+    pub result: NlsResult,
+    /// Uncertainties of the global parameters, from the pooled residual variance.
+    pub uncertainty: ParameterUncertainty,
+    pub datasets: Vec<DatasetStatistics>,
+    pub sharing: SharingTest,
+}
+
+impl GlobalFitResult {
+    /// Report lines: global parameters with `level` intervals, each dataset's
+    /// fit, the pooled fit and the test of sharing.
+    pub fn report(&self, level: f64) -> Vec<String> {
+        let mut lines = self.uncertainty.report(level);
+        for stats in &self.datasets {
+            lines.push(format!(
+                "{}: {} points, RSS = {:.6}, rms residual = {:.6}",
+                stats.label, stats.n_points, stats.rss, stats.rms
+            ));
+        }
+        lines.push(format!(
+            "global: RSS = {:.6} on {} degrees of freedom, s = {:.6}",
+            self.result.rss(),
+            self.uncertainty.dof,
+            self.uncertainty.residual_variance.sqrt()
+        ));
+        lines.push(format!(
+            "shared vs separate: RSS {:.6} vs {:.6}, F({}, {}) = {:.4}, p = {:.4}",
+            self.result.rss(),
+            self.sharing.rss_separate,
+            self.uncertainty.dof.saturating_sub(self.sharing.dof_separate),
+            self.sharing.dof_separate,
+            self.sharing.f_statistic,
+            self.sharing.p_value
+        ));
+        lines
+    }
+}
+
+/// Fit all datasets of `model` together by Levenberg-Marquardt from `p0`,
+/// then refit each dataset on its own from the global solution to test
+/// whether the shared parameters are consistent.
+pub fn global_fit(model: &GlobalFit, p0: &[f64], config: &LevenbergMarquardtConfig) -> GlobalFitResult {
+    let result = levenberg_marquardt(model, p0, config);
+    let uncertainty = parameter_uncertainty(model, &result);
+
+    let mut rss_separate = 0.0;
+    let mut datasets = Vec::with_capacity(model.n_datasets());
+    for (d, label) in model.labels().iter().enumerate() {
+        let dataset = model.dataset(d);
+        let params = model.dataset_params(&result.params, d);
+        let r = dataset.residuals(&params);
+        let rss = r.dot(&r);
+        let n_points = r.len();
+        rss_separate += levenberg_marquardt(dataset, &params, config).rss().min(rss);
+        let rms = (rss / n_points as f64).sqrt();
+        datasets.push(DatasetStatistics { label: label.clone(), n_points, params, rss, rms });
+    }
+
+    let dof_separate = model.n_residuals().saturating_sub(model.n_datasets() * model.shared.len());
+    let extra_dof = uncertainty.dof.saturating_sub(dof_separate);
+    let (f_statistic, p_value) = if extra_dof > 0 && dof_separate > 0 {
+        let f = ((result.rss() - rss_separate).max(0.0) / extra_dof as f64) / (rss_separate / dof_separate as f64);
+        (f, 1.0 - f_cdf(f, extra_dof as f64, dof_separate as f64))
+    } else {
+        (f64::NAN, f64::NAN)
+    };
+
+    GlobalFitResult {
+        result,
+        uncertainty,
+        datasets,
+        sharing: SharingTest { rss_separate, dof_separate, f_statistic, p_value },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::rng::Rng;
+    use crate::week06_nonlinear_least_squares::jacobian::{finite_difference_jacobian, FiniteDifference};
+    use crate::week06_nonlinear_least_squares::models::CurveModel;
+
+    fn decays<'a>(t: &[f64], curves: &[Vec<f64>]) -> GlobalFit<'a> {
+        let mut global = GlobalFit::new(&[false, true, false]);
+        for (d, y) in curves.iter().enumerate() {
+            global = global.with_dataset(&format!("run{d}"), CurveModel::Exponential.curve_fit(t, y));
+        }
+        global
+    }
+
+    fn noisy_decays(rates: &[f64], seed: u64) -> (Vec<f64>, Vec<Vec<f64>>) {
+        let mut rng = Rng::new(seed);
+        let t: Vec<f64> = (0..30).map(|i| 0.3 * i as f64).collect();
+        let curves = rates
+            .iter()
+            .enumerate()
+            .map(|(d, &k)| t.iter().map(|&t| (2.0 + d as f64) * (-k * t).exp() + 0.5 + 0.02 * rng.normal()).collect())
+            .collect();
+        (t, curves)
+    }
+
+    #[test]
+    fn test_parameter_map_and_names() {
+        let (t, curves) = noisy_decays(&[0.4, 0.4, 0.4], 1);
+        let global = decays(&t, &curves);
+        assert_eq!(global.n_params(), 1 + 3 * 2);
+        assert_eq!(global.n_residuals(), 90);
+        assert_eq!(global.index(0, 1), 0);
+        assert_eq!(global.index(2, 1), 0);
+        assert_eq!(global.index(1, 0), 3);
+        assert_eq!(global.index(1, 2), 4);
+        assert_eq!(global.param_names(), ["k", "A[run0]", "C[run0]", "A[run1]", "C[run1]", "A[run2]", "C[run2]"]);
+        let p = global.initial_params(&[vec![1.0, 0.2, 3.0], vec![2.0, 0.4, 4.0], vec![5.0, 0.6, 6.0]]);
+        assert!((p[0] - 0.4).abs() < 1e-12);
+        assert_eq!(global.dataset_params(&p, 2), [5.0, p[0], 6.0]);
+    }
+
+    #[test]
+    fn test_assembled_jacobian() {
+        let (t, curves) = noisy_decays(&[0.4, 0.4], 2);
+        let global = decays(&t, &curves);
+        let p = [0.3, 1.5, 0.2, 2.5, 0.7];
+        let analytic = global.jacobian(&p);
+        let numeric = finite_difference_jacobian(&global, &p, FiniteDifference::Central);
+        assert!((&analytic - &numeric).iter().all(|e| e.abs() < 1e-7));
+    }
+
+    #[test]
+    fn test_shared_rate_recovered_and_tested() {
+        let config = LevenbergMarquardtConfig::default();
+        let (t, curves) = noisy_decays(&[0.4, 0.4, 0.4], 3);
+        let global = decays(&t, &curves);
+        let p0 = global.initial_params(&vec![vec![1.0, 1.0, 0.0]; 3]);
+        let fit = global_fit(&global, &p0, &config);
+        assert!(fit.result.converged());
+        assert!((fit.result.params[0] - 0.4).abs() < 3.0 * fit.uncertainty.standard_errors[0]);
+        assert!(fit.sharing.p_value > 0.01);
+        let total: f64 = fit.datasets.iter().map(|d| d.rss).sum();
+        assert!((total - fit.result.rss()).abs() < 1e-12);
+
+        // Rates that really differ: sharing is rejected.
+        let (t, curves) = noisy_decays(&[0.3, 0.4, 0.5], 3);
+        let global = decays(&t, &curves);
+        let fit = global_fit(&global, &p0, &config);
+        assert!(fit.sharing.p_value < 1e-6);
+        assert!(fit.sharing.rss_separate < fit.result.rss());
+    }
+}
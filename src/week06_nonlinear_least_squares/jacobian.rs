@@ -0,0 +1,160 @@
+//! Finite-difference Jacobians and checking analytic ones.
+//!
+//! Forward differences cost one residual evaluation per parameter with
+//! error O(h); central differences cost two with error O(h²). The step is
+//! scaled to each parameter, h = c · max(|p_j|, 1), with c = √ε for forward
+//! and ∛ε for central differences, balancing truncation against rounding.
+
+use ndarray::Array2;
+
+use super::types::ResidualModel;
+
+/// Finite-difference scheme for approximating a Jacobian.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FiniteDifference {
+    /// (r(p + h e_j) - r(p)) / h.
+    Forward,
+    /// (r(p + h e_j) - r(p - h e_j)) / 2h.
+    Central,
+}
+
+/// Approximates J[i, j] = ∂r_i / ∂p_j by finite differences.
+pub fn finite_difference_jacobian<M: ResidualModel + ?Sized>(model: &M, p: &[f64], scheme: FiniteDifference) -> Array2<f64> {
+    let mut j = Array2::zeros((model.n_residuals(), p.len()));
+    let base = match scheme {
+        FiniteDifference::Forward => Some(model.residuals(p)),
+        FiniteDifference::Central => None,
+    };
+    let mut probe = p.to_vec();
+
+    for k in 0..p.len() {
+        let scale = p[k].abs().max(1.0);
+        let column = match &base {
+            Some(r0) => {
+                let h = f64::EPSILON.sqrt() * scale;
+                probe[k] = p[k] + h;
+                // Use the step actually represented in floating point.
+                let h = probe[k] - p[k];
+                (model.residuals(&probe) - r0) / h
+            }
+            None => {
+                let h = f64::EPSILON.cbrt() * scale;
+                probe[k] = p[k] + h;
+                let r_plus = model.residuals(&probe);
+                probe[k] = p[k] - h;
+                let r_minus = model.residuals(&probe);
+                (r_plus - r_minus) / (2.0 * h)
+            }
+        };
+        probe[k] = p[k];
+        j.column_mut(k).assign(&column);
+    }
+    j
+}
+
+/// Comparison of an analytic Jacobian with a central-difference one.
+#[derive(Debug, Clone)]
+pub struct JacobianCheck {
+    pub analytic: Array2<f64>,
+    pub numeric: Array2<f64>,
+    /// Largest |analytic - numeric| / max(|analytic|, 1) over all entries.
+    pub max_relative_error: f64,
+    /// (residual, parameter) index of the worst entry.
+    pub worst_entry: (usize, usize),
+}
+
+impl JacobianCheck {
+    /// Whether every entry agrees to within `tol` (relative, or absolute near zero).
+    pub fn passed(&self, tol: f64) -> bool {
+        self.max_relative_error <= tol
+    }
+
+    /// Parameters whose column contains an entry differing by more than `tol`.
+    pub fn suspect_parameters(&self, tol: f64) -> Vec<usize> {
+        (0..self.analytic.ncols())
+            .filter(|&k| {
+                self.analytic
+                    .column(k)
+                    .iter()
+                    .zip(self.numeric.column(k))
+                    .any(|(a, n)| (a - n).abs() / a.abs().max(1.0) > tol)
+            })
+            .collect()
+    }
+}
+
+/// Compares the model's analytic Jacobian at `p` with central differences.
+///
+/// Returns `None` if the model has no analytic Jacobian. Central differences
+/// are accurate to about ε^(2/3) ≈ 1e-10 relative, so a correct Jacobian
+/// typically passes with `tol` around 1e-6.
+pub fn check_jacobian<M: ResidualModel + ?Sized>(model: &M, p: &[f64]) -> Option<JacobianCheck> {
+    let analytic = model.analytic_jacobian(p)?;
+    let numeric = finite_difference_jacobian(model, p, FiniteDifference::Central);
+    assert_eq!(analytic.dim(), numeric.dim(), "analytic Jacobian has the wrong shape");
+
+    let mut max_relative_error = 0.0;
+    let mut worst_entry = (0, 0);
+    for ((index, a), n) in analytic.indexed_iter().zip(numeric.iter()) {
+        let error = (a - n).abs() / a.abs().max(1.0);
+        if error > max_relative_error || error.is_nan() {
+            max_relative_error = error;
+            worst_entry = index;
+        }
+    }
+    Some(JacobianCheck { analytic, numeric, max_relative_error, worst_entry })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::week06_nonlinear_least_squares::types::CurveFit;
+
+    fn decay(analytic: bool, wrong_sign: bool) -> CurveFit<'static> {
+        let t: Vec<f64> = (0..10).map(|i| 0.4 * i as f64).collect();
+        let y: Vec<f64> = t.iter().map(|&t| 2.0 * (-0.5 * t).exp()).collect();
+        let model = CurveFit::new(&t, &y, 2, |t, p| p[0] * (-p[1] * t).exp()).with_names(&["A", "k"]);
+        if !analytic {
+            return model;
+        }
+        let sign = if wrong_sign { 1.0 } else { -1.0 };
+        model.with_gradient(move |t, p| vec![(-p[1] * t).exp(), sign * p[0] * t * (-p[1] * t).exp()])
+    }
+
+    #[test]
+    fn test_schemes_match_analytic() {
+        let model = decay(true, false);
+        let p = [1.7, 0.3];
+        let exact = model.analytic_jacobian(&p).unwrap();
+        for (scheme, tol) in [(FiniteDifference::Forward, 1e-6), (FiniteDifference::Central, 1e-9)] {
+            let approx = finite_difference_jacobian(&model, &p, scheme);
+            for (a, b) in exact.iter().zip(approx.iter()) {
+                assert!((a - b).abs() < tol, "{scheme:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_fallback_without_analytic_jacobian() {
+        let model = decay(false, false);
+        assert!(model.analytic_jacobian(&[1.0, 1.0]).is_none());
+        assert!(check_jacobian(&model, &[1.0, 1.0]).is_none());
+        let fallback = model.jacobian(&[1.7, 0.3]);
+        let exact = decay(true, false).jacobian(&[1.7, 0.3]);
+        for (a, b) in exact.iter().zip(fallback.iter()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_check_flags_wrong_derivative() {
+        let good = check_jacobian(&decay(true, false), &[1.7, 0.3]).unwrap();
+        assert!(good.passed(1e-6));
+        assert!(good.suspect_parameters(1e-6).is_empty());
+
+        let bad = check_jacobian(&decay(true, true), &[1.7, 0.3]).unwrap();
+        assert!(!bad.passed(1e-6));
+        assert_eq!(bad.suspect_parameters(1e-6), vec![1]);
+        assert_eq!(bad.worst_entry.1, 1);
+    }
+}
@@ -0,0 +1,209 @@
+//! Levenberg-Marquardt method with Marquardt scaling and box bounds.
+//!
+//! Each step solves the damped Gauss-Newton system
+//!
+//!   (JᵀJ + λ D) δ = -Jᵀr,    D = diag(JᵀJ),
+//!
+//! as the least-squares problem min ||[J; √(λD)] δ + [r; 0]||₂. Small λ gives
+//! the Gauss-Newton step; large λ a short step along the scaled steepest
+//! descent direction. Marquardt's diagonal D makes the damping invariant to
+//! the units of each parameter; it holds the largest column norms seen so
+//! far, so a column that momentarily vanishes is still damped.
+//!
+//! λ adapts to the gain ratio ρ = actual / predicted cost reduction
+//! (Nielsen's rule): accepted steps shrink it by max(1/3, 1 - (2ρ - 1)³),
+//! rejected steps grow it by a factor that doubles on each rejection.
+//!
+//! With bounds, parameters sitting on a bound whose gradient points out of
+//! the box are held fixed, the step is taken in the others, and the trial
+//! point is projected back into the box.
+
+use ndarray::{concatenate, Array1, Array2, Axis};
+
+use super::types::{LevenbergMarquardtConfig, NlsIteration, NlsResult, ResidualModel, StopReason};
+use crate::week05_linear_least_squares::lstsq::qr_lstsq;
+
+/// Minimises ½||r(p)||² by Levenberg-Marquardt iteration from `p0`.
+///
+/// A starting point outside the bounds is first projected into them.
+pub fn levenberg_marquardt(model: &dyn ResidualModel, p0: &[f64], config: &LevenbergMarquardtConfig) -> NlsResult {
+    let n = model.n_params();
+    assert_eq!(p0.len(), n, "initial guess must have one value per parameter");
+    let lower = config.lower.clone().unwrap_or_else(|| vec![f64::NEG_INFINITY; n]);
+    let upper = config.upper.clone().unwrap_or_else(|| vec![f64::INFINITY; n]);
+    assert!(lower.len() == n && upper.len() == n, "one bound per parameter");
+    assert!(lower.iter().zip(&upper).all(|(l, u)| l <= u), "lower bounds must not exceed upper bounds");
+    let project = |p: Array1<f64>| -> Array1<f64> {
+        p.iter().enumerate().map(|(k, &v)| v.clamp(lower[k], upper[k])).collect()
+    };
+
+    let mut p = project(Array1::from(p0.to_vec()));
+    let mut r = model.residuals(p.as_slice().unwrap());
+    let mut cost = 0.5 * r.dot(&r);
+    let mut j = model.jacobian(p.as_slice().unwrap());
+    let mut scale = Array1::<f64>::zeros(n);
+    let mut lambda = config.initial_lambda;
+    let mut growth = 2.0;
+    let mut history = Vec::new();
+
+    let stop_reason = loop {
+        for (k, column) in j.axis_iter(Axis(1)).enumerate() {
+            scale[k] = scale[k].max(column.dot(&column));
+        }
+        let gradient = j.t().dot(&r);
+        let free = free_parameters(&p, &gradient, &lower, &upper);
+        if projected_norm(&gradient, &free) <= config.gradient_tol {
+            break StopReason::GradientTolerance;
+        }
+        if history.len() >= config.max_iter {
+            break StopReason::MaxIterations;
+        }
+
+        // Damped step in the free parameters, projected into the box.
+        let columns: Vec<usize> = (0..n).filter(|&k| free[k]).collect();
+        let damping: Array1<f64> = columns.iter().map(|&k| (lambda * scale[k].max(f64::MIN_POSITIVE)).sqrt()).collect();
+        let damping = Array2::from_diag(&damping);
+        let a = concatenate(Axis(0), &[j.select(Axis(1), &columns).view(), damping.view()]).unwrap();
+        let b = concatenate(Axis(0), &[r.mapv(|v| -v).view(), Array1::zeros(columns.len()).view()]).unwrap();
+        let delta = qr_lstsq(&a, &b).coefficients;
+        let mut trial = p.clone();
+        for (i, &k) in columns.iter().enumerate() {
+            trial[k] += delta[i];
+        }
+        let trial = project(trial);
+        let step = &trial - &p;
+
+        let p_norm = p.dot(&p).sqrt();
+        let step_norm = step.dot(&step).sqrt();
+        if step_norm <= config.step_tol * (p_norm + config.step_tol) {
+            break StopReason::StepTolerance;
+        }
+
+        let j_step = j.dot(&step);
+        let predicted = -(gradient.dot(&step) + 0.5 * j_step.dot(&j_step));
+        let trial_r = model.residuals(trial.as_slice().unwrap());
+        let trial_cost = 0.5 * trial_r.dot(&trial_r);
+        let rho = if predicted > 0.0 && trial_cost.is_finite() { (cost - trial_cost) / predicted } else { -1.0 };
+        let relative_cost_change = if cost > 0.0 { (cost - trial_cost) / cost } else { 0.0 };
+        let tried_lambda = lambda;
+
+        let accepted = rho > 0.0;
+        if accepted {
+            p = trial;
+            r = trial_r;
+            cost = trial_cost;
+            j = model.jacobian(p.as_slice().unwrap());
+            lambda *= (1.0 - (2.0 * rho - 1.0).powi(3)).max(1.0 / 3.0);
+            growth = 2.0;
+        } else {
+            lambda *= growth;
+            growth *= 2.0;
+        }
+
+        let gradient = j.t().dot(&r);
+        history.push(NlsIteration {
+            iteration: history.len() + 1,
+            params: p.to_vec(),
+            cost,
+            gradient_norm: projected_norm(&gradient, &free_parameters(&p, &gradient, &lower, &upper)),
+            relative_step: step_norm / (p_norm + config.step_tol),
+            relative_cost_change,
+            step_length: 1.0,
+            step_norm,
+            damping: tried_lambda,
+            accepted,
+        });
+
+        if accepted && step_norm <= config.step_tol * (p_norm + config.step_tol) {
+            break StopReason::StepTolerance;
+        }
+        if accepted && relative_cost_change <= config.cost_tol {
+            break StopReason::CostTolerance;
+        }
+    };
+
+    let gradient = j.t().dot(&r);
+    let gradient_norm = projected_norm(&gradient, &free_parameters(&p, &gradient, &lower, &upper));
+    NlsResult {
+        params: p.to_vec(),
+        residuals: r,
+        cost,
+        gradient_norm,
+        iterations: history.len(),
+        stop_reason,
+        history,
+    }
+}
+
+/// Parameters that may move: not held on a bound by a gradient pointing out of the box.
+fn free_parameters(p: &Array1<f64>, gradient: &Array1<f64>, lower: &[f64], upper: &[f64]) -> Vec<bool> {
+    (0..p.len())
+        .map(|k| !((p[k] <= lower[k] && gradient[k] > 0.0) || (p[k] >= upper[k] && gradient[k] < 0.0)))
+        .collect()
+}
+
+/// Max-norm of the gradient over the free parameters.
+fn projected_norm(gradient: &Array1<f64>, free: &[bool]) -> f64 {
+    gradient.iter().zip(free).filter(|(_, &f)| f).fold(0.0_f64, |acc, (g, _)| acc.max(g.abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::week06_nonlinear_least_squares::gauss_newton::gauss_newton;
+    use crate::week06_nonlinear_least_squares::problems::michaelis_menten_model;
+    use crate::week06_nonlinear_least_squares::types::GaussNewtonConfig;
+
+    #[test]
+    fn test_matches_gauss_newton() {
+        let lm = levenberg_marquardt(&michaelis_menten_model(), &[205.0, 0.08], &LevenbergMarquardtConfig::default());
+        let gn = gauss_newton(&michaelis_menten_model(), &[205.0, 0.08], &GaussNewtonConfig::default());
+        assert!(lm.converged(), "{:?}", lm.stop_reason);
+        for (a, b) in lm.params.iter().zip(&gn.params) {
+            assert!((a - b).abs() < 1e-6 * b.abs());
+        }
+    }
+
+    #[test]
+    fn test_bounds_hold_and_bind() {
+        let config = LevenbergMarquardtConfig::default().with_bounds(&[0.0, 0.0], &[f64::INFINITY, 0.05]);
+        let result = levenberg_marquardt(&michaelis_menten_model(), &[100.0, 1.0], &config);
+        assert!(result.converged(), "{:?}", result.stop_reason);
+        assert_eq!(result.params[1], 0.05);
+        assert!(result.history.iter().all(|it| it.params[1] <= 0.05 && it.params[0] >= 0.0));
+        // V_max re-fits to the bound; the free gradient vanishes but K_m's does not.
+        assert!(result.gradient_norm < 1e-6);
+        let j = michaelis_menten_model().jacobian(&result.params);
+        assert!(j.t().dot(&result.residuals)[1] < 0.0);
+    }
+
+    #[test]
+    fn test_converges_where_gauss_newton_diverges() {
+        let gn = gauss_newton(&michaelis_menten_model(), &[1.0, 1.0], &GaussNewtonConfig::default());
+        assert!(gn.params[0].abs() > 1e6);
+
+        let result = levenberg_marquardt(&michaelis_menten_model(), &[1.0, 1.0], &LevenbergMarquardtConfig::default());
+        assert!(result.converged(), "{:?}", result.stop_reason);
+        assert!((result.params[0] - 212.68).abs() < 0.01);
+        // Accepted steps never increase the cost; rejections raise λ.
+        let accepted: Vec<&NlsIteration> = result.history.iter().filter(|it| it.accepted).collect();
+        assert!(accepted.windows(2).all(|w| w[1].cost <= w[0].cost));
+        for w in result.history.windows(2) {
+            if !w[0].accepted {
+                assert!(w[1].damping > w[0].damping);
+            }
+        }
+    }
+
+    #[test]
+    fn test_history_csv() {
+        let path = "/tmp/test_lm_history.csv";
+        let result = levenberg_marquardt(&michaelis_menten_model(), &[100.0, 1.0], &LevenbergMarquardtConfig::default());
+        result.write_history_csv(path).unwrap();
+        let table = crate::common::csv::read_csv(path).unwrap();
+        assert_eq!(table.rows.len(), result.iterations);
+        assert_eq!(table.column("lambda").unwrap()[0], 1e-3);
+        assert!(table.column_index("step_norm").is_some());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,3 +1,33 @@
 //! Week 6: Nonlinear Least Squares
 //!
 //! Fitting nonlinear models using iterative least squares methods.
+
+pub mod types;
+pub mod jacobian;
+pub mod gauss_newton;
+pub mod levenberg_marquardt;
+pub mod uncertainty;
+pub mod models;
+pub mod csv_fit;
+pub mod separable;
+pub mod ode;
+pub mod multistart;
+pub mod global;
+pub mod problems;
+
+pub use types::{
+    CurveFit, GaussNewtonConfig, LevenbergMarquardtConfig, ModelFn, ModelGradientFn, NlsIteration, NlsResult,
+    ResidualModel, StopReason,
+};
+pub use gauss_newton::gauss_newton;
+pub use levenberg_marquardt::levenberg_marquardt;
+pub use jacobian::{check_jacobian, finite_difference_jacobian, FiniteDifference, JacobianCheck};
+pub use uncertainty::{
+    parameter_uncertainty, parameter_uncertainty_known_sigma, profile_interval, ParameterUncertainty, ProfileInterval,
+};
+pub use models::{CurveModel, GAS_CONSTANT};
+pub use csv_fit::{fit_measurements, CsvFit, FitModel};
+pub use separable::{variable_projection, SeparableBasisFn, SeparableDerivativeFn, SeparableFit, VarProResult};
+pub use ode::{solve_ode, InitialStateFn, OdeFit, OdeMethod, OdeRhsFn, OdeSolution};
+pub use multistart::{multi_start, sample_starts, MultiStartConfig, MultiStartResult, SamplingMethod, SolutionCluster};
+pub use global::{global_fit, DatasetStatistics, GlobalFit, GlobalFitResult, SharingTest};
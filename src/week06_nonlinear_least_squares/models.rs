@@ -0,0 +1,398 @@
+//! Library of common curve models with analytic Jacobians and initial guesses.
+//!
+//! Each [`CurveModel`] knows its formula, parameter names, parameter
+//! gradient ∂m/∂p and a heuristic starting point read off the data (an
+//! asymptote, a peak, a half-maximum crossing, a log-linear regression or a
+//! periodogram). The guesses are rough but land inside the basin of
+//! convergence for reasonably sampled data, which is all Levenberg-Marquardt
+//! needs.
+
+use std::f64::consts::{LN_2, PI};
+
+use super::levenberg_marquardt::levenberg_marquardt;
+use super::types::{CurveFit, LevenbergMarquardtConfig, NlsResult};
+
+/// Molar gas constant R in J / (mol K), used by [`CurveModel::Arrhenius`].
+pub const GAS_CONSTANT: f64 = 8.314462618;
+
+/// A named curve model m(x; p).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CurveModel {
+    /// A exp(-k x) + C.
+    Exponential,
+    /// A₁ exp(-k₁ x) + A₂ exp(-k₂ x) + C.
+    DoubleExponential,
+    /// A x^b.
+    PowerLaw,
+    /// K / (1 + exp(-r (x - x₀))).
+    Logistic,
+    /// A exp(-(x - μ)² / 2σ²) + C.
+    Gaussian,
+    /// A / (1 + ((x - x₀) / γ)²) + C, with γ the half width at half maximum.
+    Lorentzian,
+    /// A sin(ω x + φ) + C.
+    Sinusoid,
+    /// V_max x / (K_m + x).
+    MichaelisMenten,
+    /// A exp(-E_a / R x) with x the absolute temperature in kelvin.
+    Arrhenius,
+}
+
+impl CurveModel {
+    /// Every model in the library.
+    pub const ALL: [CurveModel; 9] = [
+        CurveModel::Exponential,
+        CurveModel::DoubleExponential,
+        CurveModel::PowerLaw,
+        CurveModel::Logistic,
+        CurveModel::Gaussian,
+        CurveModel::Lorentzian,
+        CurveModel::Sinusoid,
+        CurveModel::MichaelisMenten,
+        CurveModel::Arrhenius,
+    ];
+
+    /// Name used to select the model, e.g. "double_exponential".
+    pub fn name(&self) -> &'static str {
+        match self {
+            CurveModel::Exponential => "exponential",
+            CurveModel::DoubleExponential => "double_exponential",
+            CurveModel::PowerLaw => "power_law",
+            CurveModel::Logistic => "logistic",
+            CurveModel::Gaussian => "gaussian",
+            CurveModel::Lorentzian => "lorentzian",
+            CurveModel::Sinusoid => "sinusoid",
+            CurveModel::MichaelisMenten => "michaelis_menten",
+            CurveModel::Arrhenius => "arrhenius",
+        }
+    }
+
+    /// Look a model up by name, ignoring case and treating '-' and ' ' as '_'.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase().replace(['-', ' '], "_");
+        Self::ALL.into_iter().find(|m| m.name() == name)
+    }
+
+    /// The model formula, for reports.
+    pub fn formula(&self) -> &'static str {
+        match self {
+            CurveModel::Exponential => "y = A exp(-k x) + C",
+            CurveModel::DoubleExponential => "y = A1 exp(-k1 x) + A2 exp(-k2 x) + C",
+            CurveModel::PowerLaw => "y = A x^b",
+            CurveModel::Logistic => "y = K / (1 + exp(-r (x - x0)))",
+            CurveModel::Gaussian => "y = A exp(-(x - mu)^2 / (2 sigma^2)) + C",
+            CurveModel::Lorentzian => "y = A / (1 + ((x - x0) / gamma)^2) + C",
+            CurveModel::Sinusoid => "y = A sin(omega x + phi) + C",
+            CurveModel::MichaelisMenten => "y = V_max x / (K_m + x)",
+            CurveModel::Arrhenius => "y = A exp(-E_a / (R x))",
+        }
+    }
+
+    /// Parameter names, in the order of the parameter vector.
+    pub fn param_names(&self) -> &'static [&'static str] {
+        match self {
+            CurveModel::Exponential => &["A", "k", "C"],
+            CurveModel::DoubleExponential => &["A1", "k1", "A2", "k2", "C"],
+            CurveModel::PowerLaw => &["A", "b"],
+            CurveModel::Logistic => &["K", "r", "x0"],
+            CurveModel::Gaussian => &["A", "mu", "sigma", "C"],
+            CurveModel::Lorentzian => &["A", "x0", "gamma", "C"],
+            CurveModel::Sinusoid => &["A", "omega", "phi", "C"],
+            CurveModel::MichaelisMenten => &["V_max", "K_m"],
+            CurveModel::Arrhenius => &["A", "E_a"],
+        }
+    }
+
+    /// Number of parameters.
+    pub fn n_params(&self) -> usize {
+        self.param_names().len()
+    }
+
+    /// Evaluate m(x; p).
+    pub fn evaluate(&self, x: f64, p: &[f64]) -> f64 {
+        match self {
+            CurveModel::Exponential => p[0] * (-p[1] * x).exp() + p[2],
+            CurveModel::DoubleExponential => p[0] * (-p[1] * x).exp() + p[2] * (-p[3] * x).exp() + p[4],
+            CurveModel::PowerLaw => p[0] * x.powf(p[1]),
+            CurveModel::Logistic => p[0] / (1.0 + (-p[1] * (x - p[2])).exp()),
+            CurveModel::Gaussian => {
+                let z = (x - p[1]) / p[2];
+                p[0] * (-0.5 * z * z).exp() + p[3]
+            }
+            CurveModel::Lorentzian => {
+                let u = (x - p[1]) / p[2];
+                p[0] / (1.0 + u * u) + p[3]
+            }
+            CurveModel::Sinusoid => p[0] * (p[1] * x + p[2]).sin() + p[3],
+            CurveModel::MichaelisMenten => p[0] * x / (p[1] + x),
+            CurveModel::Arrhenius => p[0] * (-p[1] / (GAS_CONSTANT * x)).exp(),
+        }
+    }
+
+    /// Parameter gradient ∂m/∂p at x.
+    pub fn gradient(&self, x: f64, p: &[f64]) -> Vec<f64> {
+        match self {
+            CurveModel::Exponential => {
+                let e = (-p[1] * x).exp();
+                vec![e, -p[0] * x * e, 1.0]
+            }
+            CurveModel::DoubleExponential => {
+                let e1 = (-p[1] * x).exp();
+                let e2 = (-p[3] * x).exp();
+                vec![e1, -p[0] * x * e1, e2, -p[2] * x * e2, 1.0]
+            }
+            CurveModel::PowerLaw => {
+                let power = x.powf(p[1]);
+                vec![power, p[0] * power * x.ln()]
+            }
+            CurveModel::Logistic => {
+                let e = (-p[1] * (x - p[2])).exp();
+                let f = 1.0 / (1.0 + e);
+                let df = p[0] * f * f * e;
+                vec![f, df * (x - p[2]), -df * p[1]]
+            }
+            CurveModel::Gaussian => {
+                let z = (x - p[1]) / p[2];
+                let g = (-0.5 * z * z).exp();
+                vec![g, p[0] * g * z / p[2], p[0] * g * z * z / p[2], 1.0]
+            }
+            CurveModel::Lorentzian => {
+                let u = (x - p[1]) / p[2];
+                let l = 1.0 / (1.0 + u * u);
+                vec![l, 2.0 * p[0] * l * l * u / p[2], 2.0 * p[0] * l * l * u * u / p[2], 1.0]
+            }
+            CurveModel::Sinusoid => {
+                let (s, c) = (p[1] * x + p[2]).sin_cos();
+                vec![s, p[0] * c * x, p[0] * c, 1.0]
+            }
+            CurveModel::MichaelisMenten => vec![x / (p[1] + x), -p[0] * x / (p[1] + x).powi(2)],
+            CurveModel::Arrhenius => {
+                let e = (-p[1] / (GAS_CONSTANT * x)).exp();
+                vec![e, -p[0] * e / (GAS_CONSTANT * x)]
+            }
+        }
+    }
+
+    /// Heuristic starting point read off the data.
+    ///
+    /// - Exponential: C from the last sample, k from where |y - C| falls by 1/e.
+    /// - Double exponential: the single-exponential guess split into a fast
+    ///   and a slow component, k₁ = 2k and k₂ = k/2.
+    /// - Power law and Arrhenius: log-linear regression (ln y on ln x, or on 1/x).
+    /// - Logistic: K from the maximum, x₀ and r from the 25%, 50% and 75% crossings.
+    /// - Gaussian and Lorentzian: baseline from the median, peak (or dip) from
+    ///   the largest deviation, width from the points above half maximum.
+    /// - Sinusoid: frequency from the peak of a least-squares periodogram.
+    /// - Michaelis-Menten: V_max from the maximum, K_m at the half-maximum.
+    pub fn initial_guess(&self, x: &[f64], y: &[f64]) -> Vec<f64> {
+        assert_eq!(x.len(), y.len(), "x and y must have the same length");
+        assert!(x.len() >= 2, "need at least two points for an initial guess");
+        let mut points: Vec<(f64, f64)> = x.iter().copied().zip(y.iter().copied()).collect();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let range = points[points.len() - 1].0 - points[0].0;
+        let range = if range > 0.0 { range } else { 1.0 };
+
+        match self {
+            CurveModel::Exponential => exponential_guess(&points, range).to_vec(),
+            CurveModel::DoubleExponential => {
+                let [a, k, c] = exponential_guess(&points, range);
+                vec![0.5 * a, 2.0 * k, 0.5 * a, 0.5 * k, c]
+            }
+            CurveModel::PowerLaw => {
+                let sign = if points.iter().map(|p| p.1).sum::<f64>() < 0.0 { -1.0 } else { 1.0 };
+                let logs = points.iter().filter(|p| p.0 > 0.0 && sign * p.1 > 0.0).map(|p| (p.0.ln(), (sign * p.1).ln()));
+                match line_fit(logs) {
+                    Some((intercept, slope)) => vec![sign * intercept.exp(), slope],
+                    None => vec![sign, 1.0],
+                }
+            }
+            CurveModel::Logistic => {
+                let k = points.iter().fold(f64::NEG_INFINITY, |m, p| m.max(p.1));
+                let x25 = closest_x(&points, 0.25 * k);
+                let x50 = closest_x(&points, 0.5 * k);
+                let x75 = closest_x(&points, 0.75 * k);
+                // y crosses K/4 and 3K/4 at x₀ ∓ ln 3 / r.
+                let r = 2.0 * 3.0_f64.ln() / (x75 - x25);
+                let r = if r.is_finite() { r } else { 4.0 / range };
+                vec![k, r, x50]
+            }
+            CurveModel::Gaussian | CurveModel::Lorentzian => {
+                let mut sorted: Vec<f64> = points.iter().map(|p| p.1).collect();
+                sorted.sort_by(f64::total_cmp);
+                let baseline = sorted[sorted.len() / 2];
+                let peak = points
+                    .iter()
+                    .copied()
+                    .max_by(|a, b| (a.1 - baseline).abs().total_cmp(&(b.1 - baseline).abs()))
+                    .unwrap();
+                let amplitude = peak.1 - baseline;
+                let above: Vec<f64> =
+                    points.iter().filter(|p| (p.1 - baseline) / amplitude >= 0.5).map(|p| p.0).collect();
+                let fwhm = above.iter().fold(f64::NEG_INFINITY, |m, &x| m.max(x))
+                    - above.iter().fold(f64::INFINITY, |m, &x| m.min(x));
+                let fwhm = if fwhm > 0.0 { fwhm } else { 0.1 * range };
+                let width = match self {
+                    // FWHM = 2 √(2 ln 2) σ for a Gaussian, 2γ for a Lorentzian.
+                    CurveModel::Gaussian => fwhm / (2.0 * (2.0 * LN_2).sqrt()),
+                    _ => 0.5 * fwhm,
+                };
+                vec![amplitude, peak.0, width, baseline]
+            }
+            CurveModel::Sinusoid => sinusoid_guess(&points, range).to_vec(),
+            CurveModel::MichaelisMenten => {
+                let v_max = points.iter().fold(f64::NEG_INFINITY, |m, p| m.max(p.1));
+                vec![v_max, closest_x(&points, 0.5 * v_max)]
+            }
+            CurveModel::Arrhenius => {
+                // ln y = ln A - (E_a / R) (1 / x).
+                let logs = points.iter().filter(|p| p.0 > 0.0 && p.1 > 0.0).map(|p| (1.0 / p.0, p.1.ln()));
+                match line_fit(logs) {
+                    Some((intercept, slope)) => vec![intercept.exp(), -slope * GAS_CONSTANT],
+                    None => vec![1.0, 0.0],
+                }
+            }
+        }
+    }
+
+    /// A curve-fitting problem for this model with analytic Jacobian and named parameters.
+    pub fn curve_fit<'a>(self, x: &[f64], y: &[f64]) -> CurveFit<'a> {
+        CurveFit::new(x, y, self.n_params(), move |x, p| self.evaluate(x, p))
+            .with_gradient(move |x, p| self.gradient(x, p))
+            .with_names(self.param_names())
+    }
+
+    /// Fit by Levenberg-Marquardt from [`CurveModel::initial_guess`].
+    pub fn fit(self, x: &[f64], y: &[f64], config: &LevenbergMarquardtConfig) -> NlsResult {
+        levenberg_marquardt(&self.curve_fit(x, y), &self.initial_guess(x, y), config)
+    }
+}
+
+/// (A, k, C) for A exp(-k x) + C from points sorted by x.
+fn exponential_guess(points: &[(f64, f64)], range: f64) -> [f64; 3] {
+    let (x0, y0) = points[0];
+    let c = points[points.len() - 1].1;
+    let start = y0 - c;
+    let x_e = points.iter().find(|p| (p.1 - c).abs() <= start.abs() / std::f64::consts::E).map_or(x0, |p| p.0);
+    let k = if x_e > x0 { 1.0 / (x_e - x0) } else { 1.0 / range };
+    [start * (k * x0).exp(), k, c]
+}
+
+/// (A, ω, φ, C) for A sin(ω x + φ) + C from the best-fitting frequency on a grid.
+fn sinusoid_guess(points: &[(f64, f64)], range: f64) -> [f64; 4] {
+    let n = points.len();
+    let mean = points.iter().map(|p| p.1).sum::<f64>() / n as f64;
+    let mut best = (f64::NEG_INFINITY, 2.0 * PI / range, 0.0, 0.0);
+    // Frequencies up to the Nyquist limit of an equally spaced sample, oversampled four times.
+    for j in 2..=2 * n {
+        let omega = 2.0 * PI * (j as f64 / 4.0) / range;
+        let (mut ss, mut sc, mut cc, mut ys, mut yc) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for &(x, y) in points {
+            let (s, c) = (omega * x).sin_cos();
+            ss += s * s;
+            sc += s * c;
+            cc += c * c;
+            ys += (y - mean) * s;
+            yc += (y - mean) * c;
+        }
+        // Least-squares coefficients of sin and cos; explained sum of squares.
+        let det = ss * cc - sc * sc;
+        if det.abs() < 1e-12 * ss * cc {
+            continue;
+        }
+        let a = (cc * ys - sc * yc) / det;
+        let b = (ss * yc - sc * ys) / det;
+        let explained = a * ys + b * yc;
+        if explained > best.0 {
+            best = (explained, omega, a, b);
+        }
+    }
+    let (_, omega, a, b) = best;
+    // a sin + b cos = A sin(ωx + φ) with A cos φ = a, A sin φ = b.
+    [a.hypot(b), omega, b.atan2(a), mean]
+}
+
+/// x of the point whose y is closest to `target`.
+fn closest_x(points: &[(f64, f64)], target: f64) -> f64 {
+    points.iter().min_by(|a, b| (a.1 - target).abs().total_cmp(&(b.1 - target).abs())).unwrap().0
+}
+
+/// Ordinary least-squares line (intercept, slope), or `None` if degenerate.
+fn line_fit(points: impl Iterator<Item = (f64, f64)>) -> Option<(f64, f64)> {
+    let points: Vec<(f64, f64)> = points.collect();
+    let n = points.len() as f64;
+    let x_mean = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let y_mean = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|p| (p.0 - x_mean).powi(2)).sum();
+    let sxy: f64 = points.iter().map(|p| (p.0 - x_mean) * (p.1 - y_mean)).sum();
+    if points.len() < 2 || sxx <= 0.0 {
+        return None;
+    }
+    let slope = sxy / sxx;
+    Some((y_mean - slope * x_mean, slope))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::week06_nonlinear_least_squares::jacobian::check_jacobian;
+
+    /// True parameters and sample x values for each model.
+    fn example(model: CurveModel) -> (Vec<f64>, Vec<f64>) {
+        let grid = |a: f64, b: f64, n: usize| (0..n).map(|i| a + (b - a) * i as f64 / (n - 1) as f64).collect();
+        match model {
+            CurveModel::Exponential => (vec![3.0, 0.4, 1.0], grid(0.0, 10.0, 30)),
+            CurveModel::DoubleExponential => (vec![3.0, 1.5, 2.0, 0.1, 0.5], grid(0.0, 20.0, 60)),
+            CurveModel::PowerLaw => (vec![2.0, 1.5], grid(1.0, 10.0, 20)),
+            CurveModel::Logistic => (vec![10.0, 0.8, 8.0], grid(0.0, 20.0, 40)),
+            CurveModel::Gaussian => (vec![4.0, 0.5, 1.2, 0.3], grid(-5.0, 5.0, 41)),
+            CurveModel::Lorentzian => (vec![4.0, 0.5, 0.8, 0.3], grid(-5.0, 5.0, 41)),
+            CurveModel::Sinusoid => (vec![2.0, 3.0, 0.5, 1.0], grid(0.0, 10.0, 60)),
+            CurveModel::MichaelisMenten => (vec![200.0, 0.1], grid(0.02, 2.0, 15)),
+            CurveModel::Arrhenius => (vec![1e7, 5e4], grid(300.0, 400.0, 12)),
+        }
+    }
+
+    #[test]
+    fn test_names_round_trip() {
+        for model in CurveModel::ALL {
+            assert_eq!(CurveModel::from_name(model.name()), Some(model));
+            assert_eq!(model.n_params(), model.gradient(1.0, &vec![1.0; model.n_params()]).len());
+        }
+        assert_eq!(CurveModel::from_name("Michaelis-Menten"), Some(CurveModel::MichaelisMenten));
+        assert_eq!(CurveModel::from_name("cubic"), None);
+    }
+
+    #[test]
+    fn test_analytic_jacobians() {
+        for model in CurveModel::ALL {
+            let (p, x) = example(model);
+            let y: Vec<f64> = x.iter().map(|&x| model.evaluate(x, &p)).collect();
+            let p: Vec<f64> = p.iter().map(|v| 1.1 * v).collect();
+            let check = check_jacobian(&model.curve_fit(&x, &y), &p).unwrap();
+            assert!(check.passed(1e-6), "{}: {:e}", model.name(), check.max_relative_error);
+        }
+    }
+
+    #[test]
+    fn test_fit_from_initial_guess_recovers_parameters() {
+        for model in CurveModel::ALL {
+            let (p, x) = example(model);
+            let y: Vec<f64> = x.iter().map(|&x| model.evaluate(x, &p)).collect();
+            let result = model.fit(&x, &y, &LevenbergMarquardtConfig::default());
+            assert!(result.converged(), "{}: {:?}", model.name(), result.stop_reason);
+            for (fitted, expected) in result.params.iter().zip(&p) {
+                assert!((fitted - expected).abs() < 1e-6 * expected.abs().max(1.0), "{}: {:?}", model.name(), result.params);
+            }
+        }
+    }
+
+    #[test]
+    fn test_peak_guess_handles_dips() {
+        let x: Vec<f64> = (0..41).map(|i| -5.0 + 0.25 * i as f64).collect();
+        let y: Vec<f64> = x.iter().map(|&x| CurveModel::Gaussian.evaluate(x, &[-3.0, 1.0, 0.7, 2.0])).collect();
+        let guess = CurveModel::Gaussian.initial_guess(&x, &y);
+        assert!(guess[0] < 0.0);
+        assert_eq!(guess[1], 1.0);
+        assert!((guess[2] - 0.7).abs() < 0.2);
+    }
+}
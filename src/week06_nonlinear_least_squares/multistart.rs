@@ -0,0 +1,277 @@
+//! Multi-start nonlinear least squares.
+//!
+//! Gauss-Newton and Levenberg-Marquardt find the minimum in whose basin they
+//! start. Running the solver from many starting points spread over a box of
+//! plausible parameters, and grouping the converged solutions that coincide,
+//! gives the best fit found together with an estimate of how large each
+//! basin of attraction is: the fraction of starts that ended in it.
+//!
+//! Starts are drawn uniformly at random, or by Latin hypercube sampling,
+//! which splits every parameter range into n equal strata and uses each
+//! stratum exactly once, so the starts cover each axis evenly.
+
+use super::levenberg_marquardt::levenberg_marquardt;
+use super::types::{LevenbergMarquardtConfig, NlsResult, ResidualModel};
+use crate::common::rng::Rng;
+
+/// How starting points are drawn from the parameter box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SamplingMethod {
+    /// Independent uniform samples.
+    Uniform,
+    /// Latin hypercube: one sample per stratum along every axis.
+    LatinHypercube,
+}
+
+/// Configuration for [`multi_start`].
+#[derive(Debug, Clone)]
+pub struct MultiStartConfig {
+    /// Lower corner of the box the starts are drawn from.
+    pub lower: Vec<f64>,
+    /// Upper corner of the box the starts are drawn from.
+    pub upper: Vec<f64>,
+    /// Number of starting points.
+    pub n_starts: usize,
+    pub sampling: SamplingMethod,
+    /// Seed for the starting points.
+    pub seed: u64,
+    /// Two solutions are the same when every parameter differs by at most
+    /// this fraction of its range upper - lower.
+    pub cluster_tol: f64,
+    /// Solver settings for each run.
+    pub solver: LevenbergMarquardtConfig,
+}
+
+impl MultiStartConfig {
+    /// Draw starts from the box lower <= p <= upper, with 50 Latin hypercube
+    /// starts by default.
+    pub fn new(lower: &[f64], upper: &[f64]) -> Self {
+        assert_eq!(lower.len(), upper.len(), "bounds must have equal length");
+        assert!(!lower.is_empty(), "need at least one parameter");
+        assert!(lower.iter().zip(upper).all(|(l, u)| l < u), "lower bounds must be below upper bounds");
+        Self {
+            lower: lower.to_vec(),
+            upper: upper.to_vec(),
+            n_starts: 50,
+            sampling: SamplingMethod::LatinHypercube,
+            seed: 551,
+            cluster_tol: 1e-4,
+            solver: LevenbergMarquardtConfig::default(),
+        }
+    }
+
+    /// Set the number of starting points.
+    pub fn with_n_starts(mut self, n_starts: usize) -> Self {
+        assert!(n_starts > 0, "need at least one start");
+        self.n_starts = n_starts;
+        self
+    }
+
+    /// Set the sampling method.
+    pub fn with_sampling(mut self, sampling: SamplingMethod) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Set the random seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Set the relative distance below which solutions are merged.
+    pub fn with_cluster_tol(mut self, tol: f64) -> Self {
+        self.cluster_tol = tol;
+        self
+    }
+
+    /// Set the solver configuration used from each start.
+    pub fn with_solver(mut self, solver: LevenbergMarquardtConfig) -> Self {
+        self.solver = solver;
+        self
+    }
+}
+
+/// A group of runs that converged to the same solution.
+#[derive(Debug, Clone)]
+pub struct SolutionCluster {
+    /// Parameters of the lowest-cost run in the cluster.
+    pub params: Vec<f64>,
+    /// Cost ½||r||² of the lowest-cost run.
+    pub cost: f64,
+    /// Indices of the starts that ended here.
+    pub starts: Vec<usize>,
+}
+
+impl SolutionCluster {
+    /// Number of starts that ended in this cluster.
+    pub fn count(&self) -> usize {
+        self.starts.len()
+    }
+}
+
+/// Result of [`multi_start`].
+#[derive(Debug, Clone)]
+pub struct MultiStartResult {
+    /// Starting points, in the order they were run.
+    pub starts: Vec<Vec<f64>>,
+    /// Solver result from each start.
+    pub results: Vec<NlsResult>,
+    /// Distinct converged solutions, lowest cost first.
+    pub clusters: Vec<SolutionCluster>,
+    /// Indices of the starts whose runs did not converge.
+    pub failed: Vec<usize>,
+}
+
+impl MultiStartResult {
+    /// The lowest-cost converged run, or the lowest-cost run if none converged.
+    pub fn best(&self) -> &NlsResult {
+        let index = match self.clusters.first() {
+            Some(cluster) => cluster.starts[0],
+            None => (0..self.results.len())
+                .min_by(|&a, &b| self.results[a].cost.total_cmp(&self.results[b].cost))
+                .expect("no starts were run"),
+        };
+        &self.results[index]
+    }
+
+    /// Fraction of starts that reached the best solution.
+    pub fn success_rate(&self) -> f64 {
+        match self.clusters.first() {
+            Some(cluster) => cluster.count() as f64 / self.results.len() as f64,
+            None => 0.0,
+        }
+    }
+
+    /// One line per cluster with its cost, hit count and parameters, then
+    /// the number of runs that failed to converge.
+    pub fn report(&self) -> Vec<String> {
+        let n = self.results.len();
+        let mut lines: Vec<String> = self
+            .clusters
+            .iter()
+            .enumerate()
+            .map(|(k, cluster)| {
+                let params: Vec<String> = cluster.params.iter().map(|p| format!("{:.6}", p)).collect();
+                format!(
+                    "basin {}: cost {:.6e}, hit {}/{} ({:.0}%), p = [{}]",
+                    k + 1,
+                    cluster.cost,
+                    cluster.count(),
+                    n,
+                    100.0 * cluster.count() as f64 / n as f64,
+                    params.join(", ")
+                )
+            })
+            .collect();
+        lines.push(format!("not converged: {}/{}", self.failed.len(), n));
+        lines
+    }
+}
+
+/// Draw `n` points from the box lower <= p < upper.
+pub fn sample_starts(lower: &[f64], upper: &[f64], n: usize, sampling: SamplingMethod, rng: &mut Rng) -> Vec<Vec<f64>> {
+    match sampling {
+        SamplingMethod::Uniform => (0..n)
+            .map(|_| lower.iter().zip(upper).map(|(&l, &u)| rng.uniform_range(l, u)).collect())
+            .collect(),
+        SamplingMethod::LatinHypercube => {
+            let mut points = vec![Vec::with_capacity(lower.len()); n];
+            for (&l, &u) in lower.iter().zip(upper) {
+                let mut strata: Vec<usize> = (0..n).collect();
+                rng.shuffle(&mut strata);
+                for (point, stratum) in points.iter_mut().zip(strata) {
+                    let t = (stratum as f64 + rng.uniform()) / n as f64;
+                    point.push(l + (u - l) * t);
+                }
+            }
+            points
+        }
+    }
+}
+
+/// Run Levenberg-Marquardt from `config.n_starts` sampled starting points
+/// and cluster the converged solutions.
+pub fn multi_start(model: &dyn ResidualModel, config: &MultiStartConfig) -> MultiStartResult {
+    assert_eq!(config.lower.len(), model.n_params(), "bounds must have one entry per parameter");
+    assert!(config.n_starts > 0, "need at least one start");
+    let mut rng = Rng::new(config.seed);
+    let starts = sample_starts(&config.lower, &config.upper, config.n_starts, config.sampling, &mut rng);
+    let results: Vec<NlsResult> = starts.iter().map(|p0| levenberg_marquardt(model, p0, &config.solver)).collect();
+
+    let (mut converged, failed): (Vec<usize>, Vec<usize>) =
+        (0..results.len()).partition(|&i| results[i].converged() && results[i].cost.is_finite());
+    converged.sort_by(|&a, &b| results[a].cost.total_cmp(&results[b].cost));
+
+    // Leader clustering in order of cost: each run joins the first cluster
+    // whose best solution is within tolerance, or starts a new one.
+    let mut clusters: Vec<SolutionCluster> = Vec::new();
+    for i in converged {
+        let p = &results[i].params;
+        let same = |q: &[f64]| {
+            p.iter()
+                .zip(q)
+                .zip(config.lower.iter().zip(&config.upper))
+                .all(|((a, b), (l, u))| (a - b).abs() <= config.cluster_tol * (u - l))
+        };
+        match clusters.iter_mut().find(|c| same(&c.params)) {
+            Some(cluster) => cluster.starts.push(i),
+            None => clusters.push(SolutionCluster { params: p.clone(), cost: results[i].cost, starts: vec![i] }),
+        }
+    }
+
+    MultiStartResult { starts, results, clusters, failed }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::week06_nonlinear_least_squares::types::CurveFit;
+
+    #[test]
+    fn test_latin_hypercube_strata() {
+        let mut rng = Rng::new(1);
+        let n = 20;
+        let points = sample_starts(&[0.0, -5.0], &[1.0, 5.0], n, SamplingMethod::LatinHypercube, &mut rng);
+        assert_eq!(points.len(), n);
+        for (axis, (l, u)) in [(0.0, 1.0), (-5.0, 5.0)].into_iter().enumerate() {
+            let mut strata: Vec<usize> =
+                points.iter().map(|p| ((p[axis] - l) / (u - l) * n as f64).floor() as usize).collect();
+            strata.sort();
+            assert_eq!(strata, (0..n).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_frequency_fit_basins() {
+        // y = cos(ω t) has a local minimum of the cost near every ω.
+        let t: Vec<f64> = (0..80).map(|i| 0.1 * i as f64).collect();
+        let y: Vec<f64> = t.iter().map(|&t| 2.0 * (1.7 * t).cos()).collect();
+        let model = CurveFit::new(&t, &y, 2, |t, p| p[0] * (p[1] * t).cos())
+            .with_gradient(|t, p| vec![(p[1] * t).cos(), -p[0] * t * (p[1] * t).sin()]);
+        let config = MultiStartConfig::new(&[0.5, 0.1], &[3.0, 6.0]).with_n_starts(40);
+        let result = multi_start(&model, &config);
+
+        assert!(result.clusters.len() > 1);
+        assert!((result.best().params[0] - 2.0).abs() < 1e-8);
+        assert!((result.best().params[1] - 1.7).abs() < 1e-8);
+        assert!(result.best().cost < 1e-16);
+        let hits: usize = result.clusters.iter().map(SolutionCluster::count).sum();
+        assert_eq!(hits + result.failed.len(), 40);
+        assert!(result.clusters.windows(2).all(|w| w[0].cost <= w[1].cost));
+    }
+
+    #[test]
+    fn test_convex_problem_has_one_basin() {
+        let x: Vec<f64> = (0..10).map(|i| i as f64).collect();
+        let y: Vec<f64> = x.iter().map(|&x| 1.0 + 0.5 * x + 0.1 * (x * x).sin()).collect();
+        let model = CurveFit::new(&x, &y, 2, |x, p| p[0] + p[1] * x);
+        let config = MultiStartConfig::new(&[-10.0, -10.0], &[10.0, 10.0])
+            .with_n_starts(15)
+            .with_sampling(SamplingMethod::Uniform);
+        let result = multi_start(&model, &config);
+        assert_eq!(result.clusters.len(), 1);
+        assert_eq!(result.clusters[0].count(), 15);
+        assert!((result.success_rate() - 1.0).abs() < 1e-12);
+    }
+}
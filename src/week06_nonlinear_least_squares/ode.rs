@@ -0,0 +1,367 @@
+//! Runge-Kutta integration and parameter estimation for ODE models.
+//!
+//! A dynamic model dy/dt = g(t, y; p) with y(t₀) = y₀(p) has no closed form
+//! in general, so each residual evaluation integrates it numerically to the
+//! observation times. [`OdeFit`] wraps that as a [`ResidualModel`]; its
+//! Jacobian comes from finite differences of whole trajectories, so the
+//! integration error must be well below the finite-difference step. A fixed
+//! RK4 step gives residuals that are smooth in p; the adaptive Dormand-Prince
+//! method picks its own steps, which pays off when the solution changes on
+//! very different time scales, but should then be run with a tight tolerance.
+//! Both are explicit methods and are not suited to stiff systems.
+
+use ndarray::Array1;
+
+use super::types::ResidualModel;
+
+/// Right-hand side g(t, y; p) of dy/dt = g(t, y; p).
+pub type OdeRhsFn<'a> = Box<dyn Fn(f64, &[f64], &[f64]) -> Vec<f64> + 'a>;
+
+/// Initial state y₀(p); parameters may include unknown initial conditions.
+pub type InitialStateFn<'a> = Box<dyn Fn(&[f64]) -> Vec<f64> + 'a>;
+
+/// Runge-Kutta method for [`solve_ode`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OdeMethod {
+    /// Classical fourth-order Runge-Kutta. Each interval between output
+    /// times is split into equal steps no longer than `step`.
+    Rk4 { step: f64 },
+    /// Dormand-Prince 5(4) with adaptive steps, keeping the local error
+    /// of each component below `atol + rtol |y|`.
+    Rk45 { rtol: f64, atol: f64 },
+}
+
+/// States at the requested output times.
+#[derive(Debug, Clone)]
+pub struct OdeSolution {
+    pub t: Vec<f64>,
+    /// y[i] is the state at t[i]; NaN from the point where integration failed.
+    pub y: Vec<Vec<f64>>,
+    /// Accepted steps.
+    pub steps: usize,
+    /// Rejected adaptive steps.
+    pub rejected: usize,
+    /// Right-hand side evaluations.
+    pub evaluations: usize,
+}
+
+impl OdeSolution {
+    /// Component `k` of the state at every output time.
+    pub fn component(&self, k: usize) -> Vec<f64> {
+        self.y.iter().map(|y| y[k]).collect()
+    }
+
+    /// Whether every output state is finite.
+    pub fn succeeded(&self) -> bool {
+        self.y.iter().flatten().all(|v| v.is_finite())
+    }
+}
+
+/// Maximum number of steps (accepted or rejected) before giving up.
+const MAX_STEPS: usize = 100_000;
+
+/// Integrates dy/dt = f(t, y) from y(t0) = y0, returning y at each of `times`.
+///
+/// `times` must be non-decreasing and not before `t0`. If the solution blows
+/// up, the step size underflows or [`MAX_STEPS`] is exceeded, the remaining
+/// states are NaN, which the least-squares solvers treat as a failed step.
+pub fn solve_ode(
+    f: impl Fn(f64, &[f64]) -> Vec<f64>,
+    t0: f64,
+    y0: &[f64],
+    times: &[f64],
+    method: OdeMethod,
+) -> OdeSolution {
+    assert!(times.windows(2).all(|w| w[0] <= w[1]), "output times must be non-decreasing");
+    assert!(times.iter().all(|&t| t >= t0), "output times must not precede t0");
+    let mut solution = OdeSolution {
+        t: times.to_vec(),
+        y: Vec::with_capacity(times.len()),
+        steps: 0,
+        rejected: 0,
+        evaluations: 0,
+    };
+    let mut eval = |t: f64, y: &[f64]| {
+        solution.evaluations += 1;
+        f(t, y)
+    };
+
+    let mut t = t0;
+    let mut y = y0.to_vec();
+    let mut outputs = Vec::with_capacity(times.len());
+    let (mut steps, mut rejected) = (0, 0);
+    match method {
+        OdeMethod::Rk4 { step } => {
+            assert!(step > 0.0, "step must be positive");
+            for &t_out in times {
+                let n = ((t_out - t) / step).ceil() as usize;
+                let h = if n > 0 { (t_out - t) / n as f64 } else { 0.0 };
+                for _ in 0..n {
+                    y = rk4_step(&mut eval, t, &y, h);
+                    t += h;
+                    steps += 1;
+                }
+                t = t_out;
+                if steps > MAX_STEPS || y.iter().any(|v| !v.is_finite()) {
+                    break;
+                }
+                outputs.push(y.clone());
+            }
+        }
+        OdeMethod::Rk45 { rtol, atol } => {
+            assert!(rtol > 0.0 || atol > 0.0, "need a positive tolerance");
+            let span = times.last().map_or(0.0, |&t_end| t_end - t0);
+            let mut h = if span > 0.0 { 0.01 * span } else { 1.0 };
+            let mut k1 = eval(t, &y);
+            'outer: for &t_out in times {
+                while t < t_out {
+                    if steps + rejected > MAX_STEPS || h <= 1e-14 * t.abs().max(1.0) {
+                        break 'outer;
+                    }
+                    // Shorten the step to land on the output time without forgetting the proposal.
+                    let landing = h >= t_out - t;
+                    let h_step = if landing { t_out - t } else { h };
+                    let (y_new, k7, error) = dormand_prince_step(&mut eval, t, &y, &k1, h_step);
+                    let scale = |i: usize| atol + rtol * y[i].abs().max(y_new[i].abs());
+                    let sum_squares: f64 = (0..y.len()).map(|i| (error[i] / scale(i)).powi(2)).sum();
+                    let norm = (sum_squares / y.len().max(1) as f64).sqrt();
+
+                    if norm <= 1.0 && y_new.iter().all(|v| v.is_finite()) {
+                        t = if landing { t_out } else { t + h_step };
+                        y = y_new;
+                        k1 = k7;
+                        steps += 1;
+                    } else {
+                        rejected += 1;
+                    }
+                    let factor = if norm.is_finite() { (0.9 * norm.powf(-0.2)).clamp(0.2, 5.0) } else { 0.2 };
+                    h = if landing && norm <= 1.0 { h.max(h_step * factor) } else { h_step * factor };
+                }
+                outputs.push(y.clone());
+            }
+        }
+    }
+
+    // States after a failure are unknown.
+    outputs.resize(times.len(), vec![f64::NAN; y0.len()]);
+    solution.y = outputs;
+    solution.steps = steps;
+    solution.rejected = rejected;
+    solution
+}
+
+fn rk4_step(f: &mut impl FnMut(f64, &[f64]) -> Vec<f64>, t: f64, y: &[f64], h: f64) -> Vec<f64> {
+    let shifted = |k: &[f64], a: f64| -> Vec<f64> { y.iter().zip(k).map(|(y, k)| y + a * h * k).collect() };
+    let k1 = f(t, y);
+    let k2 = f(t + 0.5 * h, &shifted(&k1, 0.5));
+    let k3 = f(t + 0.5 * h, &shifted(&k2, 0.5));
+    let k4 = f(t + h, &shifted(&k3, 1.0));
+    (0..y.len()).map(|i| y[i] + h / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i])).collect()
+}
+
+/// One Dormand-Prince step: (fifth-order y, f at the new point, y₅ - y₄).
+fn dormand_prince_step(
+    f: &mut impl FnMut(f64, &[f64]) -> Vec<f64>,
+    t: f64,
+    y: &[f64],
+    k1: &[f64],
+    h: f64,
+) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    const C: [f64; 6] = [1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0];
+    const A: [&[f64]; 6] = [
+        &[1.0 / 5.0],
+        &[3.0 / 40.0, 9.0 / 40.0],
+        &[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0],
+        &[19372.0 / 6561.0, -25360.0 / 2187.0, 64448.0 / 6561.0, -212.0 / 729.0],
+        &[9017.0 / 3168.0, -355.0 / 33.0, 46732.0 / 5247.0, 49.0 / 176.0, -5103.0 / 18656.0],
+        &[35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0],
+    ];
+    // Fifth-order weights minus the embedded fourth-order ones.
+    const E: [f64; 7] =
+        [71.0 / 57600.0, 0.0, -71.0 / 16695.0, 71.0 / 1920.0, -17253.0 / 339200.0, 22.0 / 525.0, -1.0 / 40.0];
+
+    let mut k = vec![k1.to_vec()];
+    for (c, a) in C.iter().zip(A) {
+        let stage: Vec<f64> =
+            (0..y.len()).map(|i| y[i] + h * a.iter().zip(&k).map(|(a, k)| a * k[i]).sum::<f64>()).collect();
+        k.push(f(t + c * h, &stage));
+    }
+    // The last stage is evaluated at the fifth-order solution (first same as last).
+    let y_new: Vec<f64> = (0..y.len()).map(|i| y[i] + h * A[5].iter().zip(&k).map(|(a, k)| a * k[i]).sum::<f64>()).collect();
+    let error = (0..y.len()).map(|i| h * E.iter().zip(&k).map(|(e, k)| e * k[i]).sum::<f64>()).collect();
+    (y_new, k.pop().unwrap(), error)
+}
+
+/// Fit of an ODE model to observations of one state component.
+///
+/// Residuals are y_k(t_i; p) - y_i, where y(t; p) solves dy/dt = g(t, y; p)
+/// from y(t₀) = y₀(p). The Jacobian is by finite differences.
+pub struct OdeFit<'a> {
+    pub t: Vec<f64>,
+    pub y: Vec<f64>,
+    /// Number of parameters p.
+    pub n_params: usize,
+    /// Index of the observed state component.
+    pub component: usize,
+    /// Start of integration; defaults to the first observation time.
+    pub t0: f64,
+    pub method: OdeMethod,
+    rhs: OdeRhsFn<'a>,
+    initial: InitialStateFn<'a>,
+    names: Option<Vec<String>>,
+}
+
+impl<'a> OdeFit<'a> {
+    /// Fit dy/dt = `rhs`(t, y, p) with y(t₀) = `initial`(p) to observations y(t).
+    ///
+    /// Observation times must be sorted. Integrates with Dormand-Prince at
+    /// rtol = 1e-10 unless [`OdeFit::with_method`] says otherwise.
+    pub fn new(
+        t: &[f64],
+        y: &[f64],
+        n_params: usize,
+        rhs: impl Fn(f64, &[f64], &[f64]) -> Vec<f64> + 'a,
+        initial: impl Fn(&[f64]) -> Vec<f64> + 'a,
+    ) -> Self {
+        assert_eq!(t.len(), y.len(), "t and y must have the same length");
+        assert!(!t.is_empty(), "need at least one observation");
+        assert!(t.windows(2).all(|w| w[0] <= w[1]), "observation times must be sorted");
+        Self {
+            t: t.to_vec(),
+            y: y.to_vec(),
+            n_params,
+            component: 0,
+            t0: t[0],
+            method: OdeMethod::Rk45 { rtol: 1e-10, atol: 1e-12 },
+            rhs: Box::new(rhs),
+            initial: Box::new(initial),
+            names: None,
+        }
+    }
+
+    /// Choose the integrator.
+    pub fn with_method(mut self, method: OdeMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Observe state component `k` instead of the first.
+    ///
+    /// The state dimension is taken from the initial state at p = 0.
+    pub fn with_component(mut self, k: usize) -> Self {
+        let n_states = (self.initial)(&vec![0.0; self.n_params]).len();
+        assert!(k < n_states, "component {k} out of range for a state of dimension {n_states}");
+        self.component = k;
+        self
+    }
+
+    /// Start integration at `t0` rather than the first observation time.
+    pub fn with_initial_time(mut self, t0: f64) -> Self {
+        assert!(t0 <= self.t[0], "integration must start at or before the first observation");
+        self.t0 = t0;
+        self
+    }
+
+    /// Name the parameters for reports.
+    pub fn with_names(mut self, names: &[&str]) -> Self {
+        assert_eq!(names.len(), self.n_params, "one name per parameter");
+        self.names = Some(names.iter().map(|n| n.to_string()).collect());
+        self
+    }
+
+    /// Integrate the model with parameters `p` to the given times.
+    pub fn trajectory(&self, p: &[f64], times: &[f64]) -> OdeSolution {
+        solve_ode(|t, y| (self.rhs)(t, y, p), self.t0, &(self.initial)(p), times, self.method)
+    }
+}
+
+impl ResidualModel for OdeFit<'_> {
+    fn n_params(&self) -> usize {
+        self.n_params
+    }
+
+    fn n_residuals(&self) -> usize {
+        self.t.len()
+    }
+
+    fn residuals(&self, p: &[f64]) -> Array1<f64> {
+        let solution = self.trajectory(p, &self.t);
+        solution.y.iter().zip(&self.y).map(|(state, y)| state[self.component] - y).collect()
+    }
+
+    fn param_names(&self) -> Vec<String> {
+        match &self.names {
+            Some(names) => names.clone(),
+            None => (0..self.n_params).map(|k| format!("p{k}")).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::week06_nonlinear_least_squares::levenberg_marquardt::levenberg_marquardt;
+    use crate::week06_nonlinear_least_squares::types::LevenbergMarquardtConfig;
+
+    #[test]
+    fn test_rk4_is_fourth_order() {
+        let error = |h: f64| {
+            let solution = solve_ode(|_, y| vec![-y[0]], 0.0, &[1.0], &[2.0], OdeMethod::Rk4 { step: h });
+            (solution.y[0][0] - (-2.0_f64).exp()).abs()
+        };
+        let ratio = error(0.1) / error(0.05);
+        assert!((ratio - 16.0).abs() < 1.0, "{ratio}");
+    }
+
+    #[test]
+    fn test_rk45_meets_tolerance_on_oscillator() {
+        // y'' = -y from (1, 0): y = cos t at the output times.
+        let times: Vec<f64> = (1..=20).map(|i| i as f64).collect();
+        let method = OdeMethod::Rk45 { rtol: 1e-9, atol: 1e-12 };
+        let solution = solve_ode(|_, y| vec![y[1], -y[0]], 0.0, &[1.0, 0.0], &times, method);
+        assert!(solution.succeeded());
+        for (t, y) in times.iter().zip(solution.component(0)) {
+            assert!((y - t.cos()).abs() < 1e-7);
+        }
+        // First same as last: six new evaluations per step after the first.
+        assert_eq!(solution.evaluations, 6 * (solution.steps + solution.rejected) + 1);
+    }
+
+    #[test]
+    fn test_blow_up_gives_nan() {
+        // y' = y² from y(0) = 1 blows up at t = 1.
+        let method = OdeMethod::Rk45 { rtol: 1e-8, atol: 1e-10 };
+        let solution = solve_ode(|_, y| vec![y[0] * y[0]], 0.0, &[1.0], &[0.5, 2.0], method);
+        assert!((solution.y[0][0] - 2.0).abs() < 1e-6);
+        assert!(solution.y[1][0].is_nan());
+        assert!(!solution.succeeded());
+    }
+
+    #[test]
+    fn test_logistic_parameters_recovered() {
+        let (r, k, y0) = (0.5, 600.0, 10.0);
+        let t: Vec<f64> = (0..19).map(|i| i as f64).collect();
+        let y: Vec<f64> = t.iter().map(|&t| k / (1.0 + (k / y0 - 1.0) * (-r * t).exp())).collect();
+        for method in [OdeMethod::Rk4 { step: 0.05 }, OdeMethod::Rk45 { rtol: 1e-10, atol: 1e-12 }] {
+            let model = OdeFit::new(&t, &y, 3, |_, y, p| vec![p[0] * y[0] * (1.0 - y[0] / p[1])], |p| vec![p[2]])
+                .with_method(method);
+            let result = levenberg_marquardt(&model, &[1.0, 400.0, 20.0], &LevenbergMarquardtConfig::default());
+            assert!(result.converged(), "{method:?}: {:?}", result.stop_reason);
+            for (p, expected) in result.params.iter().zip([r, k, y0]) {
+                assert!((p - expected).abs() < 1e-4 * expected, "{method:?}: {:?}", result.params);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_component_out_of_range_rejected() {
+        OdeFit::new(&[0.0, 1.0], &[1.0, 0.5], 1, |_, y, p| vec![-p[0] * y[0]], |_| vec![1.0]).with_component(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "first observation")]
+    fn test_late_initial_time_rejected() {
+        OdeFit::new(&[0.0, 1.0], &[1.0, 0.5], 1, |_, y, p| vec![-p[0] * y[0]], |_| vec![1.0]).with_initial_time(0.5);
+    }
+}
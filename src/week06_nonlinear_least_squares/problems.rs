@@ -0,0 +1,596 @@
+//! EG551T Practical Problems - Nonlinear Least Squares
+//!
+//! Fit an exponential decay y = A exp(-k t) + C to noisy measurements.
+//!
+//! Fit the Michaelis-Menten rate law v = V_max S / (K_m + S) to the
+//! Puromycin enzyme kinetics data (treated cells, Bates and Watts 1988).
+//!
+//! Compare Gauss-Newton with Levenberg-Marquardt from poor starting
+//! guesses, and fit with a bound on K_m.
+//!
+//! Check an analytic Jacobian against finite differences, catch a
+//! deliberate sign error, and fit without any analytic Jacobian.
+//!
+//! Report standard errors, correlations and 95% confidence intervals for
+//! the fitted constants, comparing linearised (Wald) intervals with
+//! profile-likelihood intervals.
+//!
+//! Fit every model in the curve library to synthetic noisy data, starting
+//! from the automatic initial guess.
+//!
+//! Fit a sum of three exponential decays by variable projection and by
+//! Levenberg-Marquardt on all six parameters from many random starts.
+//!
+//! Estimate the growth rate, carrying capacity and initial population of
+//! Carlson's yeast culture by fitting the logistic ODE dy/dt = r y (1 - y/K),
+//! integrated numerically, and compare with the closed-form logistic curve.
+//!
+//! Fit an oscillation, whose cost has a local minimum near every frequency,
+//! from 60 random and 60 Latin hypercube starts, and count how often each
+//! distinct solution is reached.
+//!
+//! Fit four first-order decay runs with a common rate constant globally,
+//! compare the precision of the shared rate with separate fits, and test
+//! whether a common rate is consistent with the data.
+
+use std::cell::Cell;
+
+use crate::week06_nonlinear_least_squares::{
+    check_jacobian, gauss_newton, global_fit, levenberg_marquardt, multi_start, parameter_uncertainty, profile_interval,
+    variable_projection, CurveFit, CurveModel, FiniteDifference, GaussNewtonConfig, GlobalFit, GlobalFitResult,
+    LevenbergMarquardtConfig, MultiStartConfig, MultiStartResult, NlsResult, OdeFit, OdeMethod, ProfileInterval,
+    ResidualModel, SamplingMethod, SeparableFit, VarProResult,
+};
+use crate::common::rng::Rng;
+
+#[cfg(feature = "plotting")]
+use crate::common::plot::{self, PlotConfig};
+
+/// Noisy samples of y = 4 exp(-0.3t) + 1 on [0, 12].
+pub const DECAY_T: [f64; 25] = [
+    0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.5, 4.0, 4.5, 5.0, 5.5, 6.0, 6.5, 7.0, 7.5, 8.0, 8.5, 9.0, 9.5, 10.0, 10.5,
+    11.0, 11.5, 12.0,
+];
+pub const DECAY_Y: [f64; 25] = [
+    4.973, 4.467, 4.019, 3.646, 3.128, 2.853, 2.580, 2.384, 2.197, 1.972, 1.907, 1.696, 1.690, 1.524, 1.506, 1.445,
+    1.358, 1.282, 1.230, 1.174, 1.281, 1.203, 1.246, 1.148, 1.128,
+];
+
+/// Puromycin substrate concentration (ppm) and initial reaction velocity (counts/min²).
+pub const PUROMYCIN_CONC: [f64; 12] = [0.02, 0.02, 0.06, 0.06, 0.11, 0.11, 0.22, 0.22, 0.56, 0.56, 1.10, 1.10];
+pub const PUROMYCIN_RATE: [f64; 12] =
+    [76.0, 47.0, 97.0, 107.0, 123.0, 139.0, 159.0, 152.0, 191.0, 201.0, 207.0, 200.0];
+
+/// Hours since inoculation for [`YEAST_BIOMASS`].
+pub const YEAST_HOURS: [f64; 19] =
+    [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0, 17.0, 18.0];
+/// Amount of yeast in a culture (Carlson 1913, as used by Pearl).
+pub const YEAST_BIOMASS: [f64; 19] = [
+    9.6, 18.3, 29.0, 47.2, 71.1, 119.1, 174.6, 257.3, 350.7, 441.0, 513.3, 559.7, 594.8, 629.4, 640.8, 651.1, 655.9,
+    659.6, 661.8,
+];
+
+/// Logistic growth dy/dt = r y (1 - y/K) with parameters (r, K, y0).
+pub fn logistic_ode_model(method: OdeMethod) -> OdeFit<'static> {
+    OdeFit::new(&YEAST_HOURS, &YEAST_BIOMASS, 3, |_, y, p| vec![p[0] * y[0] * (1.0 - y[0] / p[1])], |p| vec![p[2]])
+        .with_method(method)
+        .with_names(&["r", "K", "y0"])
+}
+
+/// y = A exp(-k t) + C with parameters (A, k, C).
+pub fn exponential_decay_model() -> CurveFit<'static> {
+    CurveModel::Exponential.curve_fit(&DECAY_T, &DECAY_Y)
+}
+
+/// v = V_max S / (K_m + S) with parameters (V_max, K_m).
+pub fn michaelis_menten_model() -> CurveFit<'static> {
+    CurveModel::MichaelisMenten.curve_fit(&PUROMYCIN_CONC, &PUROMYCIN_RATE)
+}
+
+fn print_history(result: &NlsResult, names: &[String]) {
+    let params: String = names.iter().map(|n| format!(" {:>10}", n)).collect();
+    println!(
+        "  {:>4}{} {:>12} {:>10} {:>10} {:>10} {:>6}",
+        "iter", params, "½||r||²", "|Jᵀr|∞", "rel. step", "rel. Δcost", "α"
+    );
+    for it in &result.history {
+        let params: String = it.params.iter().map(|p| format!(" {:>10.5}", p)).collect();
+        println!(
+            "  {:>4}{} {:>12.6e} {:>10.2e} {:>10.2e} {:>10.2e} {:>6.3}",
+            it.iteration, params, it.cost, it.gradient_norm, it.relative_step, it.relative_cost_change, it.step_length
+        );
+    }
+    println!(
+        "  Stopped: {:?} after {} iterations, RSS = {:.6}",
+        result.stop_reason,
+        result.iterations,
+        result.rss()
+    );
+}
+
+/// Fit y = A exp(-k t) + C by Gauss-Newton from a deliberately poor start.
+pub fn problem_exponential_decay() -> NlsResult {
+    let p0 = [1.0, 1.0, 0.0];
+    println!("  y = A exp(-k t) + C, {} points, start (A, k, C) = {:?}", DECAY_T.len(), p0);
+    let model = exponential_decay_model();
+    let result = gauss_newton(&model, &p0, &GaussNewtonConfig::default());
+    print_history(&result, &model.param_names());
+    result
+}
+
+/// Fit the Michaelis-Menten law to the Puromycin data by Gauss-Newton.
+pub fn problem_michaelis_menten() -> NlsResult {
+    let p0 = [100.0, 1.0];
+    println!("  v = V_max S / (K_m + S), Puromycin data, start (V_max, K_m) = {:?}", p0);
+    let model = michaelis_menten_model();
+    let result = gauss_newton(&model, &p0, &GaussNewtonConfig::default());
+    print_history(&result, &model.param_names());
+    result
+}
+
+/// Compare Gauss-Newton and Levenberg-Marquardt on the Puromycin data from
+/// poor starting guesses, then fit with K_m <= 0.05.
+///
+/// Writes the iteration log of the first LM fit to `dir`/lm_history.csv and
+/// returns that fit.
+pub fn problem_levenberg_marquardt(dir: &str) -> Result<NlsResult, Box<dyn std::error::Error>> {
+    let model = michaelis_menten_model();
+    let starts = [[1.0, 1.0], [50.0, 0.5], [100.0, 0.001]];
+
+    println!("  {:>12}  {:>29}  {:>29}", "start", "Gauss-Newton (V_max, K_m)", "Levenberg-Marquardt");
+    let mut fits = Vec::new();
+    for p0 in starts {
+        let gn = gauss_newton(&model, &p0, &GaussNewtonConfig::default());
+        let lm = levenberg_marquardt(&model, &p0, &LevenbergMarquardtConfig::default());
+        let describe = |r: &NlsResult| format!("{:>10.4e}, {:>10.4e} ({:>3})", r.params[0], r.params[1], r.iterations);
+        println!("  {:>12}  {:>29}  {:>29}", format!("({}, {})", p0[0], p0[1]), describe(&gn), describe(&lm));
+        fits.push(lm);
+    }
+    println!("  (iterations in brackets; LM counts rejected steps)");
+
+    let config = LevenbergMarquardtConfig::default().with_bounds(&[0.0, 0.0], &[f64::INFINITY, 0.05]);
+    let bounded = levenberg_marquardt(&model, &starts[0], &config);
+    println!(
+        "  Bounded 0 <= K_m <= 0.05: V_max = {:.4}, K_m = {:.5}, RSS = {:.2} ({:?})",
+        bounded.params[0],
+        bounded.params[1],
+        bounded.rss(),
+        bounded.stop_reason
+    );
+
+    let path = format!("{}/lm_history.csv", dir);
+    let first = fits.swap_remove(0);
+    first.write_history_csv(&path)?;
+    println!("  LM iteration log from (1, 1): {}", path);
+    Ok(first)
+}
+
+/// Check Jacobians against central differences and fit the decay model
+/// with analytic, forward-difference and central-difference Jacobians.
+///
+/// Returns the central-difference fit.
+pub fn problem_jacobian_check() -> NlsResult {
+    let p = [200.0, 0.07];
+    let check = check_jacobian(&michaelis_menten_model(), &p).expect("model has an analytic Jacobian");
+    println!("  Michaelis-Menten Jacobian at {:?}: max relative error {:.2e}", p, check.max_relative_error);
+
+    // ∂m/∂K_m with the sign dropped, as students often write it.
+    let wrong = CurveFit::new(&PUROMYCIN_CONC, &PUROMYCIN_RATE, 2, |s, p| p[0] * s / (p[1] + s))
+        .with_gradient(|s, p| vec![s / (p[1] + s), p[0] * s / (p[1] + s).powi(2)])
+        .with_names(&["V_max", "K_m"]);
+    let check = check_jacobian(&wrong, &p).expect("model has an analytic Jacobian");
+    let names = wrong.param_names();
+    let suspects: Vec<&str> = check.suspect_parameters(1e-6).iter().map(|&k| names[k].as_str()).collect();
+    println!(
+        "  With a sign error in ∂m/∂K_m: max relative error {:.2e}, suspect parameters {:?}",
+        check.max_relative_error, suspects
+    );
+
+    let p0 = [1.0, 1.0, 0.0];
+    let config = LevenbergMarquardtConfig::default();
+    println!("  {:<18} {:>10} {:>10} {:>10} {:>6} {:>12}", "Jacobian", "A", "k", "C", "iter", "model evals");
+    let mut last = None;
+    let schemes = [
+        ("analytic", None),
+        ("forward diff.", Some(FiniteDifference::Forward)),
+        ("central diff.", Some(FiniteDifference::Central)),
+    ];
+    for (label, scheme) in schemes {
+        let evaluations = Cell::new(0usize);
+        let counted = |t: f64, p: &[f64]| {
+            evaluations.set(evaluations.get() + 1);
+            p[0] * (-p[1] * t).exp() + p[2]
+        };
+        let model = CurveFit::new(&DECAY_T, &DECAY_Y, 3, counted);
+        let model = match scheme {
+            None => model.with_gradient(|t, p| {
+                let e = (-p[1] * t).exp();
+                vec![e, -p[0] * t * e, 1.0]
+            }),
+            Some(scheme) => model.with_finite_difference(scheme),
+        };
+        let result = levenberg_marquardt(&model, &p0, &config);
+        println!(
+            "  {:<18} {:>10.6} {:>10.6} {:>10.6} {:>6} {:>12}",
+            label,
+            result.params[0],
+            result.params[1],
+            result.params[2],
+            result.iterations,
+            evaluations.get()
+        );
+        last = Some(result);
+    }
+    last.unwrap()
+}
+
+/// Confidence level used for the reported intervals.
+pub const CONFIDENCE_LEVEL: f64 = 0.95;
+
+/// Error bars for the decay and Michaelis-Menten fits: standard errors,
+/// Wald and profile-likelihood intervals, and the correlation matrix.
+///
+/// Returns the profile intervals of the Michaelis-Menten parameters.
+pub fn problem_parameter_uncertainty() -> Vec<ProfileInterval> {
+    let config = LevenbergMarquardtConfig::default();
+    let fits = [
+        ("y = A exp(-k t) + C", exponential_decay_model(), vec![1.0, 1.0, 0.0]),
+        ("v = V_max S / (K_m + S)", michaelis_menten_model(), vec![100.0, 1.0]),
+    ];
+
+    let mut profiles = Vec::new();
+    for (label, model, p0) in fits {
+        let result = levenberg_marquardt(&model, &p0, &config);
+        let u = parameter_uncertainty(&model, &result);
+        let wald = u.intervals(CONFIDENCE_LEVEL);
+        println!("  {}: s = {:.4} on {} degrees of freedom", label, u.residual_variance.sqrt(), u.dof);
+        println!(
+            "  {:<8} {:>12} {:>10} {:>24} {:>24}",
+            "param", "estimate", "std. err.", "95% Wald CI", "95% profile CI"
+        );
+        profiles.clear();
+        for (k, wald) in wald.iter().enumerate() {
+            let profile = profile_interval(&model, &result, &u, k, CONFIDENCE_LEVEL, &config);
+            println!(
+                "  {:<8} {:>12.6} {:>10.6} [{:>10.5}, {:>10.5}] [{:>10.5}, {:>10.5}]",
+                u.names[k],
+                u.estimates[k],
+                u.standard_errors[k],
+                wald.lower,
+                wald.upper,
+                profile.interval.lower,
+                profile.interval.upper
+            );
+            profiles.push(profile);
+        }
+        println!("  Correlation matrix:");
+        for row in u.correlation.rows() {
+            let row: String = row.iter().map(|c| format!(" {:>7.3}", c)).collect();
+            println!("   {}", row);
+        }
+    }
+    profiles
+}
+
+/// Plot the profile t statistic of each Michaelis-Menten parameter against
+/// its linear approximation (p_k - p̂_k) / se_k and the ±t critical values.
+#[cfg(feature = "plotting")]
+pub fn problem_profile_plot(dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let model = michaelis_menten_model();
+    let config = LevenbergMarquardtConfig::default();
+    let result = levenberg_marquardt(&model, &[100.0, 1.0], &config);
+    let u = parameter_uncertainty(&model, &result);
+    let t = u.critical_value(CONFIDENCE_LEVEL);
+
+    for k in 0..model.n_params() {
+        let profile = profile_interval(&model, &result, &u, k, CONFIDENCE_LEVEL, &config);
+        let points: Vec<(f64, f64)> = profile.trace.iter().copied().filter(|(_, tau)| tau.abs() <= 2.0 * t).collect();
+        let (x_min, x_max) = points.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), &(x, _)| (a.min(x), b.max(x)));
+        let linear = vec![
+            (x_min, (x_min - u.estimates[k]) / u.standard_errors[k]),
+            (x_max, (x_max - u.estimates[k]) / u.standard_errors[k]),
+        ];
+        let upper = vec![(x_min, t), (x_max, t)];
+        let lower = vec![(x_min, -t), (x_max, -t)];
+
+        let config = PlotConfig {
+            title: format!("Profile t statistic, {}", u.names[k]),
+            x_label: u.names[k].clone(),
+            y_label: String::from("τ"),
+            ..PlotConfig::default()
+        };
+        plot::scatter_plot(
+            &format!("{}/profile_{}.png", dir, u.names[k].to_lowercase()),
+            &[("profile τ", &points)],
+            &[("linear approximation", &linear), ("+t", &upper), ("-t", &lower)],
+            &config,
+        )?;
+    }
+    Ok(())
+}
+
+/// Sample range, true parameters and noise level for each library model.
+fn library_example(model: CurveModel) -> ((f64, f64), Vec<f64>, f64) {
+    match model {
+        CurveModel::Exponential => ((0.0, 10.0), vec![3.0, 0.4, 1.0], 0.05),
+        CurveModel::DoubleExponential => ((0.0, 20.0), vec![3.0, 1.5, 2.0, 0.1, 0.5], 0.02),
+        CurveModel::PowerLaw => ((1.0, 10.0), vec![2.0, 1.5], 0.5),
+        CurveModel::Logistic => ((0.0, 20.0), vec![10.0, 0.8, 8.0], 0.2),
+        CurveModel::Gaussian => ((-5.0, 5.0), vec![4.0, 0.5, 1.2, 0.3], 0.1),
+        CurveModel::Lorentzian => ((-5.0, 5.0), vec![4.0, 0.5, 0.8, 0.3], 0.1),
+        CurveModel::Sinusoid => ((0.0, 10.0), vec![2.0, 3.0, 0.5, 1.0], 0.2),
+        CurveModel::MichaelisMenten => ((0.02, 2.0), vec![200.0, 0.1], 5.0),
+        CurveModel::Arrhenius => ((300.0, 400.0), vec![1e7, 5e4], 0.02),
+    }
+}
+
+/// Fit each library model to 60 noisy samples of itself from the automatic
+/// initial guess, and compare the guess and the fit with the truth.
+///
+/// Returns one fit per model, in [`CurveModel::ALL`] order.
+pub fn problem_model_library() -> Vec<NlsResult> {
+    let mut rng = Rng::new(45);
+    let config = LevenbergMarquardtConfig::default();
+    let mut results = Vec::new();
+    for model in CurveModel::ALL {
+        let ((a, b), truth, noise) = library_example(model);
+        let x: Vec<f64> = (0..60).map(|i| a + (b - a) * i as f64 / 59.0).collect();
+        let y: Vec<f64> = x
+            .iter()
+            .map(|&x| {
+                let m = model.evaluate(x, &truth);
+                // Arrhenius rates span orders of magnitude; use relative noise.
+                match model {
+                    CurveModel::Arrhenius => m * (1.0 + noise * rng.normal()),
+                    _ => m + noise * rng.normal(),
+                }
+            })
+            .collect();
+
+        let guess = model.initial_guess(&x, &y);
+        let result = model.fit(&x, &y, &config);
+        println!("  {} ({}): {:?} after {} iterations", model.name(), model.formula(), result.stop_reason, result.iterations);
+        println!("    {:<8} {:>14} {:>14} {:>14}", "param", "true", "guess", "fitted");
+        for (k, name) in model.param_names().iter().enumerate() {
+            println!("    {:<8} {:>14.6} {:>14.6} {:>14.6}", name, truth[k], guess[k], result.params[k]);
+        }
+        results.push(result);
+    }
+    results
+}
+
+/// Rates and amplitudes of the three-component decay in [`problem_variable_projection`].
+pub const TRIPLE_DECAY_RATES: [f64; 3] = [5.0, 1.0, 0.2];
+pub const TRIPLE_DECAY_AMPLITUDES: [f64; 3] = [3000.0, 2000.0, 1000.0];
+
+/// Fit y = Σ c_j exp(-a_j t) to noisy counts from 40 random rate guesses,
+/// by variable projection (3 unknowns) and by Levenberg-Marquardt on all
+/// 6 parameters with the amplitudes started at 1 and at 0.
+///
+/// Returns the variable-projection fit from the true rates.
+pub fn problem_variable_projection() -> VarProResult {
+    let mut rng = Rng::new(47);
+    let t: Vec<f64> = (0..60).map(|i| 10.0 * i as f64 / 59.0).collect();
+    let y: Vec<f64> = t
+        .iter()
+        .map(|&t| {
+            let m: f64 = TRIPLE_DECAY_RATES.iter().zip(TRIPLE_DECAY_AMPLITUDES).map(|(a, c)| c * (-a * t).exp()).sum();
+            m + m.sqrt() * rng.normal()
+        })
+        .collect();
+    let model = SeparableFit::new(&t, &y, 3, 3, |t, a| a.iter().map(|a| (-a * t).exp()).collect())
+        .with_derivatives(|t, a| {
+            (0..3).map(|k| (0..3).map(|j| if j == k { -t * (-a[k] * t).exp() } else { 0.0 }).collect()).collect()
+        })
+        .with_names(&["a1", "a2", "a3"], &["c1", "c2", "c3"]);
+    let full = model.curve_fit();
+    let config = LevenbergMarquardtConfig::default();
+    let reference = variable_projection(&model, &TRIPLE_DECAY_RATES, &config);
+    let best = reference.fit.cost;
+
+    let labels = ["variable projection", "full LM, c = 1", "full LM, c = 0"];
+    let mut successes = [0usize; 3];
+    let mut iterations = [0usize; 3];
+    let starts = 40;
+    for _ in 0..starts {
+        let rates: Vec<f64> = (0..3).map(|_| 10f64.powf(rng.uniform_range(-2.0, 1.5))).collect();
+        let runs = [
+            variable_projection(&model, &rates, &config).fit,
+            levenberg_marquardt(&full, &rates.iter().copied().chain([1.0; 3]).collect::<Vec<_>>(), &config),
+            levenberg_marquardt(&full, &rates.iter().copied().chain([0.0; 3]).collect::<Vec<_>>(), &config),
+        ];
+        for (k, run) in runs.iter().enumerate() {
+            if run.cost < 1.0001 * best {
+                successes[k] += 1;
+                iterations[k] += run.iterations;
+            }
+        }
+    }
+
+    println!("  y = c1 exp(-a1 t) + c2 exp(-a2 t) + c3 exp(-a3 t), {} points, {} random rate guesses", t.len(), starts);
+    println!("  {:<22} {:>10} {:>16}", "method", "converged", "mean iterations");
+    for ((label, successes), iterations) in labels.iter().zip(successes).zip(iterations) {
+        let mean = if successes > 0 { iterations as f64 / successes as f64 } else { f64::NAN };
+        println!("  {:<22} {:>7}/{} {:>16.1}", label, successes, starts, mean);
+    }
+    let names: Vec<String> = model.param_names().into_iter().chain(model.linear_names()).collect();
+    let truth: Vec<f64> = TRIPLE_DECAY_RATES.iter().chain(&TRIPLE_DECAY_AMPLITUDES).copied().collect();
+    println!("  {:<6} {:>12} {:>12}", "param", "true", "fitted");
+    for ((name, truth), fitted) in names.iter().zip(&truth).zip(reference.params()) {
+        println!("  {:<6} {:>12.4} {:>12.4}", name, truth, fitted);
+    }
+    reference
+}
+
+/// Fit the logistic ODE to the yeast data with RK4 and Dormand-Prince, and
+/// check against the closed-form solution y = K / (1 + (K/y0 - 1) e^(-rt)).
+///
+/// Returns the Dormand-Prince fit.
+pub fn problem_ode_logistic() -> NlsResult {
+    let config = LevenbergMarquardtConfig::default();
+    let p0 = [1.0, 500.0, 5.0];
+    println!("  dy/dt = r y (1 - y/K), y(0) = y0, Carlson yeast data, start (r, K, y0) = {:?}", p0);
+    println!("  {:<26} {:>10} {:>10} {:>10} {:>10} {:>6}", "method", "r", "K", "y0", "RSS", "iter");
+
+    let mut fits = Vec::new();
+    for (label, method) in [
+        ("ODE, RK4 h = 0.1", OdeMethod::Rk4 { step: 0.1 }),
+        ("ODE, Dormand-Prince", OdeMethod::Rk45 { rtol: 1e-10, atol: 1e-12 }),
+    ] {
+        let model = logistic_ode_model(method);
+        let result = levenberg_marquardt(&model, &p0, &config);
+        println!(
+            "  {:<26} {:>10.6} {:>10.4} {:>10.4} {:>10.3} {:>6}",
+            label, result.params[0], result.params[1], result.params[2], result.rss(), result.iterations
+        );
+        fits.push((model, result));
+    }
+
+    // Closed form K / (1 + exp(-r (t - t_mid))) with y0 = K / (1 + exp(r t_mid)).
+    let closed = CurveModel::Logistic.fit(&YEAST_HOURS, &YEAST_BIOMASS, &config);
+    let [k, r, t_mid] = [closed.params[0], closed.params[1], closed.params[2]];
+    println!(
+        "  {:<26} {:>10.6} {:>10.4} {:>10.4} {:>10.3} {:>6}",
+        "closed-form logistic",
+        r,
+        k,
+        k / (1.0 + (r * t_mid).exp()),
+        closed.rss(),
+        closed.iterations
+    );
+
+    let (model, result) = fits.pop().unwrap();
+    let u = parameter_uncertainty(&model, &result);
+    for line in u.report(CONFIDENCE_LEVEL) {
+        println!("  {}", line);
+    }
+    result
+}
+
+/// Plot the yeast data with the fitted logistic ODE trajectory.
+#[cfg(feature = "plotting")]
+pub fn problem_ode_logistic_plot(dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let model = logistic_ode_model(OdeMethod::Rk45 { rtol: 1e-10, atol: 1e-12 });
+    let result = levenberg_marquardt(&model, &[1.0, 500.0, 5.0], &LevenbergMarquardtConfig::default());
+    let times: Vec<f64> = (0..=180).map(|i| 0.1 * i as f64).collect();
+    let trajectory = model.trajectory(&result.params, &times);
+    let curve: Vec<(f64, f64)> = times.iter().copied().zip(trajectory.component(0)).collect();
+    let data: Vec<(f64, f64)> = YEAST_HOURS.iter().copied().zip(YEAST_BIOMASS).collect();
+
+    let config = PlotConfig {
+        title: String::from("Yeast Growth, Logistic ODE Fit"),
+        x_label: String::from("t (hours)"),
+        y_label: String::from("biomass"),
+        ..PlotConfig::default()
+    };
+    plot::scatter_plot(&format!("{}/yeast_logistic_ode.png", dir), &[("data", &data)], &[("ODE fit", &curve)], &config)
+}
+
+/// Fit y = A cos(ω t) to noisy samples from starts in A ∈ [0.5, 5],
+/// ω ∈ [0.2, 6], sampled uniformly and by Latin hypercube.
+///
+/// Returns the Latin hypercube run.
+pub fn problem_multi_start() -> MultiStartResult {
+    let mut rng = Rng::new(49);
+    let truth = [2.5, 2.2];
+    let t: Vec<f64> = (0..100).map(|i| 0.1 * i as f64).collect();
+    let y: Vec<f64> = t.iter().map(|&t| truth[0] * (truth[1] * t).cos() + 0.3 * rng.normal()).collect();
+    let model = CurveFit::new(&t, &y, 2, |t, p| p[0] * (p[1] * t).cos())
+        .with_gradient(|t, p| vec![(p[1] * t).cos(), -t * p[0] * (p[1] * t).sin()])
+        .with_names(&["A", "omega"]);
+    println!("  y = A cos(omega t), true (A, omega) = {:?}, noise 0.3", truth);
+
+    // cos is even in ω, so keep ω >= 0 to avoid counting the mirror image.
+    let solver = LevenbergMarquardtConfig::default().with_bounds(&[f64::NEG_INFINITY, 0.0], &[f64::INFINITY; 2]);
+    let base = MultiStartConfig::new(&[0.5, 0.2], &[5.0, 6.0]).with_n_starts(60).with_solver(solver);
+    let mut runs = Vec::new();
+    for (label, sampling) in [("uniform", SamplingMethod::Uniform), ("Latin hypercube", SamplingMethod::LatinHypercube)] {
+        let result = multi_start(&model, &base.clone().with_sampling(sampling));
+        println!(
+            "  {} starts: {} distinct solutions, best reached from {:.0}% of starts",
+            label,
+            result.clusters.len(),
+            100.0 * result.success_rate()
+        );
+        let report = result.report();
+        for line in report.iter().take(4).chain(report.last()) {
+            println!("    {}", line);
+        }
+        runs.push(result);
+    }
+    runs.pop().unwrap()
+}
+
+/// Initial concentrations of the decay runs in [`problem_global_fit`].
+pub const KINETICS_INITIAL: [f64; 4] = [0.5, 1.0, 2.0, 4.0];
+
+/// Fit y = A exp(-k t) + C to four noisy runs with k = 0.25 shared and A, C
+/// per run, and compare the rate from each run fitted separately.
+pub fn problem_global_fit() -> GlobalFitResult {
+    let mut rng = Rng::new(50);
+    let config = LevenbergMarquardtConfig::default();
+    let t: Vec<f64> = (0..15).map(|i| 0.8 * i as f64).collect();
+    let runs: Vec<Vec<f64>> = KINETICS_INITIAL
+        .iter()
+        .map(|&a| t.iter().map(|&t| a * (-0.25 * t).exp() + 0.1 + 0.05 * rng.normal()).collect())
+        .collect();
+
+    let mut global = GlobalFit::new(&[false, true, false]);
+    let mut starts = Vec::new();
+    println!("  y = A exp(-k t) + C, true k = 0.25, {} runs of {} points", runs.len(), t.len());
+    println!("  {:<10} {:>10} {:>10}", "run", "k alone", "se");
+    for (a, y) in KINETICS_INITIAL.iter().zip(&runs) {
+        let label = format!("A0={a}");
+        let model = CurveModel::Exponential.curve_fit(&t, y);
+        let start = CurveModel::Exponential.initial_guess(&t, y);
+        let alone = levenberg_marquardt(&model, &start, &config);
+        let se = parameter_uncertainty(&model, &alone).standard_errors[1];
+        println!("  {:<10} {:>10.5} {:>10.5}", label, alone.params[1], se);
+        global = global.with_dataset(&label, model);
+        starts.push(start);
+    }
+
+    let fit = global_fit(&global, &global.initial_params(&starts), &config);
+    println!("  Global fit ({:?} after {} iterations):", fit.result.stop_reason, fit.result.iterations);
+    for line in fit.report(CONFIDENCE_LEVEL) {
+        println!("    {}", line);
+    }
+    fit
+}
+
+/// Plot both datasets with their Gauss-Newton fits.
+#[cfg(feature = "plotting")]
+pub fn problem_gauss_newton_plots(dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let fits = [
+        ("decay_fit.png", "Exponential Decay (Gauss-Newton)", "t", "y", exponential_decay_model(), vec![1.0, 1.0, 0.0]),
+        (
+            "michaelis_menten_fit.png",
+            "Puromycin, Michaelis-Menten (Gauss-Newton)",
+            "S",
+            "v",
+            michaelis_menten_model(),
+            vec![100.0, 1.0],
+        ),
+    ];
+
+    for (file, title, x_label, y_label, model, p0) in fits {
+        let result = gauss_newton(&model, &p0, &GaussNewtonConfig::default());
+        let data: Vec<(f64, f64)> = model.x.iter().copied().zip(model.y.iter().copied()).collect();
+        let x_max = model.x.iter().fold(0.0_f64, |a, &b| a.max(b));
+        let curve: Vec<(f64, f64)> = (0..=200)
+            .map(|i| {
+                let x = x_max * i as f64 / 200.0;
+                (x, model.predict(x, &result.params))
+            })
+            .collect();
+
+        let config = PlotConfig {
+            title: String::from(title),
+            x_label: String::from(x_label),
+            y_label: String::from(y_label),
+            ..PlotConfig::default()
+        };
+        plot::scatter_plot(&format!("{}/{}", dir, file), &[("data", &data)], &[("fit", &curve)], &config)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,407 @@
+//! Core data structures for nonlinear least squares.
+
+use std::io;
+use std::path::Path;
+
+use ndarray::{Array1, Array2};
+
+use super::jacobian::{finite_difference_jacobian, FiniteDifference};
+use crate::common::csv::write_csv;
+
+/// A nonlinear least-squares problem: minimise ½||r(p)||² over p.
+///
+/// Only the residuals are required. Models that can differentiate
+/// themselves override [`ResidualModel::analytic_jacobian`]; otherwise
+/// [`ResidualModel::jacobian`] falls back to finite differences. Every
+/// solver in this week accepts a `&dyn ResidualModel`.
+pub trait ResidualModel {
+    /// Number of parameters p.
+    fn n_params(&self) -> usize;
+
+    /// Number of residuals r_i.
+    fn n_residuals(&self) -> usize;
+
+    /// Residual vector r(p).
+    fn residuals(&self, p: &[f64]) -> Array1<f64>;
+
+    /// Analytic Jacobian J[i, j] = ∂r_i / ∂p_j, if the model provides one.
+    fn analytic_jacobian(&self, _p: &[f64]) -> Option<Array2<f64>> {
+        None
+    }
+
+    /// Parameter names for reports. Defaults to p0, p1, ...
+    fn param_names(&self) -> Vec<String> {
+        (0..self.n_params()).map(|k| format!("p{k}")).collect()
+    }
+
+    /// Scheme used when there is no analytic Jacobian.
+    fn finite_difference(&self) -> FiniteDifference {
+        FiniteDifference::Central
+    }
+
+    /// Jacobian J[i, j] = ∂r_i / ∂p_j: analytic if available, else by finite differences.
+    fn jacobian(&self, p: &[f64]) -> Array2<f64> {
+        self.analytic_jacobian(p)
+            .unwrap_or_else(|| finite_difference_jacobian(self, p, self.finite_difference()))
+    }
+
+    /// Cost ½||r(p)||².
+    fn cost(&self, p: &[f64]) -> f64 {
+        let r = self.residuals(p);
+        0.5 * r.dot(&r)
+    }
+}
+
+/// A model function m(x; p).
+pub type ModelFn<'a> = Box<dyn Fn(f64, &[f64]) -> f64 + 'a>;
+
+/// Gradient of a model function with respect to its parameters, ∂m/∂p.
+pub type ModelGradientFn<'a> = Box<dyn Fn(f64, &[f64]) -> Vec<f64> + 'a>;
+
+/// Curve-fitting problem y_i ≈ m(x_i; p) with residuals r_i = m(x_i; p) - y_i.
+///
+/// With measurement uncertainties σ_i the residuals are (m(x_i; p) - y_i) / σ_i,
+/// so ½||r||² is half the χ² statistic.
+pub struct CurveFit<'a> {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    /// Standard deviation of each y_i, if known.
+    pub sigma: Option<Vec<f64>>,
+    /// Number of model parameters.
+    pub n_params: usize,
+    model: ModelFn<'a>,
+    gradient: Option<ModelGradientFn<'a>>,
+    names: Option<Vec<String>>,
+    difference: FiniteDifference,
+}
+
+impl<'a> CurveFit<'a> {
+    /// Fit `model` with `n_params` parameters to the data (x, y).
+    ///
+    /// Without [`CurveFit::with_gradient`] the Jacobian is computed by
+    /// central differences.
+    pub fn new(x: &[f64], y: &[f64], n_params: usize, model: impl Fn(f64, &[f64]) -> f64 + 'a) -> Self {
+        assert_eq!(x.len(), y.len(), "x and y must have the same length");
+        Self {
+            x: x.to_vec(),
+            y: y.to_vec(),
+            sigma: None,
+            n_params,
+            model: Box::new(model),
+            gradient: None,
+            names: None,
+            difference: FiniteDifference::Central,
+        }
+    }
+
+    /// Supply the parameter gradient ∂m/∂p for an analytic Jacobian.
+    pub fn with_gradient(mut self, gradient: impl Fn(f64, &[f64]) -> Vec<f64> + 'a) -> Self {
+        self.gradient = Some(Box::new(gradient));
+        self
+    }
+
+    /// Weight each residual by 1 / σ_i.
+    pub fn with_sigma(mut self, sigma: &[f64]) -> Self {
+        assert_eq!(sigma.len(), self.x.len(), "one uncertainty per data point");
+        assert!(sigma.iter().all(|&s| s > 0.0), "uncertainties must be positive");
+        self.sigma = Some(sigma.to_vec());
+        self
+    }
+
+    /// Name the parameters for reports.
+    pub fn with_names(mut self, names: &[&str]) -> Self {
+        assert_eq!(names.len(), self.n_params, "one name per parameter");
+        self.names = Some(names.iter().map(|n| n.to_string()).collect());
+        self
+    }
+
+    /// Choose the finite-difference scheme used when there is no gradient.
+    pub fn with_finite_difference(mut self, scheme: FiniteDifference) -> Self {
+        self.difference = scheme;
+        self
+    }
+
+    /// Evaluate the model m(x; p).
+    pub fn predict(&self, x: f64, p: &[f64]) -> f64 {
+        (self.model)(x, p)
+    }
+
+    fn sigma_at(&self, i: usize) -> f64 {
+        self.sigma.as_ref().map_or(1.0, |s| s[i])
+    }
+}
+
+impl ResidualModel for CurveFit<'_> {
+    fn n_params(&self) -> usize {
+        self.n_params
+    }
+
+    fn n_residuals(&self) -> usize {
+        self.x.len()
+    }
+
+    fn residuals(&self, p: &[f64]) -> Array1<f64> {
+        (0..self.x.len()).map(|i| ((self.model)(self.x[i], p) - self.y[i]) / self.sigma_at(i)).collect()
+    }
+
+    fn analytic_jacobian(&self, p: &[f64]) -> Option<Array2<f64>> {
+        let gradient = self.gradient.as_ref()?;
+        let mut j = Array2::zeros((self.x.len(), self.n_params));
+        for (i, &x) in self.x.iter().enumerate() {
+            for (k, d) in gradient(x, p).into_iter().enumerate() {
+                j[[i, k]] = d / self.sigma_at(i);
+            }
+        }
+        Some(j)
+    }
+
+    fn param_names(&self) -> Vec<String> {
+        match &self.names {
+            Some(names) => names.clone(),
+            None => (0..self.n_params).map(|k| format!("p{k}")).collect(),
+        }
+    }
+
+    fn finite_difference(&self) -> FiniteDifference {
+        self.difference
+    }
+}
+
+/// Configuration for the Gauss-Newton solver.
+#[derive(Debug, Clone)]
+pub struct GaussNewtonConfig {
+    /// Maximum number of iterations.
+    pub max_iter: usize,
+    /// Stop when the max-norm of the gradient Jᵀr falls below this value.
+    pub gradient_tol: f64,
+    /// Stop when ||Δp|| <= step_tol * (||p|| + step_tol).
+    pub step_tol: f64,
+    /// Stop when the relative decrease in cost falls below this value.
+    pub cost_tol: f64,
+    /// Maximum number of times a step may be halved before giving up.
+    pub max_halvings: usize,
+}
+
+impl Default for GaussNewtonConfig {
+    fn default() -> Self {
+        Self {
+            max_iter: 100,
+            gradient_tol: 1e-8,
+            step_tol: 1e-10,
+            cost_tol: 1e-14,
+            max_halvings: 30,
+        }
+    }
+}
+
+impl GaussNewtonConfig {
+    /// Set the maximum number of iterations.
+    pub fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    /// Set the gradient tolerance.
+    pub fn with_gradient_tol(mut self, tol: f64) -> Self {
+        self.gradient_tol = tol;
+        self
+    }
+
+    /// Set the relative step tolerance.
+    pub fn with_step_tol(mut self, tol: f64) -> Self {
+        self.step_tol = tol;
+        self
+    }
+
+    /// Set the relative cost-change tolerance.
+    pub fn with_cost_tol(mut self, tol: f64) -> Self {
+        self.cost_tol = tol;
+        self
+    }
+}
+
+/// Configuration for the Levenberg-Marquardt solver.
+#[derive(Debug, Clone)]
+pub struct LevenbergMarquardtConfig {
+    /// Maximum number of iterations (accepted and rejected steps).
+    pub max_iter: usize,
+    /// Stop when the max-norm of the projected gradient falls below this value.
+    pub gradient_tol: f64,
+    /// Stop when ||Δp|| <= step_tol * (||p|| + step_tol).
+    pub step_tol: f64,
+    /// Stop when an accepted step lowers the cost by less than this fraction.
+    pub cost_tol: f64,
+    /// Initial damping λ; the step solves (JᵀJ + λ diag(JᵀJ)) δ = -Jᵀr.
+    pub initial_lambda: f64,
+    /// Optional lower bounds on the parameters.
+    pub lower: Option<Vec<f64>>,
+    /// Optional upper bounds on the parameters.
+    pub upper: Option<Vec<f64>>,
+}
+
+impl Default for LevenbergMarquardtConfig {
+    fn default() -> Self {
+        Self {
+            max_iter: 200,
+            gradient_tol: 1e-8,
+            step_tol: 1e-10,
+            cost_tol: 1e-14,
+            initial_lambda: 1e-3,
+            lower: None,
+            upper: None,
+        }
+    }
+}
+
+impl LevenbergMarquardtConfig {
+    /// Set the maximum number of iterations.
+    pub fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    /// Set the gradient tolerance.
+    pub fn with_gradient_tol(mut self, tol: f64) -> Self {
+        self.gradient_tol = tol;
+        self
+    }
+
+    /// Set the relative step tolerance.
+    pub fn with_step_tol(mut self, tol: f64) -> Self {
+        self.step_tol = tol;
+        self
+    }
+
+    /// Set the initial (relative) damping.
+    pub fn with_initial_lambda(mut self, lambda: f64) -> Self {
+        self.initial_lambda = lambda;
+        self
+    }
+
+    /// Constrain the parameters to lower <= p <= upper. Use ±∞ for unbounded entries.
+    pub fn with_bounds(mut self, lower: &[f64], upper: &[f64]) -> Self {
+        self.lower = Some(lower.to_vec());
+        self.upper = Some(upper.to_vec());
+        self
+    }
+}
+
+/// Why a nonlinear least-squares solver stopped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopReason {
+    /// The gradient Jᵀr is (numerically) zero.
+    GradientTolerance,
+    /// The relative step became negligible.
+    StepTolerance,
+    /// The relative cost decrease became negligible.
+    CostTolerance,
+    /// No step along the search direction reduced the cost.
+    LineSearchFailed,
+    /// The iteration limit was reached first.
+    MaxIterations,
+}
+
+impl StopReason {
+    /// Whether this reason indicates a successful fit.
+    pub fn is_converged(&self) -> bool {
+        matches!(self, StopReason::GradientTolerance | StopReason::StepTolerance | StopReason::CostTolerance)
+    }
+}
+
+/// One iteration of a nonlinear least-squares solver.
+#[derive(Debug, Clone)]
+pub struct NlsIteration {
+    /// Iteration number (1-based).
+    pub iteration: usize,
+    /// Parameters after the step (unchanged if it was rejected).
+    pub params: Vec<f64>,
+    /// Cost ½||r||² at `params`.
+    pub cost: f64,
+    /// Max-norm of the gradient Jᵀr at `params`.
+    pub gradient_norm: f64,
+    /// ||Δp|| / ||p|| for the step.
+    pub relative_step: f64,
+    /// (cost_old - cost_trial) / cost_old; negative for a rejected step.
+    pub relative_cost_change: f64,
+    /// Fraction α of the full step that was taken.
+    pub step_length: f64,
+    /// Norm of the step ||Δp|| that was tried.
+    pub step_norm: f64,
+    /// Levenberg-Marquardt damping λ used for the step (zero for Gauss-Newton).
+    pub damping: f64,
+    /// Whether the step was accepted; rejected steps leave the parameters unchanged.
+    pub accepted: bool,
+}
+
+/// Result from a nonlinear least-squares solver.
+#[derive(Debug, Clone)]
+pub struct NlsResult {
+    /// Final parameters.
+    pub params: Vec<f64>,
+    /// Residuals r(p) at the final parameters.
+    pub residuals: Array1<f64>,
+    /// Cost ½||r||² at the final parameters.
+    pub cost: f64,
+    /// Max-norm of the gradient Jᵀr at the final parameters, ignoring
+    /// components held at a bound.
+    pub gradient_norm: f64,
+    /// Number of iterations performed.
+    pub iterations: usize,
+    pub stop_reason: StopReason,
+    /// Per-iteration log.
+    pub history: Vec<NlsIteration>,
+}
+
+impl NlsResult {
+    /// Whether the solver stopped on a convergence test.
+    pub fn converged(&self) -> bool {
+        self.stop_reason.is_converged()
+    }
+
+    /// Residual sum of squares ||r||².
+    pub fn rss(&self) -> f64 {
+        2.0 * self.cost
+    }
+
+    /// Write the iteration log to a CSV file.
+    pub fn write_history_csv<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut headers = vec![String::from("iteration")];
+        headers.extend((0..self.params.len()).map(|i| format!("p{i}")));
+        headers.extend(
+            [
+                "cost",
+                "gradient_norm",
+                "step_norm",
+                "relative_step",
+                "relative_cost_change",
+                "step_length",
+                "lambda",
+                "accepted",
+            ]
+            .map(String::from),
+        );
+        let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+
+        let rows: Vec<Vec<f64>> = self
+            .history
+            .iter()
+            .map(|it| {
+                let mut row = vec![it.iteration as f64];
+                row.extend(&it.params);
+                row.extend([
+                    it.cost,
+                    it.gradient_norm,
+                    it.step_norm,
+                    it.relative_step,
+                    it.relative_cost_change,
+                    it.step_length,
+                    it.damping,
+                    if it.accepted { 1.0 } else { 0.0 },
+                ]);
+                row
+            })
+            .collect();
+
+        write_csv(path, &headers, &rows)
+    }
+}
@@ -0,0 +1,338 @@
+//! Parameter uncertainty for nonlinear least-squares fits.
+//!
+//! Linearising the model at the solution p̂ gives the usual asymptotic
+//! covariance Cov(p̂) ≈ s² (JᵀJ)⁻¹ with s² = RSS / (n - p), and Wald
+//! intervals p̂_k ± t_{n-p} · se_k. These are exact only for models linear
+//! in p; when the model is strongly nonlinear in a parameter the true
+//! confidence region is skewed.
+//!
+//! Profile intervals follow the cost instead of its quadratic
+//! approximation. Fixing p_k and re-fitting the other parameters gives the
+//! profile RSS S(p_k) and the profile t statistic
+//! τ(p_k) = sign(p_k - p̂_k) √(S(p_k) - S(p̂)) / s; the interval is where
+//! |τ| <= t_{n-p}. For a linear model τ is a straight line and the two
+//! intervals coincide.
+//!
+//! When the residuals are already divided by known measurement uncertainties
+//! σ_i, the covariance is (JᵀJ)⁻¹ without the s² factor, as for weighted
+//! linear least squares in week05, and 2 × cost is a χ² statistic.
+
+use ndarray::{Array1, Array2};
+
+use super::levenberg_marquardt::levenberg_marquardt;
+use super::types::{LevenbergMarquardtConfig, NlsResult, ResidualModel};
+use crate::week05_linear_least_squares::distributions::{chi_squared_cdf, student_t_quantile};
+use crate::week05_linear_least_squares::statistics::{normal_matrix_pseudo_inverse, Interval};
+
+/// Asymptotic (linearised) uncertainty of fitted parameters.
+#[derive(Debug, Clone)]
+pub struct ParameterUncertainty {
+    pub names: Vec<String>,
+    pub estimates: Vec<f64>,
+    /// Degrees of freedom n - p.
+    pub dof: usize,
+    /// Residual variance s² = RSS / (n - p).
+    pub residual_variance: f64,
+    /// s² (JᵀJ)⁻¹ (pseudo-inverse if J is rank deficient).
+    pub covariance: Array2<f64>,
+    pub standard_errors: Vec<f64>,
+    /// Correlation matrix Cov_ij / (se_i se_j).
+    pub correlation: Array2<f64>,
+    /// Numerical rank of J; less than p means some parameters are not identifiable.
+    pub rank: usize,
+    /// χ² = ||r||² and its upper-tail probability, when σ is known.
+    pub chi_squared: Option<(f64, f64)>,
+}
+
+impl ParameterUncertainty {
+    /// Two-sided Student-t critical value for confidence `level`.
+    pub fn critical_value(&self, level: f64) -> f64 {
+        student_t_quantile(0.5 * (1.0 + level), self.dof as f64)
+    }
+
+    /// Wald intervals p̂_k ± t · se_k.
+    pub fn intervals(&self, level: f64) -> Vec<Interval> {
+        let t = self.critical_value(level);
+        self.estimates
+            .iter()
+            .zip(&self.standard_errors)
+            .map(|(&estimate, &se)| Interval { estimate, lower: estimate - t * se, upper: estimate + t * se })
+            .collect()
+    }
+
+    /// One line per parameter, "name = estimate ± se [lower, upper]".
+    pub fn report(&self, level: f64) -> Vec<String> {
+        self.intervals(level)
+            .iter()
+            .zip(&self.names)
+            .zip(&self.standard_errors)
+            .map(|((interval, name), se)| {
+                format!(
+                    "{name} = {:.6} ± {:.6}  {:.0}% CI [{:.6}, {:.6}]",
+                    interval.estimate,
+                    se,
+                    100.0 * level,
+                    interval.lower,
+                    interval.upper
+                )
+            })
+            .collect()
+    }
+}
+
+/// Linearised parameter uncertainty at the solution in `result`.
+///
+/// The residual variance is estimated from the fit. NaN standard errors when
+/// there are no residual degrees of freedom.
+pub fn parameter_uncertainty(model: &dyn ResidualModel, result: &NlsResult) -> ParameterUncertainty {
+    let dof = model.n_residuals().saturating_sub(model.n_params());
+    let residual_variance = if dof > 0 { result.rss() / dof as f64 } else { f64::NAN };
+    linearised(model, result, dof, residual_variance, None)
+}
+
+/// Linearised parameter uncertainty when the residuals are scaled by known σ_i.
+///
+/// The covariance is (JᵀJ)⁻¹ and `residual_variance` is 1; the reduced χ²
+/// (χ² / (n - p)) should then be close to 1 if the stated σ are right.
+pub fn parameter_uncertainty_known_sigma(model: &dyn ResidualModel, result: &NlsResult) -> ParameterUncertainty {
+    let dof = model.n_residuals().saturating_sub(model.n_params());
+    let chi_squared = result.rss();
+    let p_value = 1.0 - chi_squared_cdf(chi_squared, dof as f64);
+    linearised(model, result, dof, 1.0, Some((chi_squared, p_value)))
+}
+
+fn linearised(
+    model: &dyn ResidualModel,
+    result: &NlsResult,
+    dof: usize,
+    residual_variance: f64,
+    chi_squared: Option<(f64, f64)>,
+) -> ParameterUncertainty {
+    let p = model.n_params();
+    let j = model.jacobian(&result.params);
+    let (inverse, _, rank) = normal_matrix_pseudo_inverse(&j);
+
+    let covariance = inverse * residual_variance;
+    let standard_errors: Vec<f64> = covariance.diag().iter().map(|v| v.sqrt()).collect();
+    let correlation =
+        Array2::from_shape_fn((p, p), |(a, b)| covariance[[a, b]] / (standard_errors[a] * standard_errors[b]));
+
+    ParameterUncertainty {
+        names: model.param_names(),
+        estimates: result.params.clone(),
+        dof,
+        residual_variance,
+        covariance,
+        standard_errors,
+        correlation,
+        rank,
+        chi_squared,
+    }
+}
+
+/// A profile-likelihood confidence interval and the profile trace behind it.
+#[derive(Debug, Clone)]
+pub struct ProfileInterval {
+    /// Index of the profiled parameter.
+    pub index: usize,
+    /// Interval; a side is infinite if |τ| never reached the critical value.
+    pub interval: Interval,
+    /// Profile t statistic (p_k, τ(p_k)) at the points visited, sorted by p_k.
+    pub trace: Vec<(f64, f64)>,
+}
+
+/// Residual model with one parameter held fixed.
+struct FixedParameter<'a> {
+    inner: &'a dyn ResidualModel,
+    index: usize,
+    value: f64,
+}
+
+impl FixedParameter<'_> {
+    fn expand(&self, p: &[f64]) -> Vec<f64> {
+        let mut full = p.to_vec();
+        full.insert(self.index, self.value);
+        full
+    }
+}
+
+impl ResidualModel for FixedParameter<'_> {
+    fn n_params(&self) -> usize {
+        self.inner.n_params() - 1
+    }
+
+    fn n_residuals(&self) -> usize {
+        self.inner.n_residuals()
+    }
+
+    fn residuals(&self, p: &[f64]) -> Array1<f64> {
+        self.inner.residuals(&self.expand(p))
+    }
+
+    fn analytic_jacobian(&self, p: &[f64]) -> Option<Array2<f64>> {
+        let j = self.inner.jacobian(&self.expand(p));
+        let keep: Vec<usize> = (0..j.ncols()).filter(|&k| k != self.index).collect();
+        Some(j.select(ndarray::Axis(1), &keep))
+    }
+}
+
+/// Maximum number of half-standard-error steps taken in each direction.
+const MAX_PROFILE_STEPS: usize = 40;
+
+/// Profile-likelihood interval for parameter `index` at confidence `level`.
+///
+/// Steps p_k away from p̂_k in half-standard-error increments, re-fitting
+/// the other parameters with Levenberg-Marquardt (warm-started from the
+/// previous point), until |τ| exceeds the critical value, then bisects.
+///
+/// τ is scaled by the same s as `uncertainty`, so pass the result of
+/// [`parameter_uncertainty_known_sigma`] for a σ-weighted model to keep the
+/// profile and Wald intervals comparable.
+pub fn profile_interval(
+    model: &dyn ResidualModel,
+    result: &NlsResult,
+    uncertainty: &ParameterUncertainty,
+    index: usize,
+    level: f64,
+    config: &LevenbergMarquardtConfig,
+) -> ProfileInterval {
+    let t = uncertainty.critical_value(level);
+    let s = uncertainty.residual_variance.sqrt();
+    let estimate = result.params[index];
+    let se = uncertainty.standard_errors[index];
+    let step = if se.is_finite() && se > 0.0 { 0.5 * se } else { 0.05 * estimate.abs().max(1.0) };
+    let others = |p: &[f64]| -> Vec<f64> {
+        p.iter().enumerate().filter(|&(k, _)| k != index).map(|(_, &v)| v).collect()
+    };
+
+    // Profile τ at p_k = value, warm-started from `start`; also returns the refit.
+    let tau = |value: f64, start: &[f64]| -> (f64, Vec<f64>) {
+        let fixed = FixedParameter { inner: model, index, value };
+        let fit = levenberg_marquardt(&fixed, start, config);
+        let excess = (fit.rss() - result.rss()).max(0.0);
+        ((value - estimate).signum() * excess.sqrt() / s, fit.params)
+    };
+
+    let mut trace = vec![(estimate, 0.0)];
+    let mut bounds = [f64::NEG_INFINITY, f64::INFINITY];
+    for (side, direction) in [(0, -1.0), (1, 1.0)] {
+        let mut start = others(&result.params);
+        let mut inside = estimate;
+        for i in 1..=MAX_PROFILE_STEPS {
+            let value = estimate + direction * step * i as f64;
+            let (tau_value, refit) = tau(value, &start);
+            trace.push((value, tau_value));
+            if !tau_value.is_finite() {
+                break;
+            }
+            if tau_value.abs() >= t {
+                // Bisect between the last point inside and this one.
+                let (mut a, mut b) = (inside, value);
+                for _ in 0..40 {
+                    let mid = 0.5 * (a + b);
+                    let (tau_mid, refit_mid) = tau(mid, &start);
+                    if tau_mid.abs() < t {
+                        a = mid;
+                        start = refit_mid;
+                    } else {
+                        b = mid;
+                    }
+                }
+                bounds[side] = 0.5 * (a + b);
+                break;
+            }
+            inside = value;
+            start = refit;
+        }
+    }
+
+    trace.sort_by(|a, b| a.0.total_cmp(&b.0));
+    ProfileInterval { index, interval: Interval { estimate, lower: bounds[0], upper: bounds[1] }, trace }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::week06_nonlinear_least_squares::problems::michaelis_menten_model;
+    use crate::week06_nonlinear_least_squares::types::CurveFit;
+
+    fn fit(model: &dyn ResidualModel, p0: &[f64]) -> NlsResult {
+        levenberg_marquardt(model, p0, &LevenbergMarquardtConfig::default())
+    }
+
+    #[test]
+    fn test_puromycin_standard_errors() {
+        // Bates and Watts (1988): se(V_max) = 6.947, se(K_m) = 0.008281, correlation 0.765.
+        let model = michaelis_menten_model();
+        let result = fit(&model, &[200.0, 0.1]);
+        let u = parameter_uncertainty(&model, &result);
+        assert_eq!(u.dof, 10);
+        assert_eq!(u.rank, 2);
+        assert!((u.standard_errors[0] - 6.947).abs() < 1e-3);
+        assert!((u.standard_errors[1] - 0.008281).abs() < 1e-6);
+        assert!((u.correlation[[0, 1]] - 0.765).abs() < 1e-3);
+        assert!((u.correlation[[1, 1]] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_known_sigma_equal_to_s_matches_estimated() {
+        let model = michaelis_menten_model();
+        let result = fit(&model, &[200.0, 0.1]);
+        let estimated = parameter_uncertainty(&model, &result);
+        let s = estimated.residual_variance.sqrt();
+        let weighted = michaelis_menten_model().with_sigma(&[s; 12]);
+        let result = fit(&weighted, &[200.0, 0.1]);
+        let known = parameter_uncertainty_known_sigma(&weighted, &result);
+        let (chi_squared, p_value) = known.chi_squared.unwrap();
+        assert!((chi_squared - 10.0).abs() < 1e-8);
+        assert!(p_value > 0.3 && p_value < 0.6);
+        for (a, b) in known.standard_errors.iter().zip(&estimated.standard_errors) {
+            assert!((a - b).abs() < 1e-8 * b);
+        }
+    }
+
+    #[test]
+    fn test_linear_model_profile_matches_wald() {
+        let x: Vec<f64> = (0..12).map(|i| i as f64).collect();
+        let y: Vec<f64> = x.iter().map(|&x| 1.0 + 0.5 * x + 0.3 * (2.1 * x).sin()).collect();
+        let model = CurveFit::new(&x, &y, 2, |x, p| p[0] + p[1] * x);
+        let result = fit(&model, &[0.0, 0.0]);
+        let u = parameter_uncertainty(&model, &result);
+        for (k, wald) in u.intervals(0.95).iter().enumerate() {
+            let profile = profile_interval(&model, &result, &u, k, 0.95, &LevenbergMarquardtConfig::default());
+            assert!((profile.interval.lower - wald.lower).abs() < 1e-6);
+            assert!((profile.interval.upper - wald.upper).abs() < 1e-6);
+        }
+
+        // With known σ both intervals use s = 1.
+        let weighted = CurveFit::new(&x, &y, 2, |x, p| p[0] + p[1] * x).with_sigma(&[0.1; 12]);
+        let result = fit(&weighted, &[0.0, 0.0]);
+        let u = parameter_uncertainty_known_sigma(&weighted, &result);
+        let wald = u.intervals(0.95);
+        let profile = profile_interval(&weighted, &result, &u, 1, 0.95, &LevenbergMarquardtConfig::default());
+        assert!((profile.interval.lower - wald[1].lower).abs() < 1e-6);
+        assert!((profile.interval.upper - wald[1].upper).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_profile_interval_is_skewed_for_nonlinear_parameter() {
+        let model = michaelis_menten_model();
+        let result = fit(&model, &[200.0, 0.1]);
+        let u = parameter_uncertainty(&model, &result);
+        let profile = profile_interval(&model, &result, &u, 1, 0.95, &LevenbergMarquardtConfig::default());
+        let k = profile.interval;
+        assert!(k.lower > 0.0 && k.upper.is_finite());
+        // Longer on the high side than the symmetric Wald interval.
+        assert!(k.upper - k.estimate > k.estimate - k.lower);
+        assert!(profile.trace.windows(2).all(|w| w[0].0 <= w[1].0 && w[0].1 <= w[1].1));
+    }
+
+    #[test]
+    fn test_report_lines() {
+        let model = michaelis_menten_model().with_names(&["V_max", "K_m"]);
+        let result = fit(&model, &[200.0, 0.1]);
+        let report = parameter_uncertainty(&model, &result).report(0.95);
+        assert!(report[0].starts_with("V_max = 212.68"));
+        assert!(report[1].contains("95% CI"));
+    }
+}